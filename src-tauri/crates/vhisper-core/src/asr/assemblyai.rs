@@ -0,0 +1,351 @@
+//! AssemblyAI 实时语音识别
+//!
+//! 通过 `wss://streaming.assemblyai.com/v3/ws` 发送 PCM 数据，按 `turn_order` 收集各轮次的最终结果；
+//! 开启说话人分离时每轮带有说话人标签
+
+use async_trait::async_trait;
+use futures_util::{SinkExt, StreamExt};
+use http::HeaderValue;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+use super::traits::{
+    chunk_channel, next_with_optional_timeout, recv_audio_chunk, AsrError, AsrResult, AsrService,
+    SpeakerSegment, WordInfo,
+};
+
+const STREAMING_URL: &str = "wss://streaming.assemblyai.com/v3/ws";
+
+/// AssemblyAI 实时 ASR 服务
+pub struct AssemblyAiAsr {
+    api_key: String,
+    /// 是否开启说话人分离
+    enable_diarization: bool,
+    /// 等待服务端消息的超时时间
+    timeout: Duration,
+}
+
+impl AssemblyAiAsr {
+    pub fn new(api_key: String, enable_diarization: bool, timeout: Duration) -> Self {
+        Self {
+            api_key,
+            enable_diarization,
+            timeout,
+        }
+    }
+
+    /// 构建带认证头的 WebSocket 握手请求
+    fn build_request(&self, sample_rate: u32) -> Result<http::Request<()>, AsrError> {
+        let mut url = format!(
+            "{}?sample_rate={}&encoding=pcm_s16le&format_turns=true",
+            STREAMING_URL, sample_rate
+        );
+        if self.enable_diarization {
+            url.push_str("&speaker_labels=true");
+        }
+
+        let mut request = url
+            .as_str()
+            .into_client_request()
+            .map_err(|e| AsrError::Config(format!("无效的 WebSocket 地址 {}: {}", url, e)))?;
+        request.headers_mut().insert(
+            "authorization",
+            HeaderValue::from_str(&self.api_key).map_err(|e| AsrError::Config(e.to_string()))?,
+        );
+        Ok(request)
+    }
+
+    /// 识别完整录音，按每块 100ms 切分后发送
+    async fn recognize_buffer(
+        &self,
+        audio_data: &[u8],
+        sample_rate: u32,
+        partial_tx: Option<&mpsc::Sender<String>>,
+    ) -> Result<AsrResult, AsrError> {
+        // 空音频直接返回，避免建立无意义的连接
+        if audio_data.is_empty() {
+            return Err(AsrError::NoAudio);
+        }
+
+        let chunk_size = (sample_rate as usize) * 2 / 10;
+        let audio_rx = chunk_channel(audio_data, chunk_size);
+        self.run_recognition(audio_rx, sample_rate, partial_tx).await
+    }
+
+    /// 执行一次识别：音频块从 `audio_rx` 到达后立即发送，同时接收识别结果
+    ///
+    /// 中间结果通过 `partial_tx` 推送（不阻塞识别，消费方过慢时丢弃）
+    async fn run_recognition(
+        &self,
+        audio_rx: mpsc::Receiver<Vec<u8>>,
+        sample_rate: u32,
+        partial_tx: Option<&mpsc::Sender<String>>,
+    ) -> Result<AsrResult, AsrError> {
+        let request = self.build_request(sample_rate)?;
+        let (ws_stream, _) = connect_async(request)
+            .await
+            .map_err(|e| AsrError::Network(format!("WebSocket 连接失败: {}", e)))?;
+        let (mut write, mut read) = ws_stream.split();
+
+        // 发送音频的同时收集识别结果，音频发送完毕后通知服务端结束会话
+        let mut audio_rx = Some(audio_rx);
+        let mut sent_bytes = 0usize;
+        let mut turns = TurnCollector::default();
+
+        loop {
+            // 音频发送完毕后才按超时等待服务端消息
+            let timeout = audio_rx.is_none().then_some(self.timeout);
+            let msg = tokio::select! {
+                chunk = recv_audio_chunk(&mut audio_rx) => {
+                    match chunk {
+                        Some(chunk) => {
+                            sent_bytes += chunk.len();
+                            write
+                                .send(Message::Binary(chunk.into()))
+                                .await
+                                .map_err(|e| AsrError::Network(e.to_string()))?;
+                        }
+                        None => {
+                            audio_rx = None;
+                            // 没有发送任何音频，不等待空会话的结果
+                            if sent_bytes == 0 {
+                                return Err(AsrError::NoAudio);
+                            }
+                            write
+                                .send(Message::Text(r#"{"type":"Terminate"}"#.into()))
+                                .await
+                                .map_err(|e| AsrError::Network(e.to_string()))?;
+                        }
+                    }
+                    continue;
+                }
+                msg = next_with_optional_timeout(&mut read, timeout) => msg?,
+            };
+            let Some(msg) = msg else {
+                break;
+            };
+
+            match msg {
+                Ok(Message::Text(text)) => {
+                    let response: StreamingResponse = serde_json::from_str(&text)
+                        .map_err(|e| AsrError::Api(format!("解析响应失败: {}", e)))?;
+
+                    if let Some(error) = response.error {
+                        tracing::error!("AssemblyAI error: {}", error);
+                        return Err(AsrError::Api(error));
+                    }
+
+                    match response.response_type.as_str() {
+                        "Turn" => {
+                            tracing::debug!(
+                                "AssemblyAI turn {}: {} (end={}, speaker={:?})",
+                                response.turn_order,
+                                response.transcript,
+                                response.end_of_turn,
+                                response.speaker_label
+                            );
+                            turns.push(response);
+                            if let Some(tx) = partial_tx {
+                                let _ = tx.try_send(turns.text());
+                            }
+                        }
+                        "Termination" => break,
+                        _ => {}
+                    }
+                }
+                // 认证失败等错误通过关闭帧返回
+                Ok(Message::Close(Some(frame))) if u16::from(frame.code) != 1000 => {
+                    return Err(AsrError::Api(format!(
+                        "连接被关闭: {} {}",
+                        u16::from(frame.code),
+                        frame.reason
+                    )));
+                }
+                Ok(Message::Close(_)) => break,
+                Err(e) => {
+                    return Err(AsrError::Network(e.to_string()));
+                }
+                _ => {}
+            }
+        }
+
+        Ok(turns.finish(self.enable_diarization))
+    }
+}
+
+/// 一轮发言的识别结果
+struct Turn {
+    text: String,
+    speaker: Option<String>,
+    words: Vec<WordInfo>,
+    /// 是否已结束（之后只会被格式化结果替换）
+    finished: bool,
+}
+
+/// 按 `turn_order` 保存每轮最新的识别结果
+///
+/// 同一轮会先后收到未结束、已结束和格式化后的结果，后到的覆盖先到的
+#[derive(Default)]
+struct TurnCollector {
+    turns: BTreeMap<u32, Turn>,
+}
+
+impl TurnCollector {
+    fn push(&mut self, response: StreamingResponse) {
+        // 已结束的轮次不再被迟到的未结束结果覆盖
+        let finished = self
+            .turns
+            .get(&response.turn_order)
+            .is_some_and(|turn| turn.finished);
+        if finished && !response.end_of_turn {
+            return;
+        }
+        let turn = Turn {
+            text: response.transcript.trim().to_string(),
+            speaker: response.speaker_label,
+            words: response.words.iter().map(StreamingWord::to_info).collect(),
+            finished: response.end_of_turn,
+        };
+        self.turns.insert(response.turn_order, turn);
+    }
+
+    /// 目前所有轮次的文本（含未结束的轮次）
+    fn text(&self) -> String {
+        self.turns
+            .values()
+            .map(|turn| turn.text.as_str())
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn finish(self, diarization: bool) -> AsrResult {
+        let text = self.text();
+        let words: Vec<WordInfo> = self
+            .turns
+            .values()
+            .flat_map(|turn| turn.words.iter().cloned())
+            .collect();
+        let confidences: Vec<f32> = words.iter().filter_map(|word| word.confidence).collect();
+        let confidence = (!confidences.is_empty())
+            .then(|| confidences.iter().sum::<f32>() / confidences.len() as f32);
+
+        // 说话人分离：每轮一个片段，未标注说话人的轮次不分段
+        let segments = if diarization {
+            self.turns
+                .into_values()
+                .filter(|turn| !turn.text.is_empty())
+                .filter_map(|turn| {
+                    Some(SpeakerSegment {
+                        speaker: turn.speaker?,
+                        text: turn.text,
+                    })
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        AsrResult {
+            text,
+            is_final: true,
+            confidence,
+            words,
+            segments,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct StreamingResponse {
+    /// Begin / Turn / Termination
+    #[serde(rename = "type", default)]
+    response_type: String,
+    #[serde(default)]
+    turn_order: u32,
+    #[serde(default)]
+    end_of_turn: bool,
+    #[serde(default)]
+    transcript: String,
+    #[serde(default)]
+    words: Vec<StreamingWord>,
+    /// 说话人标签（开启 speaker_labels 时提供）
+    speaker_label: Option<String>,
+    /// 错误消息
+    error: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct StreamingWord {
+    text: String,
+    /// 起止时间（毫秒）
+    start: u64,
+    end: u64,
+    confidence: f32,
+}
+
+impl StreamingWord {
+    fn to_info(&self) -> WordInfo {
+        WordInfo {
+            text: self.text.clone(),
+            confidence: Some(self.confidence),
+            start_ms: self.start,
+            end_ms: self.end,
+        }
+    }
+}
+
+#[async_trait]
+impl AsrService for AssemblyAiAsr {
+    async fn recognize(&self, audio_data: &[u8], sample_rate: u32) -> Result<AsrResult, AsrError> {
+        self.recognize_buffer(audio_data, sample_rate, None).await
+    }
+
+    async fn recognize_streaming(
+        &self,
+        audio_data: &[u8],
+        sample_rate: u32,
+        partial_tx: mpsc::Sender<String>,
+    ) -> Result<AsrResult, AsrError> {
+        self.recognize_buffer(audio_data, sample_rate, Some(&partial_tx)).await
+    }
+
+    fn supports_streaming_input(&self) -> bool {
+        true
+    }
+
+    async fn start_streaming_recognition(
+        &self,
+        audio_rx: mpsc::Receiver<Vec<u8>>,
+        sample_rate: u32,
+        partial_tx: Option<mpsc::Sender<String>>,
+    ) -> Result<AsrResult, AsrError> {
+        self.run_recognition(audio_rx, sample_rate, partial_tx.as_ref()).await
+    }
+}
+
+/// 测试 AssemblyAI API Key
+pub async fn test_api(api_key: &str) -> Result<String, AsrError> {
+    use reqwest::Client;
+
+    let client = Client::new();
+    let response = client
+        .get("https://api.assemblyai.com/v2/transcript?limit=1")
+        .header("Authorization", api_key)
+        .send()
+        .await
+        .map_err(|e| AsrError::Network(e.to_string()))?;
+
+    if response.status().is_success() {
+        Ok("API Key 验证成功".to_string())
+    } else {
+        Err(AsrError::Api(format!(
+            "API Key 无效: HTTP {}",
+            response.status()
+        )))
+    }
+}
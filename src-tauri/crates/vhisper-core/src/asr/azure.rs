@@ -0,0 +1,241 @@
+//! Azure Speech 语音识别
+//!
+//! 使用短音频 REST 接口：以分块传输上传 WAV（PCM）数据，取最终结果的 `DisplayText`
+
+use async_trait::async_trait;
+use reqwest::{Body, Client};
+use serde::Deserialize;
+use std::time::Duration;
+
+use super::traits::{AsrError, AsrResult, AsrService, WordInfo};
+
+/// 每块上传的音频时长（毫秒）
+const CHUNK_MS: usize = 100;
+
+/// Azure 时间单位为 100 纳秒
+const TICKS_PER_MS: u64 = 10_000;
+
+/// Azure Speech ASR 服务
+pub struct AzureAsr {
+    subscription_key: String,
+    /// 服务区域（如 eastasia、westus2）
+    region: String,
+    /// 识别语言（BCP-47，如 zh-CN）
+    language: String,
+    client: Client,
+    /// 等待响应的超时时间
+    timeout: Duration,
+}
+
+impl AzureAsr {
+    pub fn new(
+        subscription_key: String,
+        region: String,
+        language: String,
+        timeout: Duration,
+    ) -> Self {
+        Self {
+            subscription_key,
+            region,
+            language,
+            client: Client::new(),
+            timeout,
+        }
+    }
+
+    fn endpoint(&self) -> String {
+        format!(
+            "https://{}.stt.speech.microsoft.com/speech/recognition/conversation/cognitiveservices/v1",
+            self.region.trim()
+        )
+    }
+}
+
+/// 将 "zh"、"en" 等语言代码转换为 Azure 需要的区域代码，已带区域时原样返回
+pub(super) fn azure_locale(language: &str) -> String {
+    if language.contains('-') {
+        return language.to_string();
+    }
+    match language.to_ascii_lowercase().as_str() {
+        "zh" => "zh-CN",
+        "en" => "en-US",
+        "ja" => "ja-JP",
+        "ko" => "ko-KR",
+        "de" => "de-DE",
+        "fr" => "fr-FR",
+        "es" => "es-ES",
+        _ => language,
+    }
+    .to_string()
+}
+
+/// 16-bit 单声道 PCM 的 WAV 文件头
+fn wav_header(sample_rate: u32, data_len: u32) -> Vec<u8> {
+    let byte_rate = sample_rate * 2;
+    let mut header = Vec::with_capacity(44);
+    header.extend_from_slice(b"RIFF");
+    header.extend_from_slice(&(36 + data_len).to_le_bytes());
+    header.extend_from_slice(b"WAVEfmt ");
+    header.extend_from_slice(&16u32.to_le_bytes());
+    header.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    header.extend_from_slice(&1u16.to_le_bytes()); // 单声道
+    header.extend_from_slice(&sample_rate.to_le_bytes());
+    header.extend_from_slice(&byte_rate.to_le_bytes());
+    header.extend_from_slice(&2u16.to_le_bytes()); // 每帧字节数
+    header.extend_from_slice(&16u16.to_le_bytes()); // 位深
+    header.extend_from_slice(b"data");
+    header.extend_from_slice(&data_len.to_le_bytes());
+    header
+}
+
+/// 请求错误转换为 ASR 错误，超时单独提示
+fn request_error(e: reqwest::Error) -> AsrError {
+    if e.is_timeout() {
+        AsrError::Network("识别超时".to_string())
+    } else {
+        AsrError::Network(e.to_string())
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct AzureResponse {
+    /// Success / NoMatch / InitialSilenceTimeout / BabbleTimeout / Error
+    recognition_status: String,
+    #[serde(default)]
+    display_text: String,
+    /// detailed 格式下的候选结果，按置信度从高到低排列
+    #[serde(default, rename = "NBest")]
+    n_best: Vec<AzureCandidate>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct AzureCandidate {
+    confidence: f32,
+    #[serde(default)]
+    words: Vec<AzureWord>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct AzureWord {
+    word: String,
+    /// 起始时间和时长（100 纳秒）
+    offset: u64,
+    duration: u64,
+    confidence: Option<f32>,
+}
+
+impl AzureWord {
+    fn to_info(&self) -> WordInfo {
+        WordInfo {
+            text: self.word.clone(),
+            confidence: self.confidence,
+            start_ms: self.offset / TICKS_PER_MS,
+            end_ms: (self.offset + self.duration) / TICKS_PER_MS,
+        }
+    }
+}
+
+#[async_trait]
+impl AsrService for AzureAsr {
+    async fn recognize(&self, audio_data: &[u8], sample_rate: u32) -> Result<AsrResult, AsrError> {
+        // 空音频直接返回，避免建立无意义的连接
+        if audio_data.is_empty() {
+            return Err(AsrError::NoAudio);
+        }
+
+        // 按 2 字节对齐分块，WAV 头作为第一块发送
+        let chunk_size = (sample_rate as usize * 2 * CHUNK_MS / 1000) & !1;
+        let header = wav_header(sample_rate, audio_data.len() as u32);
+        let chunks: Vec<Vec<u8>> = std::iter::once(header)
+            .chain(audio_data.chunks(chunk_size.max(2)).map(<[u8]>::to_vec))
+            .collect();
+        let body = Body::wrap_stream(futures_util::stream::iter(
+            chunks.into_iter().map(Ok::<_, std::io::Error>),
+        ));
+
+        let response = self
+            .client
+            .post(self.endpoint())
+            .query(&[
+                ("language", self.language.as_str()),
+                ("format", "detailed"),
+                ("wordLevelTimestamps", "true"),
+            ])
+            .header("Ocp-Apim-Subscription-Key", &self.subscription_key)
+            .header(
+                "Content-Type",
+                format!("audio/wav; codecs=audio/pcm; samplerate={}", sample_rate),
+            )
+            .header("Accept", "application/json")
+            .body(body)
+            .timeout(self.timeout)
+            .send()
+            .await
+            .map_err(request_error)?;
+
+        let status = response.status();
+        let body = response.text().await.map_err(request_error)?;
+
+        if !status.is_success() {
+            return Err(AsrError::Api(format!("HTTP {}: {}", status, body)));
+        }
+
+        let result: AzureResponse = serde_json::from_str(&body)
+            .map_err(|e| AsrError::Api(format!("解析响应失败: {}", e)))?;
+        tracing::debug!("Azure recognition status: {}", result.recognition_status);
+
+        match result.recognition_status.as_str() {
+            "Success" => {}
+            // 没有识别到语音，按空结果处理
+            "NoMatch" | "InitialSilenceTimeout" | "BabbleTimeout" => {
+                return Ok(AsrResult {
+                    text: String::new(),
+                    is_final: true,
+                    confidence: None,
+                    words: Vec::new(),
+                    segments: Vec::new(),
+                });
+            }
+            status => return Err(AsrError::Api(format!("识别失败: {}", status))),
+        }
+
+        let best = result.n_best.first();
+        Ok(AsrResult {
+            text: result.display_text,
+            is_final: true,
+            confidence: best.map(|c| c.confidence),
+            words: best
+                .map(|c| c.words.iter().map(AzureWord::to_info).collect())
+                .unwrap_or_default(),
+            segments: Vec::new(),
+        })
+    }
+}
+
+/// 测试 Azure Speech 订阅密钥（通过令牌接口验证，不消耗识别额度）
+pub async fn test_api(subscription_key: &str, region: &str) -> Result<String, AsrError> {
+    let url = format!(
+        "https://{}.api.cognitive.microsoft.com/sts/v1.0/issueToken",
+        region.trim()
+    );
+    let response = Client::new()
+        .post(url)
+        .header("Ocp-Apim-Subscription-Key", subscription_key)
+        .header("Content-Length", "0")
+        .timeout(Duration::from_secs(10))
+        .send()
+        .await
+        .map_err(request_error)?;
+
+    if response.status().is_success() {
+        Ok("订阅密钥验证成功".to_string())
+    } else {
+        Err(AsrError::Api(format!(
+            "订阅密钥或区域无效: HTTP {}",
+            response.status()
+        )))
+    }
+}
@@ -1,20 +1,87 @@
 use async_trait::async_trait;
 use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::sync::mpsc;
 use tokio_tungstenite::{connect_async, tungstenite::Message};
 use uuid::Uuid;
 
-use super::traits::{AsrError, AsrResult, AsrService};
+use super::pool::{self, WsStream};
+use super::traits::{
+    chunk_channel, next_with_optional_timeout, next_with_timeout, recv_audio_chunk, AsrError,
+    AsrResult, AsrService, WordInfo,
+};
+
+/// DashScope 识别参数（标点、语气词过滤、ITN、热词表）
+#[derive(Debug, Clone)]
+pub struct DashScopeAsrOptions {
+    pub punctuation: bool,
+    pub semantic_punctuation: bool,
+    pub disfluency_removal: bool,
+    pub itn: bool,
+    /// 预先创建的热词表 ID
+    pub vocabulary_id: Option<String>,
+}
 
 /// DashScope ASR 服务 (WebSocket 实时语音识别)
 pub struct DashScopeAsr {
     api_key: String,
     model: String,
+    /// 语言提示，None 时自动检测
+    language_hints: Option<Vec<String>>,
+    /// 识别完成后将连接放回连接池
+    keep_alive: bool,
+    options: DashScopeAsrOptions,
+    /// 等待服务端消息的超时时间
+    timeout: Duration,
 }
 
 impl DashScopeAsr {
-    pub fn new(api_key: String, model: String) -> Self {
-        Self { api_key, model }
+    pub fn new(
+        api_key: String,
+        model: String,
+        language_hints: Option<Vec<String>>,
+        keep_alive: bool,
+        options: DashScopeAsrOptions,
+        timeout: Duration,
+    ) -> Self {
+        Self {
+            api_key,
+            model,
+            language_hints,
+            keep_alive,
+            options,
+            timeout,
+        }
+    }
+
+    /// 连接池中的 key（同一 API Key 的连接可在不同任务间复用）
+    fn pool_key(&self) -> String {
+        format!("dashscope:{}", self.api_key)
+    }
+
+    /// 建立新的 WebSocket 连接
+    async fn connect(&self) -> Result<WsStream, AsrError> {
+        let url = "wss://dashscope.aliyuncs.com/api-ws/v1/inference";
+
+        // 创建带认证头的请求
+        let request = http::Request::builder()
+            .uri(url)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .header("Sec-WebSocket-Key", tokio_tungstenite::tungstenite::handshake::client::generate_key())
+            .header("Sec-WebSocket-Version", "13")
+            .header("Host", "dashscope.aliyuncs.com")
+            .header("Connection", "Upgrade")
+            .header("Upgrade", "websocket")
+            .body(())
+            .map_err(|e| AsrError::Network(e.to_string()))?;
+
+        // 连接 WebSocket
+        let (ws_stream, _) = connect_async(request)
+            .await
+            .map_err(|e| AsrError::Network(format!("WebSocket 连接失败: {}", e)))?;
+
+        Ok(ws_stream)
     }
 }
 
@@ -53,6 +120,12 @@ struct WsParameters {
     sample_rate: u32,
     #[serde(skip_serializing_if = "Option::is_none")]
     language_hints: Option<Vec<String>>,
+    punctuation_prediction_enabled: bool,
+    semantic_punctuation_enabled: bool,
+    disfluency_removal_enabled: bool,
+    inverse_text_normalization_enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    vocabulary_id: Option<String>,
 }
 
 // WebSocket 响应结构
@@ -88,32 +161,134 @@ struct WsSentence {
     text: Option<String>,
     #[serde(default)]
     sentence_end: bool,
+    #[serde(default)]
+    words: Vec<WsWord>,
 }
 
-#[async_trait]
-impl AsrService for DashScopeAsr {
-    async fn recognize(&self, audio_data: &[u8], sample_rate: u32) -> Result<AsrResult, AsrError> {
-        let task_id = Uuid::new_v4().to_string().replace("-", "");
+/// 词级时间戳（DashScope 不提供词级置信度）
+#[derive(Deserialize, Debug)]
+struct WsWord {
+    text: String,
+    /// 起止时间（毫秒）
+    begin_time: u64,
+    end_time: u64,
+}
 
-        // 构建 WebSocket URL 和请求
-        let url = "wss://dashscope.aliyuncs.com/api-ws/v1/inference";
+impl WsSentence {
+    fn word_infos(&self) -> Vec<WordInfo> {
+        self.words
+            .iter()
+            .map(|word| WordInfo {
+                text: word.text.clone(),
+                confidence: None,
+                start_ms: word.begin_time,
+                end_ms: word.end_time,
+            })
+            .collect()
+    }
+}
 
-        // 创建带认证头的请求
-        let request = http::Request::builder()
-            .uri(url)
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .header("Sec-WebSocket-Key", tokio_tungstenite::tungstenite::handshake::client::generate_key())
-            .header("Sec-WebSocket-Version", "13")
-            .header("Host", "dashscope.aliyuncs.com")
-            .header("Connection", "Upgrade")
-            .header("Upgrade", "websocket")
-            .body(())
-            .map_err(|e| AsrError::Network(e.to_string()))?;
+/// 识别结果收集器：句子结束的结果优先，没有非空的结束结果时使用最后一个非空中间结果
+///
+/// 很短的语音可能收到空的 sentence_end 结果，不能用它覆盖已有的中间结果
+#[derive(Default)]
+struct SentenceCollector {
+    text: String,
+    words: Vec<WordInfo>,
+    /// 当前结果是否来自 sentence_end
+    sentence_ended: bool,
+}
 
-        // 连接 WebSocket
-        let (ws_stream, _) = connect_async(request)
-            .await
-            .map_err(|e| AsrError::Network(format!("WebSocket 连接失败: {}", e)))?;
+impl SentenceCollector {
+    fn push(&mut self, sentence: &WsSentence) {
+        let Some(text) = sentence.text.as_deref().filter(|text| !text.is_empty()) else {
+            return;
+        };
+        // 已有句子结束的结果时，后续中间结果不再覆盖
+        if sentence.sentence_end || !self.sentence_ended {
+            self.text = text.to_string();
+            self.words = sentence.word_infos();
+            self.sentence_ended = sentence.sentence_end;
+        }
+    }
+}
+
+/// 检查 task-failed 事件或带错误码的响应，出错时立即返回
+///
+/// task-failed 事件的错误详情通常在 header 中，缺失时附上原始消息便于排查
+fn check_task_failed(response: &WsResponse, raw: &str) -> Result<(), AsrError> {
+    let failed = response.header.event == "task-failed";
+    if !failed && response.header.error_code.is_none() {
+        return Ok(());
+    }
+
+    let message = match (&response.header.error_code, &response.header.error_message) {
+        (Some(code), Some(message)) => format!("{}: {}", code, message),
+        (Some(code), None) => code.clone(),
+        (None, Some(message)) => message.clone(),
+        (None, None) => format!("task-failed: {}", raw),
+    };
+    tracing::error!("DashScope task failed: {}", message);
+    Err(AsrError::Api(message))
+}
+
+/// finish-task 指令（通知服务端音频已发送完毕）
+fn finish_task_json(task_id: &str) -> Result<String, AsrError> {
+    let finish_task = WsRequest {
+        header: WsHeader {
+            action: "finish-task".to_string(),
+            task_id: task_id.to_string(),
+            streaming: "duplex".to_string(),
+        },
+        payload: WsPayload {
+            task_group: None,
+            task: None,
+            function: None,
+            model: None,
+            parameters: None,
+            input: serde_json::json!({}),
+        },
+    };
+
+    serde_json::to_string(&finish_task).map_err(|e| AsrError::Encoding(e.to_string()))
+}
+
+impl DashScopeAsr {
+    /// 识别完整录音，按每块 100ms 切分后发送
+    async fn recognize_buffer(
+        &self,
+        audio_data: &[u8],
+        sample_rate: u32,
+        partial_tx: Option<&mpsc::Sender<String>>,
+    ) -> Result<AsrResult, AsrError> {
+        // 空音频直接返回，避免建立无意义的连接
+        if audio_data.is_empty() {
+            return Err(AsrError::NoAudio);
+        }
+
+        // 每块约 3200 字节，对应 100ms @ 16kHz 16bit
+        let chunk_size = (sample_rate as usize) * 2 / 10;
+        let audio_rx = chunk_channel(audio_data, chunk_size);
+        self.run_recognition(audio_rx, sample_rate, partial_tx).await
+    }
+
+    /// 执行一次识别：音频块从 `audio_rx` 到达后立即发送，同时接收识别结果
+    ///
+    /// 中间结果通过 `partial_tx` 推送（不阻塞识别，消费方过慢时丢弃）
+    async fn run_recognition(
+        &self,
+        audio_rx: mpsc::Receiver<Vec<u8>>,
+        sample_rate: u32,
+        partial_tx: Option<&mpsc::Sender<String>>,
+    ) -> Result<AsrResult, AsrError> {
+        let task_id = Uuid::new_v4().to_string().replace("-", "");
+
+        // 优先复用连接池中的空闲或预先建立的连接（每个任务仍独立 run-task / finish-task）
+        let pooled = pool::take(&self.pool_key()).await;
+        let ws_stream = match pooled {
+            Some(stream) => stream,
+            None => self.connect().await?,
+        };
 
         let (mut write, mut read) = ws_stream.split();
 
@@ -132,7 +307,12 @@ impl AsrService for DashScopeAsr {
                 parameters: Some(WsParameters {
                     format: "pcm".to_string(),
                     sample_rate,
-                    language_hints: Some(vec!["zh".to_string(), "en".to_string()]),
+                    language_hints: self.language_hints.clone(),
+                    punctuation_prediction_enabled: self.options.punctuation,
+                    semantic_punctuation_enabled: self.options.semantic_punctuation,
+                    disfluency_removal_enabled: self.options.disfluency_removal,
+                    inverse_text_normalization_enabled: self.options.itn,
+                    vocabulary_id: self.options.vocabulary_id.clone(),
                 }),
                 input: serde_json::json!({}),
             },
@@ -148,19 +328,13 @@ impl AsrService for DashScopeAsr {
 
         // 等待 task-started 事件
         let mut task_started = false;
-        while let Some(msg) = read.next().await {
+        while let Some(msg) = next_with_timeout(&mut read, self.timeout).await? {
             match msg {
                 Ok(Message::Text(text)) => {
                     let response: WsResponse = serde_json::from_str(&text)
                         .map_err(|e| AsrError::Api(format!("解析响应失败: {}", e)))?;
 
-                    if let Some(error_code) = &response.header.error_code {
-                        return Err(AsrError::Api(format!(
-                            "{}: {}",
-                            error_code,
-                            response.header.error_message.unwrap_or_default()
-                        )));
-                    }
+                    check_task_failed(&response, &text)?;
 
                     if response.header.event == "task-started" {
                         task_started = true;
@@ -181,56 +355,51 @@ impl AsrService for DashScopeAsr {
             return Err(AsrError::Api("未收到 task-started 事件".to_string()));
         }
 
-        // 分块发送音频数据（每块约 3200 字节，对应 100ms @ 16kHz 16bit）
-        let chunk_size = (sample_rate as usize) * 2 / 10; // 100ms 的数据量
-        for chunk in audio_data.chunks(chunk_size) {
-            write
-                .send(Message::Binary(chunk.to_vec().into()))
-                .await
-                .map_err(|e| AsrError::Network(e.to_string()))?;
-        }
-
-        // 发送 finish-task 指令
-        let finish_task = WsRequest {
-            header: WsHeader {
-                action: "finish-task".to_string(),
-                task_id: task_id.clone(),
-                streaming: "duplex".to_string(),
-            },
-            payload: WsPayload {
-                task_group: None,
-                task: None,
-                function: None,
-                model: None,
-                parameters: None,
-                input: serde_json::json!({}),
-            },
-        };
-
-        let finish_task_json = serde_json::to_string(&finish_task)
-            .map_err(|e| AsrError::Encoding(e.to_string()))?;
-
-        write
-            .send(Message::Text(finish_task_json.into()))
-            .await
-            .map_err(|e| AsrError::Network(e.to_string()))?;
-
-        // 收集识别结果
-        let mut final_text = String::new();
+        // 发送音频的同时收集识别结果，音频发送完毕后发送 finish-task 指令
+        let mut audio_rx = Some(audio_rx);
+        let mut sent_bytes = 0usize;
+        let mut collector = SentenceCollector::default();
+        let mut task_finished = false;
+
+        loop {
+            // 音频发送完毕后才按超时等待服务端消息
+            let timeout = audio_rx.is_none().then_some(self.timeout);
+            let msg = tokio::select! {
+                chunk = recv_audio_chunk(&mut audio_rx) => {
+                    match chunk {
+                        Some(chunk) => {
+                            sent_bytes += chunk.len();
+                            write
+                                .send(Message::Binary(chunk.into()))
+                                .await
+                                .map_err(|e| AsrError::Network(e.to_string()))?;
+                        }
+                        None => {
+                            audio_rx = None;
+                            // 没有发送任何音频，不提交空任务
+                            if sent_bytes == 0 {
+                                return Err(AsrError::NoAudio);
+                            }
+                            write
+                                .send(Message::Text(finish_task_json(&task_id)?.into()))
+                                .await
+                                .map_err(|e| AsrError::Network(e.to_string()))?;
+                        }
+                    }
+                    continue;
+                }
+                msg = next_with_optional_timeout(&mut read, timeout) => msg?,
+            };
+            let Some(msg) = msg else {
+                break;
+            };
 
-        while let Some(msg) = read.next().await {
             match msg {
                 Ok(Message::Text(text)) => {
                     let response: WsResponse = serde_json::from_str(&text)
                         .map_err(|e| AsrError::Api(format!("解析响应失败: {}", e)))?;
 
-                    if let Some(error_code) = &response.header.error_code {
-                        return Err(AsrError::Api(format!(
-                            "{}: {}",
-                            error_code,
-                            response.header.error_message.unwrap_or_default()
-                        )));
-                    }
+                    check_task_failed(&response, &text)?;
 
                     match response.header.event.as_str() {
                         "result-generated" => {
@@ -239,19 +408,17 @@ impl AsrService for DashScopeAsr {
                                     if let Some(sentence) = output.sentence {
                                         if let Some(text) = &sentence.text {
                                             tracing::debug!("ASR partial: {} (end={})", text, sentence.sentence_end);
-                                            // 收集所有结果，不只是 sentence_end
-                                            if sentence.sentence_end {
-                                                final_text = text.clone();
-                                            } else if final_text.is_empty() {
-                                                // 如果还没有最终结果，先保存中间结果
-                                                final_text = text.clone();
+                                            if let Some(tx) = partial_tx {
+                                                let _ = tx.try_send(text.clone());
                                             }
                                         }
+                                        collector.push(&sentence);
                                     }
                                 }
                             }
                         }
                         "task-finished" => {
+                            task_finished = true;
                             break;
                         }
                         _ => {}
@@ -267,13 +434,65 @@ impl AsrService for DashScopeAsr {
             }
         }
 
+        // 任务正常结束时连接仍可用，放回连接池
+        if self.keep_alive && task_finished {
+            if let Ok(stream) = read.reunite(write) {
+                pool::put(&self.pool_key(), stream);
+            }
+        }
+
         Ok(AsrResult {
-            text: final_text,
+            text: collector.text,
             is_final: true,
+            confidence: None,
+            words: collector.words,
+            segments: Vec::new(),
         })
     }
 }
 
+#[async_trait]
+impl AsrService for DashScopeAsr {
+    async fn recognize(&self, audio_data: &[u8], sample_rate: u32) -> Result<AsrResult, AsrError> {
+        self.recognize_buffer(audio_data, sample_rate, None).await
+    }
+
+    async fn recognize_streaming(
+        &self,
+        audio_data: &[u8],
+        sample_rate: u32,
+        partial_tx: mpsc::Sender<String>,
+    ) -> Result<AsrResult, AsrError> {
+        self.recognize_buffer(audio_data, sample_rate, Some(&partial_tx)).await
+    }
+
+    fn supported_sample_rates(&self) -> &'static [u32] {
+        // paraformer-realtime 系列支持 16k，8k 模型支持 8k
+        &[16000, 8000]
+    }
+
+    async fn preconnect(&self) -> Result<(), AsrError> {
+        pool::preconnect(&self.pool_key(), self.timeout, self.connect()).await
+    }
+
+    fn discard_preconnected(&self) {
+        pool::remove(&self.pool_key());
+    }
+
+    fn supports_streaming_input(&self) -> bool {
+        true
+    }
+
+    async fn start_streaming_recognition(
+        &self,
+        audio_rx: mpsc::Receiver<Vec<u8>>,
+        sample_rate: u32,
+        partial_tx: Option<mpsc::Sender<String>>,
+    ) -> Result<AsrResult, AsrError> {
+        self.run_recognition(audio_rx, sample_rate, partial_tx.as_ref()).await
+    }
+}
+
 /// 测试 DashScope API 连接
 pub async fn test_api(api_key: &str) -> Result<String, AsrError> {
     use reqwest::Client;
@@ -295,3 +514,86 @@ pub async fn test_api(api_key: &str) -> Result<String, AsrError> {
         )))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result_generated(text: &str, sentence_end: bool) -> String {
+        serde_json::json!({
+            "header": { "task_id": "t", "event": "result-generated" },
+            "payload": { "output": { "sentence": { "text": text, "sentence_end": sentence_end } } }
+        })
+        .to_string()
+    }
+
+    /// 按接收顺序将 result-generated 消息交给收集器
+    fn collect(messages: &[String]) -> SentenceCollector {
+        let mut collector = SentenceCollector::default();
+        for message in messages {
+            let response: WsResponse = serde_json::from_str(message).unwrap();
+            if let Some(sentence) = response.payload.and_then(|p| p.output).and_then(|o| o.sentence)
+            {
+                collector.push(&sentence);
+            }
+        }
+        collector
+    }
+
+    #[test]
+    fn empty_sentence_end_keeps_last_partial() {
+        let collector = collect(&[
+            result_generated("好", false),
+            result_generated("好的", false),
+            result_generated("", true),
+        ]);
+        assert_eq!(collector.text, "好的");
+    }
+
+    #[test]
+    fn sentence_end_replaces_partial() {
+        let collector = collect(&[
+            result_generated("你好世", false),
+            result_generated("你好世界。", true),
+        ]);
+        assert_eq!(collector.text, "你好世界。");
+    }
+
+    #[test]
+    fn partial_does_not_replace_sentence_end() {
+        let collector = collect(&[
+            result_generated("你好。", true),
+            result_generated("再", false),
+            result_generated("", true),
+        ]);
+        assert_eq!(collector.text, "你好。");
+    }
+
+    #[test]
+    fn task_failed_frame_returns_api_error() {
+        let raw = serde_json::json!({
+            "header": {
+                "task_id": "t",
+                "event": "task-failed",
+                "error_code": "InvalidApiKey",
+                "error_message": "Invalid API-key provided."
+            },
+            "payload": {}
+        })
+        .to_string();
+        let response: WsResponse = serde_json::from_str(&raw).unwrap();
+        match check_task_failed(&response, &raw) {
+            Err(AsrError::Api(message)) => {
+                assert_eq!(message, "InvalidApiKey: Invalid API-key provided.")
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn result_frame_is_not_failure() {
+        let raw = result_generated("你好", false);
+        let response: WsResponse = serde_json::from_str(&raw).unwrap();
+        assert!(check_task_failed(&response, &raw).is_ok());
+    }
+}
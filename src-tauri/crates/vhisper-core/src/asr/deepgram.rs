@@ -0,0 +1,271 @@
+//! Deepgram 流式语音识别
+//!
+//! 通过 `wss://api.deepgram.com/v1/listen` 发送 PCM 数据，拼接所有 `is_final` 结果
+
+use async_trait::async_trait;
+use futures_util::{SinkExt, StreamExt};
+use http::HeaderValue;
+use serde::Deserialize;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+use super::traits::{next_with_timeout, AsrError, AsrResult, AsrService, WordInfo};
+
+const LISTEN_URL: &str = "wss://api.deepgram.com/v1/listen";
+
+/// Deepgram 流式 ASR 服务
+pub struct DeepgramAsr {
+    api_key: String,
+    model: String,
+    /// 识别语言，None 时自动检测
+    language: Option<String>,
+    /// 等待服务端消息的超时时间
+    timeout: Duration,
+}
+
+impl DeepgramAsr {
+    pub fn new(
+        api_key: String,
+        model: String,
+        language: Option<String>,
+        timeout: Duration,
+    ) -> Self {
+        Self {
+            api_key,
+            model,
+            language,
+            timeout,
+        }
+    }
+
+    /// 构建带认证头的 WebSocket 握手请求
+    fn build_request(&self, sample_rate: u32) -> Result<http::Request<()>, AsrError> {
+        let language = match &self.language {
+            Some(language) => format!("language={}", language),
+            None => "detect_language=true".to_string(),
+        };
+        let url = format!(
+            "{}?model={}&{}&encoding=linear16&sample_rate={}&channels=1&punctuate=true&smart_format=true&interim_results=true",
+            LISTEN_URL, self.model, language, sample_rate
+        );
+
+        let mut request = url
+            .as_str()
+            .into_client_request()
+            .map_err(|e| AsrError::Config(format!("无效的 WebSocket 地址 {}: {}", url, e)))?;
+        request.headers_mut().insert(
+            "authorization",
+            HeaderValue::from_str(&format!("Token {}", self.api_key))
+                .map_err(|e| AsrError::Config(e.to_string()))?,
+        );
+        Ok(request)
+    }
+
+    /// 执行一次识别，中间结果通过 `partial_tx` 推送（不阻塞识别，消费方过慢时丢弃）
+    async fn run_recognition(
+        &self,
+        audio_data: &[u8],
+        sample_rate: u32,
+        partial_tx: Option<&mpsc::Sender<String>>,
+    ) -> Result<AsrResult, AsrError> {
+        // 空音频直接返回，避免建立无意义的连接
+        if audio_data.is_empty() {
+            return Err(AsrError::NoAudio);
+        }
+
+        let request = self.build_request(sample_rate)?;
+        let (ws_stream, _) = connect_async(request)
+            .await
+            .map_err(|e| AsrError::Network(format!("WebSocket 连接失败: {}", e)))?;
+        let (mut write, mut read) = ws_stream.split();
+
+        // 分块发送音频数据（每块 100ms）
+        let chunk_size = (sample_rate as usize) * 2 / 10;
+        for chunk in audio_data.chunks(chunk_size) {
+            write
+                .send(Message::Binary(chunk.to_vec().into()))
+                .await
+                .map_err(|e| AsrError::Network(e.to_string()))?;
+        }
+
+        // 通知服务端音频结束，处理完剩余音频后服务端关闭连接
+        write
+            .send(Message::Text(r#"{"type":"CloseStream"}"#.into()))
+            .await
+            .map_err(|e| AsrError::Network(e.to_string()))?;
+
+        let mut final_text = String::new();
+        let mut confidences = Vec::new();
+        let mut words = Vec::new();
+
+        while let Some(msg) = next_with_timeout(&mut read, self.timeout).await? {
+            match msg {
+                Ok(Message::Text(text)) => {
+                    let response: DeepgramResponse = serde_json::from_str(&text)
+                        .map_err(|e| AsrError::Api(format!("解析响应失败: {}", e)))?;
+
+                    match response.response_type.as_str() {
+                        "Results" => {
+                            let Some(alternative) = response
+                                .channel
+                                .and_then(|c| c.alternatives.into_iter().next())
+                            else {
+                                continue;
+                            };
+                            let transcript = alternative.transcript.trim();
+                            if transcript.is_empty() {
+                                continue;
+                            }
+                            tracing::debug!(
+                                "Deepgram result: {} (final={})",
+                                transcript,
+                                response.is_final
+                            );
+
+                            if response.is_final {
+                                append_segment(&mut final_text, transcript);
+                                confidences.push(alternative.confidence);
+                                words.extend(alternative.words.iter().map(DeepgramWord::to_info));
+                                if let Some(tx) = partial_tx {
+                                    let _ = tx.try_send(final_text.clone());
+                                }
+                            } else if let Some(tx) = partial_tx {
+                                let mut partial = final_text.clone();
+                                append_segment(&mut partial, transcript);
+                                let _ = tx.try_send(partial);
+                            }
+                        }
+                        "Error" => {
+                            let message = response
+                                .description
+                                .or(response.err_msg)
+                                .unwrap_or_else(|| text.to_string());
+                            tracing::error!("Deepgram error: {}", message);
+                            return Err(AsrError::Api(message));
+                        }
+                        _ => {}
+                    }
+                }
+                Ok(Message::Close(_)) => break,
+                Err(e) => {
+                    return Err(AsrError::Network(e.to_string()));
+                }
+                _ => {}
+            }
+        }
+
+        let confidence = (!confidences.is_empty())
+            .then(|| confidences.iter().sum::<f32>() / confidences.len() as f32);
+
+        Ok(AsrResult {
+            text: final_text,
+            is_final: true,
+            confidence,
+            words,
+            segments: Vec::new(),
+        })
+    }
+}
+
+/// 拼接识别片段，英文等以空格分词的语言之间补一个空格
+fn append_segment(text: &mut String, segment: &str) {
+    let needs_space = text
+        .chars()
+        .last()
+        .zip(segment.chars().next())
+        .is_some_and(|(prev, next)| prev.is_ascii() && next.is_ascii());
+    if needs_space {
+        text.push(' ');
+    }
+    text.push_str(segment);
+}
+
+#[derive(Deserialize, Debug)]
+struct DeepgramResponse {
+    #[serde(rename = "type", default)]
+    response_type: String,
+    #[serde(default)]
+    is_final: bool,
+    channel: Option<DeepgramChannel>,
+    /// 错误消息（type 为 Error 时）
+    description: Option<String>,
+    err_msg: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct DeepgramChannel {
+    #[serde(default)]
+    alternatives: Vec<DeepgramAlternative>,
+}
+
+#[derive(Deserialize, Debug)]
+struct DeepgramAlternative {
+    #[serde(default)]
+    transcript: String,
+    #[serde(default)]
+    confidence: f32,
+    #[serde(default)]
+    words: Vec<DeepgramWord>,
+}
+
+#[derive(Deserialize, Debug)]
+struct DeepgramWord {
+    word: String,
+    /// 带标点和大小写的词（开启 punctuate 时提供）
+    punctuated_word: Option<String>,
+    /// 起止时间（秒）
+    start: f64,
+    end: f64,
+    confidence: f32,
+}
+
+impl DeepgramWord {
+    fn to_info(&self) -> WordInfo {
+        WordInfo {
+            text: self.punctuated_word.clone().unwrap_or_else(|| self.word.clone()),
+            confidence: Some(self.confidence),
+            start_ms: (self.start * 1000.0) as u64,
+            end_ms: (self.end * 1000.0) as u64,
+        }
+    }
+}
+
+#[async_trait]
+impl AsrService for DeepgramAsr {
+    async fn recognize(&self, audio_data: &[u8], sample_rate: u32) -> Result<AsrResult, AsrError> {
+        self.run_recognition(audio_data, sample_rate, None).await
+    }
+
+    async fn recognize_streaming(
+        &self,
+        audio_data: &[u8],
+        sample_rate: u32,
+        partial_tx: mpsc::Sender<String>,
+    ) -> Result<AsrResult, AsrError> {
+        self.run_recognition(audio_data, sample_rate, Some(&partial_tx)).await
+    }
+}
+
+/// 测试 Deepgram API Key
+pub async fn test_api(api_key: &str) -> Result<String, AsrError> {
+    use reqwest::Client;
+
+    let client = Client::new();
+    let response = client
+        .get("https://api.deepgram.com/v1/projects")
+        .header("Authorization", format!("Token {}", api_key))
+        .send()
+        .await
+        .map_err(|e| AsrError::Network(e.to_string()))?;
+
+    if response.status().is_success() {
+        Ok("API Key 验证成功".to_string())
+    } else {
+        Err(AsrError::Api(format!(
+            "API Key 无效: HTTP {}",
+            response.status()
+        )))
+    }
+}
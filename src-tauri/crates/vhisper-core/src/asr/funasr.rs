@@ -1,17 +1,35 @@
 use async_trait::async_trait;
 use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
+use std::io::Cursor;
+use std::time::Duration;
 use tokio_tungstenite::tungstenite::Message;
 
-use super::traits::{AsrError, AsrResult, AsrService};
+use super::traits::{next_with_timeout, AsrError, AsrResult, AsrService};
+use crate::config::settings::{FunAsrAudioFormat, FunAsrResultStrategy};
 
 /// FunASR 本地服务 (WebSocket 实时语音识别)
 pub struct FunAsr {
     endpoint: String,
+    result_strategy: FunAsrResultStrategy,
+    audio_format: FunAsrAudioFormat,
+    /// 每块音频时长（毫秒），0 表示整段发送
+    chunk_ms: u32,
+    /// 热词
+    hotwords: Vec<String>,
+    /// 等待服务端消息的超时时间
+    timeout: Duration,
 }
 
 impl FunAsr {
-    pub fn new(endpoint: String) -> Self {
+    pub fn new(
+        endpoint: String,
+        result_strategy: FunAsrResultStrategy,
+        audio_format: FunAsrAudioFormat,
+        chunk_ms: u32,
+        hotwords: Vec<String>,
+        timeout: Duration,
+    ) -> Self {
         // 将 HTTP 端点转换为 WebSocket Secure 端点 (FunASR 默认启用 SSL)
         let ws_endpoint = endpoint
             .replace("http://", "wss://")
@@ -19,8 +37,71 @@ impl FunAsr {
             .replace("ws://", "wss://");
         Self {
             endpoint: ws_endpoint,
+            result_strategy,
+            audio_format,
+            chunk_ms,
+            hotwords,
+            timeout,
         }
     }
+
+    /// 检查音频格式与分块方式是否兼容
+    fn validate(&self) -> Result<(), AsrError> {
+        // WAV 文件头只在第一块中，服务端需要一次拿到完整文件才能解码
+        if self.audio_format == FunAsrAudioFormat::Wav && self.chunk_ms != 0 {
+            return Err(AsrError::Config(
+                "FunASR WAV 格式需要整段发送音频，请将 chunk_ms 设为 0".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// 按配置的格式编码音频数据（输入为 16-bit PCM）
+    fn encode_audio(&self, pcm: &[u8], sample_rate: u32) -> Result<Vec<u8>, AsrError> {
+        match self.audio_format {
+            FunAsrAudioFormat::Pcm => Ok(pcm.to_vec()),
+            FunAsrAudioFormat::Wav => pcm_to_wav(pcm, sample_rate),
+        }
+    }
+}
+
+/// 为 16-bit 单声道 PCM 数据加上 WAV 文件头
+fn pcm_to_wav(pcm: &[u8], sample_rate: u32) -> Result<Vec<u8>, AsrError> {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+
+    let mut cursor = Cursor::new(Vec::new());
+    let mut writer =
+        hound::WavWriter::new(&mut cursor, spec).map_err(|e| AsrError::Encoding(e.to_string()))?;
+    for b in pcm.chunks_exact(2) {
+        writer
+            .write_sample(i16::from_le_bytes([b[0], b[1]]))
+            .map_err(|e| AsrError::Encoding(e.to_string()))?;
+    }
+    writer
+        .finalize()
+        .map_err(|e| AsrError::Encoding(e.to_string()))?;
+
+    Ok(cursor.into_inner())
+}
+
+/// 热词权重（FunASR 取值 1 - 100，越大越倾向识别为热词）
+const HOTWORD_WEIGHT: u32 = 20;
+
+/// 将热词编码为 FunASR 的 `hotwords` 参数，没有热词时返回 None
+fn hotwords_json(hotwords: &[String]) -> Option<String> {
+    if hotwords.is_empty() {
+        return None;
+    }
+    let weights: serde_json::Map<String, serde_json::Value> = hotwords
+        .iter()
+        .map(|word| (word.clone(), HOTWORD_WEIGHT.into()))
+        .collect();
+    Some(serde_json::Value::Object(weights).to_string())
 }
 
 /// 创建接受自签名证书的 TLS 连接器
@@ -43,6 +124,9 @@ struct FunAsrStartMessage {
     audio_fs: u32,
     itn: bool,
     is_speaking: bool,
+    /// 热词及权重的 JSON 字符串（如 `{"阿里巴巴": 20}`）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hotwords: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -59,9 +143,72 @@ struct FunAsrResponse {
     mode: Option<String>,
 }
 
+/// 2pass 结果收集器：分别保存在线和离线结果，按策略选择最终文本
+struct ResultCollector {
+    strategy: FunAsrResultStrategy,
+    online_text: String,
+    offline_text: Option<String>,
+}
+
+impl ResultCollector {
+    fn new(strategy: FunAsrResultStrategy) -> Self {
+        Self {
+            strategy,
+            online_text: String::new(),
+            offline_text: None,
+        }
+    }
+
+    /// 处理一条响应，返回是否可以结束识别
+    fn push(&mut self, response: FunAsrResponse) -> bool {
+        // mode: online / offline / 2pass-online / 2pass-offline
+        let is_offline = response.mode.as_deref().is_some_and(|m| m.ends_with("offline"));
+
+        if let Some(text) = response.text {
+            // FunASR 返回的是累积结果，各自取最后一个
+            if is_offline {
+                self.offline_text = Some(text);
+            } else {
+                self.online_text = text;
+            }
+        }
+
+        // 离线结果到达或服务端声明结束
+        is_offline || response.is_final
+    }
+
+    fn has_result(&self) -> bool {
+        !self.online_text.is_empty() || self.offline_text.is_some()
+    }
+
+    /// 按策略选择最终文本，首选结果缺失时回退到另一种
+    fn finish(self) -> String {
+        match self.strategy {
+            FunAsrResultStrategy::PreferOffline => match self.offline_text {
+                Some(text) if !text.is_empty() => text,
+                _ => self.online_text,
+            },
+            FunAsrResultStrategy::PreferOnline => {
+                if self.online_text.is_empty() {
+                    self.offline_text.unwrap_or_default()
+                } else {
+                    self.online_text
+                }
+            }
+        }
+    }
+}
+
 #[async_trait]
 impl AsrService for FunAsr {
     async fn recognize(&self, audio_data: &[u8], sample_rate: u32) -> Result<AsrResult, AsrError> {
+        // 空音频直接返回，避免建立无意义的连接
+        if audio_data.is_empty() {
+            return Err(AsrError::NoAudio);
+        }
+        self.validate()?;
+        let audio_data = self.encode_audio(audio_data, sample_rate)?;
+
         // 创建 TLS 连接器（接受自签名证书）
         let connector = create_tls_connector()?;
 
@@ -82,10 +229,11 @@ impl AsrService for FunAsr {
             chunk_size: vec![5, 10, 5],
             chunk_interval: 10,
             wav_name: "audio".to_string(),
-            wav_format: "pcm".to_string(),
+            wav_format: self.audio_format.wav_format().to_string(),
             audio_fs: sample_rate,
             itn: true,
             is_speaking: true,
+            hotwords: hotwords_json(&self.hotwords),
         };
 
         let start_json = serde_json::to_string(&start_msg)
@@ -96,8 +244,13 @@ impl AsrService for FunAsr {
             .await
             .map_err(|e| AsrError::Network(e.to_string()))?;
 
-        // 分块发送音频数据（每块约 6400 字节，对应 200ms @ 16kHz 16bit）
-        let chunk_size = (sample_rate as usize) * 2 / 5; // 200ms 的数据量
+        // 分块发送音频数据（默认 200ms 一块，约 6400 字节 @ 16kHz 16bit），chunk_ms 为 0 时整段发送
+        let chunk_size = if self.chunk_ms == 0 {
+            audio_data.len()
+        } else {
+            // 按 2 字节对齐，避免采样被拆开
+            ((sample_rate as usize * 2 * self.chunk_ms as usize / 1000) & !1).max(2)
+        };
         for chunk in audio_data.chunks(chunk_size) {
             write
                 .send(Message::Binary(chunk.to_vec().into()))
@@ -116,18 +269,13 @@ impl AsrService for FunAsr {
             .map_err(|e| AsrError::Network(e.to_string()))?;
 
         // 收集识别结果
-        let mut final_text = String::new();
+        let mut collector = ResultCollector::new(self.result_strategy);
 
-        while let Some(msg) = read.next().await {
+        while let Some(msg) = next_with_timeout(&mut read, self.timeout).await? {
             match msg {
                 Ok(Message::Text(text)) => {
                     if let Ok(response) = serde_json::from_str::<FunAsrResponse>(&text) {
-                        if let Some(result_text) = response.text {
-                            // FunASR 返回的是累积结果，取最后一个
-                            final_text = result_text;
-                        }
-                        // 如果是最终结果或者模式是 offline，则结束
-                        if response.is_final || response.mode.as_deref() == Some("offline") {
+                        if collector.push(response) {
                             break;
                         }
                     }
@@ -137,7 +285,7 @@ impl AsrService for FunAsr {
                 }
                 Err(e) => {
                     // 如果已经有结果，忽略关闭错误
-                    if final_text.is_empty() {
+                    if !collector.has_result() {
                         return Err(AsrError::Network(e.to_string()));
                     }
                     break;
@@ -147,10 +295,17 @@ impl AsrService for FunAsr {
         }
 
         Ok(AsrResult {
-            text: final_text,
+            text: collector.finish(),
             is_final: true,
+            confidence: None,
+            words: Vec::new(),
+            segments: Vec::new(),
         })
     }
+
+    fn supports_hotwords(&self) -> bool {
+        true
+    }
 }
 
 /// 测试 FunASR 服务连接
@@ -182,3 +337,70 @@ pub async fn test_api(endpoint: &str) -> Result<String, AsrError> {
         Err(_) => Err(AsrError::Network("连接超时".to_string())),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(mode: &str, text: &str) -> FunAsrResponse {
+        FunAsrResponse {
+            text: Some(text.to_string()),
+            is_final: false,
+            mode: Some(mode.to_string()),
+        }
+    }
+
+    fn collect(strategy: FunAsrResultStrategy, responses: Vec<FunAsrResponse>) -> String {
+        let mut collector = ResultCollector::new(strategy);
+        for response in responses {
+            collector.push(response);
+        }
+        collector.finish()
+    }
+
+    #[test]
+    fn prefer_offline_uses_offline_result() {
+        let text = collect(
+            FunAsrResultStrategy::PreferOffline,
+            vec![
+                response("2pass-online", "今天天气"),
+                response("2pass-offline", "今天天气很好。"),
+            ],
+        );
+        assert_eq!(text, "今天天气很好。");
+    }
+
+    #[test]
+    fn prefer_online_uses_online_result() {
+        let text = collect(
+            FunAsrResultStrategy::PreferOnline,
+            vec![
+                response("2pass-online", "今天天气"),
+                response("2pass-offline", "今天天气很好。"),
+            ],
+        );
+        assert_eq!(text, "今天天气");
+    }
+
+    #[test]
+    fn missing_result_falls_back_to_other_mode() {
+        let online_only = collect(
+            FunAsrResultStrategy::PreferOffline,
+            vec![response("2pass-online", "你好")],
+        );
+        assert_eq!(online_only, "你好");
+
+        let offline_only = collect(
+            FunAsrResultStrategy::PreferOnline,
+            vec![response("2pass-offline", "你好。")],
+        );
+        assert_eq!(offline_only, "你好。");
+    }
+
+    #[test]
+    fn offline_result_finishes_recognition() {
+        let mut collector = ResultCollector::new(FunAsrResultStrategy::PreferOffline);
+        assert!(!collector.push(response("2pass-online", "你好")));
+        assert!(collector.push(response("2pass-offline", "你好。")));
+    }
+}
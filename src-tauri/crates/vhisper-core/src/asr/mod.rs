@@ -1,32 +1,112 @@
+mod assemblyai;
+mod azure;
 mod dashscope;
+mod deepgram;
 mod funasr;
+mod openai_realtime;
 mod openai_whisper;
+mod pool;
 mod qwen;
-mod qwen_realtime;
+#[cfg(feature = "sherpa")]
+mod sherpa;
 mod traits;
 
-pub use dashscope::DashScopeAsr;
+pub use assemblyai::AssemblyAiAsr;
+pub use azure::AzureAsr;
+pub use dashscope::{DashScopeAsr, DashScopeAsrOptions};
+pub use deepgram::DeepgramAsr;
 pub use funasr::FunAsr;
+pub use openai_realtime::{OpenAiRealtimeAsr, RealtimeEndpoint};
 pub use openai_whisper::OpenAiWhisper;
 pub use qwen::QwenAsr;
-pub use qwen_realtime::QwenRealtimeAsr;
-pub use traits::{AsrError, AsrResult, AsrService, StreamingAsrEvent, StreamingAsrService, StreamingControl};
+#[cfg(feature = "sherpa")]
+pub use sherpa::SherpaAsr;
+pub use traits::{
+    select_sample_rate, AsrError, AsrResult, AsrService, SpeakerSegment, StreamingAsrEvent,
+    StreamingAsrService, StreamingControl, WordInfo,
+};
 
-use crate::config::settings::AsrConfig;
+use std::time::Duration;
+
+use crate::config::provider::{asr_provider_names, resolve_asr_provider};
+use crate::config::settings::{AsrConfig, OPENAI_BASE_URL};
+
+/// 解析配置中的服务商名称（忽略大小写，支持别名）
+fn resolve_provider(name: &str) -> Result<&'static str, AsrError> {
+    resolve_asr_provider(name).ok_or_else(|| {
+        AsrError::Config(format!(
+            "未知的 ASR 服务商: {}（可用: {}）",
+            name,
+            asr_provider_names()
+        ))
+    })
+}
+
+/// 识别语言提示：目标应用的语言设置优先，为空或包含 "auto" 时返回 None（自动检测）
+fn language_hints(config: &AsrConfig, languages: &[String]) -> Option<Vec<String>> {
+    let languages = match &config.language {
+        Some(language) => std::slice::from_ref(language),
+        None => languages,
+    };
+    if languages.is_empty() || languages.iter().any(|l| l.eq_ignore_ascii_case("auto")) {
+        return None;
+    }
+    Some(languages.to_vec())
+}
+
+/// 只支持单一语言的服务商取第一项
+fn primary_language(config: &AsrConfig, languages: &[String]) -> Option<String> {
+    language_hints(config, languages).and_then(|hints| hints.into_iter().next())
+}
+
+/// 识别超时时间
+fn asr_timeout(config: &AsrConfig) -> Duration {
+    Duration::from_secs(config.timeout_secs.max(1))
+}
+
+/// 通义千问实时识别，整段识别和流式识别共用
+fn qwen_asr(config: &AsrConfig) -> Result<QwenAsr, AsrError> {
+    let qwen_config = config
+        .qwen
+        .as_ref()
+        .ok_or_else(|| AsrError::Config("通义千问 ASR 配置缺失".to_string()))?;
+    Ok(QwenAsr::new(
+        qwen_config.api_key.clone(),
+        qwen_config.model.clone(),
+        primary_language(config, &qwen_config.languages),
+        config.hotwords.clone(),
+        qwen_config.keep_alive,
+        asr_timeout(config),
+    ))
+}
+
+/// OpenAI Realtime 兼容服务，整段识别和流式识别共用
+fn openai_realtime_asr(config: &AsrConfig) -> Result<OpenAiRealtimeAsr, AsrError> {
+    let realtime_config = config
+        .openai_realtime
+        .as_ref()
+        .ok_or_else(|| AsrError::Config("OpenAI Realtime 配置缺失".to_string()))?;
+    Ok(OpenAiRealtimeAsr::new(
+        RealtimeEndpoint {
+            base_url: realtime_config.base_url.clone(),
+            auth_header: realtime_config.auth_header.clone(),
+            beta_header: realtime_config.beta_header.clone(),
+            dialect: realtime_config.dialect,
+        },
+        realtime_config.api_key.clone(),
+        realtime_config.model.clone(),
+        primary_language(config, &realtime_config.languages),
+        config.hotwords.clone(),
+        false,
+        asr_timeout(config),
+    ))
+}
 
 /// 根据配置创建 ASR 服务
 pub fn create_asr_service(config: &AsrConfig) -> Result<Box<dyn AsrService>, AsrError> {
-    match config.provider.as_str() {
-        "Qwen" => {
-            let qwen_config = config
-                .qwen
-                .as_ref()
-                .ok_or_else(|| AsrError::Config("通义千问 ASR 配置缺失".to_string()))?;
-            Ok(Box::new(QwenAsr::new(
-                qwen_config.api_key.clone(),
-                qwen_config.model.clone(),
-            )))
-        }
+    let timeout = asr_timeout(config);
+    match resolve_provider(&config.provider)? {
+        "Qwen" => Ok(Box::new(qwen_asr(config)?)),
         "DashScope" => {
             let dashscope_config = config
                 .dashscope
@@ -35,6 +115,16 @@ pub fn create_asr_service(config: &AsrConfig) -> Result<Box<dyn AsrService>, Asr
             Ok(Box::new(DashScopeAsr::new(
                 dashscope_config.api_key.clone(),
                 dashscope_config.model.clone(),
+                language_hints(config, &dashscope_config.languages),
+                dashscope_config.keep_alive,
+                DashScopeAsrOptions {
+                    punctuation: dashscope_config.enable_punctuation,
+                    semantic_punctuation: dashscope_config.enable_semantic_punctuation,
+                    disfluency_removal: dashscope_config.enable_disfluency_removal,
+                    itn: dashscope_config.enable_itn,
+                    vocabulary_id: dashscope_config.vocabulary_id.clone(),
+                },
+                timeout,
             )))
         }
         "OpenAIWhisper" => {
@@ -43,22 +133,88 @@ pub fn create_asr_service(config: &AsrConfig) -> Result<Box<dyn AsrService>, Asr
                 .as_ref()
                 .ok_or_else(|| AsrError::Config("OpenAI 配置缺失".to_string()))?;
             Ok(Box::new(OpenAiWhisper::new(
+                openai_config
+                    .base_url
+                    .clone()
+                    .unwrap_or_else(|| OPENAI_BASE_URL.to_string()),
                 openai_config.api_key.clone(),
                 openai_config.model.clone(),
-                openai_config.language.clone(),
+                primary_language(config, &openai_config.languages),
+                timeout,
+            )))
+        }
+        "Deepgram" => {
+            let deepgram_config = config
+                .deepgram
+                .as_ref()
+                .ok_or_else(|| AsrError::Config("Deepgram 配置缺失".to_string()))?;
+            Ok(Box::new(DeepgramAsr::new(
+                deepgram_config.api_key.clone(),
+                deepgram_config.model.clone(),
+                primary_language(config, &deepgram_config.languages),
+                timeout,
             )))
         }
+        "Azure" => {
+            let azure_config = config
+                .azure
+                .as_ref()
+                .ok_or_else(|| AsrError::Config("Azure Speech 配置缺失".to_string()))?;
+            // Azure 不支持自动检测语言，未指定时使用中文
+            let language = primary_language(config, std::slice::from_ref(&azure_config.language))
+                .map(|language| azure::azure_locale(&language))
+                .unwrap_or_else(|| "zh-CN".to_string());
+            Ok(Box::new(AzureAsr::new(
+                azure_config.subscription_key.clone(),
+                azure_config.region.clone(),
+                language,
+                timeout,
+            )))
+        }
+        "AssemblyAI" => {
+            let assemblyai_config = config
+                .assemblyai
+                .as_ref()
+                .ok_or_else(|| AsrError::Config("AssemblyAI 配置缺失".to_string()))?;
+            Ok(Box::new(AssemblyAiAsr::new(
+                assemblyai_config.api_key.clone(),
+                assemblyai_config.enable_diarization,
+                timeout,
+            )))
+        }
+        "OpenAIRealtime" => Ok(Box::new(openai_realtime_asr(config)?)),
         "FunAsr" => {
             let funasr_config = config
                 .funasr
                 .as_ref()
                 .ok_or_else(|| AsrError::Config("FunASR 配置缺失".to_string()))?;
-            Ok(Box::new(FunAsr::new(funasr_config.endpoint.clone())))
+            Ok(Box::new(FunAsr::new(
+                funasr_config.endpoint.clone(),
+                funasr_config.result_strategy,
+                funasr_config.audio_format,
+                funasr_config.chunk_ms,
+                config.hotwords.clone(),
+                timeout,
+            )))
         }
-        _ => Err(AsrError::Config(format!(
-            "未知的 ASR 服务商: {}",
-            config.provider
-        ))),
+        #[cfg(feature = "sherpa")]
+        "Sherpa" => {
+            let sherpa_config = config
+                .sherpa
+                .as_ref()
+                .ok_or_else(|| AsrError::Config("Sherpa 配置缺失".to_string()))?;
+            // 相对路径的模型目录放在数据目录下，便于便携安装
+            let model_dir = crate::config::resolve_data_path(&sherpa_config.model_dir);
+            Ok(Box::new(SherpaAsr::new(
+                model_dir.to_string_lossy().into_owned(),
+                sherpa_config.num_threads,
+            )?))
+        }
+        #[cfg(not(feature = "sherpa"))]
+        "Sherpa" => Err(AsrError::Config(
+            "当前版本未启用 Sherpa 本地识别（需使用 sherpa 功能编译）".to_string(),
+        )),
+        provider => Err(AsrError::Config(format!("未知的 ASR 服务商: {}", provider))),
     }
 }
 
@@ -72,9 +228,24 @@ pub async fn test_dashscope_api(api_key: &str) -> Result<String, AsrError> {
     dashscope::test_api(api_key).await
 }
 
-/// 测试 OpenAI API
-pub async fn test_openai_api(api_key: &str) -> Result<String, AsrError> {
-    openai_whisper::test_api(api_key).await
+/// 测试 OpenAI API，`base_url` 为 None 时使用官方地址
+pub async fn test_openai_api(api_key: &str, base_url: Option<&str>) -> Result<String, AsrError> {
+    openai_whisper::test_api(base_url.unwrap_or(OPENAI_BASE_URL), api_key).await
+}
+
+/// 测试 Deepgram API
+pub async fn test_deepgram_api(api_key: &str) -> Result<String, AsrError> {
+    deepgram::test_api(api_key).await
+}
+
+/// 测试 Azure Speech 订阅密钥
+pub async fn test_azure_api(subscription_key: &str, region: &str) -> Result<String, AsrError> {
+    azure::test_api(subscription_key, region).await
+}
+
+/// 测试 AssemblyAI API
+pub async fn test_assemblyai_api(api_key: &str) -> Result<String, AsrError> {
+    assemblyai::test_api(api_key).await
 }
 
 /// 测试 FunASR API
@@ -86,20 +257,12 @@ pub async fn test_funasr_api(endpoint: &str) -> Result<String, AsrError> {
 pub fn create_streaming_asr_service(
     config: &AsrConfig,
 ) -> Result<Box<dyn StreamingAsrService>, AsrError> {
-    match config.provider.as_str() {
-        "Qwen" => {
-            let qwen_config = config
-                .qwen
-                .as_ref()
-                .ok_or_else(|| AsrError::Config("通义千问 ASR 配置缺失".to_string()))?;
-            Ok(Box::new(QwenRealtimeAsr::new(
-                qwen_config.api_key.clone(),
-                qwen_config.model.clone(),
-            )))
-        }
-        _ => Err(AsrError::Config(format!(
+    match resolve_provider(&config.provider)? {
+        "Qwen" => Ok(Box::new(qwen_asr(config)?)),
+        "OpenAIRealtime" => Ok(Box::new(openai_realtime_asr(config)?)),
+        provider => Err(AsrError::Config(format!(
             "ASR 服务商 {} 不支持流式识别",
-            config.provider
+            provider
         ))),
     }
 }
@@ -0,0 +1,901 @@
+//! OpenAI Realtime 兼容的 ASR 服务
+//!
+//! 基于 WebSocket 的实时语音识别，支持整段识别、边录边传和 VAD 连续识别。
+//! 通过 URL、认证头和协议方言参数化，可对接通义千问、OpenAI、Azure 及本地兼容服务
+
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use futures_util::{SinkExt, StreamExt};
+use http::{HeaderName, HeaderValue};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+use uuid::Uuid;
+
+use crate::audio::{encode_to_pcm, resample};
+use crate::config::settings::RealtimeDialect;
+
+/// WebSocket 连接超时时间
+const WS_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Session 确认超时时间
+const SESSION_CONFIRM_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 16-bit PCM 每个采样的字节数
+const BYTES_PER_SAMPLE: usize = 2;
+
+use super::pool::{self, WsStream};
+use super::traits::{
+    chunk_channel, next_with_optional_timeout, next_with_timeout, recv_audio_chunk,
+    select_sample_rate, AsrError, AsrResult, AsrService, StreamingAsrEvent, StreamingAsrService,
+    StreamingControl,
+};
+
+/// 生成事件 ID（按字符截取，不依赖字节下标）
+fn generate_event_id() -> String {
+    let id: String = Uuid::new_v4().simple().to_string().chars().take(20).collect();
+    format!("event_{}", id)
+}
+
+/// 服务端地址、认证方式和协议方言
+pub struct RealtimeEndpoint {
+    /// WebSocket 地址（如 wss://api.openai.com/v1/realtime）
+    pub base_url: String,
+    /// 认证头名称，Authorization 时使用 Bearer 格式，其他（如 Azure 的 api-key）直接传密钥
+    pub auth_header: String,
+    /// OpenAI-Beta 头的值，为 None 时不发送
+    pub beta_header: Option<String>,
+    pub dialect: RealtimeDialect,
+}
+
+/// OpenAI Realtime 兼容的 ASR 服务
+pub struct OpenAiRealtimeAsr {
+    endpoint: RealtimeEndpoint,
+    api_key: String,
+    model: String,
+    /// 识别语言，None 时自动检测
+    language: Option<String>,
+    /// 热词，作为上下文提示传给模型
+    hotwords: Vec<String>,
+    /// 整段识别完成后将连接放回连接池
+    keep_alive: bool,
+    /// 整段识别时等待服务端消息的超时时间
+    timeout: Duration,
+}
+
+impl OpenAiRealtimeAsr {
+    pub fn new(
+        endpoint: RealtimeEndpoint,
+        api_key: String,
+        model: String,
+        language: Option<String>,
+        hotwords: Vec<String>,
+        keep_alive: bool,
+        timeout: Duration,
+    ) -> Self {
+        Self {
+            endpoint,
+            api_key,
+            model,
+            language,
+            hotwords,
+            keep_alive,
+            timeout,
+        }
+    }
+
+    /// 连接池中的 key（地址和模型不同的连接不能混用）
+    fn pool_key(&self) -> String {
+        format!(
+            "realtime:{}:{}:{}",
+            self.endpoint.base_url, self.model, self.api_key
+        )
+    }
+
+    /// 建立新的 WebSocket 连接
+    async fn connect(&self) -> Result<WsStream, AsrError> {
+        let request = self.build_request()?;
+        let (ws_stream, _) = timeout(WS_CONNECT_TIMEOUT, connect_async(request))
+            .await
+            .map_err(|_| AsrError::Network("WebSocket 连接超时".to_string()))?
+            .map_err(|e| AsrError::Network(format!("WebSocket 连接失败: {}", e)))?;
+        Ok(ws_stream)
+    }
+
+    /// 构建带认证头的 WebSocket 握手请求
+    fn build_request(&self) -> Result<http::Request<()>, AsrError> {
+        let base_url = &self.endpoint.base_url;
+        let separator = if base_url.contains('?') { '&' } else { '?' };
+        let query = match self.endpoint.dialect {
+            // 转写会话的模型在会话配置中指定
+            RealtimeDialect::OpenAi => "intent=transcription".to_string(),
+            RealtimeDialect::DashScope => format!("model={}", self.model),
+        };
+        let url = format!("{}{}{}", base_url, separator, query);
+
+        let mut request = url
+            .as_str()
+            .into_client_request()
+            .map_err(|e| AsrError::Config(format!("无效的 WebSocket 地址 {}: {}", url, e)))?;
+
+        let auth_header = &self.endpoint.auth_header;
+        let auth_value = if auth_header.eq_ignore_ascii_case("authorization") {
+            format!("Bearer {}", self.api_key)
+        } else {
+            self.api_key.clone()
+        };
+        let auth_name = HeaderName::from_bytes(auth_header.as_bytes())
+            .map_err(|e| AsrError::Config(format!("无效的认证头 {}: {}", auth_header, e)))?;
+        let headers = request.headers_mut();
+        headers.insert(
+            auth_name,
+            HeaderValue::from_str(&auth_value).map_err(|e| AsrError::Config(e.to_string()))?,
+        );
+        if let Some(beta) = &self.endpoint.beta_header {
+            headers.insert(
+                "openai-beta",
+                HeaderValue::from_str(beta).map_err(|e| AsrError::Config(e.to_string()))?,
+            );
+        }
+
+        Ok(request)
+    }
+
+    /// 按方言构建会话配置事件，`sample_rate` 为发送给服务端的音频采样率
+    fn session_update(
+        &self,
+        sample_rate: u32,
+        turn_detection: Option<TurnDetection>,
+    ) -> SessionUpdateEvent {
+        let dialect = self.endpoint.dialect;
+        let (modalities, sample_rate, model) = match dialect {
+            RealtimeDialect::OpenAi => (None, None, Some(self.model.clone())),
+            RealtimeDialect::DashScope => (Some(vec!["text".to_string()]), Some(sample_rate), None),
+        };
+        // 热词：OpenAI 作为提示词，DashScope 作为上下文语料
+        let hotwords = (!self.hotwords.is_empty()).then(|| self.hotwords.join(", "));
+        let (prompt, corpus) = match dialect {
+            RealtimeDialect::OpenAi => (hotwords, None),
+            RealtimeDialect::DashScope => (None, hotwords.map(|text| Corpus { text })),
+        };
+        SessionUpdateEvent {
+            event_id: generate_event_id(),
+            event_type: dialect.session_update_event().to_string(),
+            session: SessionConfig {
+                modalities,
+                input_audio_format: dialect.input_audio_format().to_string(),
+                sample_rate,
+                input_audio_transcription: TranscriptionConfig {
+                    model,
+                    language: self.language.clone(),
+                    prompt,
+                    corpus,
+                },
+                turn_detection,
+            },
+        }
+    }
+}
+
+/// 是否为会话已建立或配置已生效的事件
+fn is_session_ready(event_type: &str) -> bool {
+    matches!(
+        event_type,
+        "session.created"
+            | "session.updated"
+            | "transcription_session.created"
+            | "transcription_session.updated"
+    )
+}
+
+/// 将 16-bit PCM 音频块重采样到服务端接受的采样率
+fn resample_pcm16(data: &[u8], from_rate: u32, to_rate: u32) -> Vec<u8> {
+    if from_rate == to_rate {
+        return data.to_vec();
+    }
+    let samples: Vec<f32> = data
+        .chunks_exact(2)
+        .map(|bytes| i16::from_le_bytes([bytes[0], bytes[1]]) as f32 / i16::MAX as f32)
+        .collect();
+    encode_to_pcm(&resample(&samples, from_rate, to_rate))
+}
+
+// ============================================================================
+// 请求事件结构
+// ============================================================================
+
+#[derive(Serialize)]
+struct SessionUpdateEvent {
+    event_id: String,
+    #[serde(rename = "type")]
+    event_type: String,
+    session: SessionConfig,
+}
+
+#[derive(Serialize)]
+struct SessionConfig {
+    /// DashScope 专有
+    #[serde(skip_serializing_if = "Option::is_none")]
+    modalities: Option<Vec<String>>,
+    input_audio_format: String,
+    /// DashScope 专有，OpenAI 固定为 24kHz
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sample_rate: Option<u32>,
+    input_audio_transcription: TranscriptionConfig,
+    turn_detection: Option<TurnDetection>,
+}
+
+#[derive(Serialize)]
+struct TranscriptionConfig {
+    /// OpenAI 转写模型（DashScope 在 URL 中指定）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    language: Option<String>,
+    /// OpenAI 提示词，提高其中词汇的识别准确率
+    #[serde(skip_serializing_if = "Option::is_none")]
+    prompt: Option<String>,
+    /// DashScope 上下文文本，作用同上
+    #[serde(skip_serializing_if = "Option::is_none")]
+    corpus: Option<Corpus>,
+}
+
+#[derive(Serialize)]
+struct Corpus {
+    text: String,
+}
+
+#[derive(Serialize)]
+struct TurnDetection {
+    #[serde(rename = "type")]
+    detection_type: String,
+    threshold: f32,
+    silence_duration_ms: u32,
+}
+
+#[derive(Serialize)]
+struct AudioAppendEvent {
+    event_id: String,
+    #[serde(rename = "type")]
+    event_type: String,
+    audio: String,
+}
+
+#[derive(Serialize)]
+struct AudioCommitEvent {
+    event_id: String,
+    #[serde(rename = "type")]
+    event_type: String,
+}
+
+// ============================================================================
+// 响应事件结构
+// ============================================================================
+
+#[derive(Deserialize, Debug)]
+struct ResponseEvent {
+    #[serde(rename = "type")]
+    event_type: String,
+    transcript: Option<String>,
+    /// OpenAI 中间结果：新增的文本
+    delta: Option<String>,
+    /// DashScope 中间结果：已确认的文本和暂定文本
+    text: Option<String>,
+    stash: Option<String>,
+    error: Option<ErrorInfo>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ErrorInfo {
+    message: String,
+}
+
+/// 服务端事件对应的识别进展
+#[derive(Debug, PartialEq)]
+enum TranscriptUpdate {
+    Partial { text: String, stash: String },
+    Completed(String),
+    Error(String),
+}
+
+/// 按方言解析识别事件，拼接当前一轮的中间结果
+struct TranscriptAssembler {
+    dialect: RealtimeDialect,
+    /// 当前一轮已收到的文本
+    text: String,
+}
+
+impl TranscriptAssembler {
+    fn new(dialect: RealtimeDialect) -> Self {
+        Self {
+            dialect,
+            text: String::new(),
+        }
+    }
+
+    fn handle(&mut self, event: ResponseEvent) -> Option<TranscriptUpdate> {
+        if let Some(error) = event.error {
+            return Some(TranscriptUpdate::Error(error.message));
+        }
+        match (self.dialect, event.event_type.as_str()) {
+            // OpenAI 每次只推送新增的文本
+            (RealtimeDialect::OpenAi, "conversation.item.input_audio_transcription.delta") => {
+                self.text.push_str(&event.delta.unwrap_or_default());
+                Some(TranscriptUpdate::Partial {
+                    text: self.text.clone(),
+                    stash: String::new(),
+                })
+            }
+            // DashScope 每次推送完整的已确认文本和暂定文本
+            (RealtimeDialect::DashScope, "conversation.item.input_audio_transcription.text") => {
+                let text = event.text.unwrap_or_default();
+                let stash = event.stash.unwrap_or_default();
+                if !text.is_empty() {
+                    self.text = text.clone();
+                }
+                Some(TranscriptUpdate::Partial { text, stash })
+            }
+            (_, "conversation.item.input_audio_transcription.completed") => {
+                // 重置累积文本，准备下一轮
+                let accumulated = std::mem::take(&mut self.text);
+                Some(TranscriptUpdate::Completed(
+                    event.transcript.or(event.text).unwrap_or(accumulated),
+                ))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// 序列化并发送一个客户端事件
+async fn send_event<S, T>(write: &mut S, event: &T) -> Result<(), AsrError>
+where
+    S: futures_util::Sink<Message> + Unpin,
+    S::Error: std::fmt::Display,
+    T: Serialize,
+{
+    let json = serde_json::to_string(event).map_err(|e| AsrError::Encoding(e.to_string()))?;
+    write
+        .send(Message::Text(json.into()))
+        .await
+        .map_err(|e| AsrError::Network(e.to_string()))
+}
+
+/// 奇数长度时丢弃末尾不完整的采样，保证每块都按采样边界切分
+fn whole_samples(audio_data: &[u8]) -> &[u8] {
+    if !audio_data.len().is_multiple_of(BYTES_PER_SAMPLE) {
+        tracing::warn!("PCM data has odd length {}, dropping trailing byte", audio_data.len());
+    }
+    &audio_data[..audio_data.len() - audio_data.len() % BYTES_PER_SAMPLE]
+}
+
+/// 每块约 100ms，块大小为采样字节数的整数倍
+fn chunk_size(sample_rate: u32) -> usize {
+    (sample_rate as usize / 10).max(1) * BYTES_PER_SAMPLE
+}
+
+// ============================================================================
+// 整段识别实现
+// ============================================================================
+
+impl OpenAiRealtimeAsr {
+    /// 识别完整录音，按每块 100ms 切分后发送
+    async fn recognize_buffer(
+        &self,
+        audio_data: &[u8],
+        sample_rate: u32,
+        partial_tx: Option<&mpsc::Sender<String>>,
+    ) -> Result<AsrResult, AsrError> {
+        let audio_data = whole_samples(audio_data);
+
+        // 空音频直接返回，避免建立无意义的连接
+        if audio_data.is_empty() {
+            return Err(AsrError::NoAudio);
+        }
+
+        tracing::debug!("发送音频数据: {} 字节", audio_data.len());
+
+        let audio_rx = chunk_channel(audio_data, chunk_size(sample_rate));
+        self.run_recognition(audio_rx, sample_rate, partial_tx).await
+    }
+
+    /// 执行一次识别：音频块从 `audio_rx` 到达后立即发送（base64 编码），同时接收识别结果
+    ///
+    /// 手动提交模式，音频发送完毕后提交缓冲区。
+    /// 中间结果通过 `partial_tx` 推送（不阻塞识别，消费方过慢时丢弃）
+    async fn run_recognition(
+        &self,
+        audio_rx: mpsc::Receiver<Vec<u8>>,
+        sample_rate: u32,
+        partial_tx: Option<&mpsc::Sender<String>>,
+    ) -> Result<AsrResult, AsrError> {
+        // 优先复用连接池中的空闲或预先建立的连接（复用时重新发送会话配置，等待配置生效）
+        let pooled = pool::take(&self.pool_key()).await;
+        let ws_stream = match pooled {
+            Some(stream) => stream,
+            None => self.connect().await?,
+        };
+
+        let (mut write, mut read) = ws_stream.split();
+
+        let target_rate = select_sample_rate(self.endpoint.dialect.sample_rates(), sample_rate);
+        // 手动模式，通过 commit 触发识别
+        send_event(&mut write, &self.session_update(target_rate, None)).await?;
+
+        // 等待会话建立或配置生效
+        let mut session_ready = false;
+        while let Some(msg) = next_with_timeout(&mut read, self.timeout).await? {
+            match msg {
+                Ok(Message::Text(text)) => {
+                    let response: ResponseEvent = serde_json::from_str(&text)
+                        .map_err(|e| AsrError::Api(format!("解析响应失败: {}", e)))?;
+
+                    if let Some(error) = response.error {
+                        return Err(AsrError::Api(error.message));
+                    }
+
+                    if is_session_ready(&response.event_type) {
+                        session_ready = true;
+                        break;
+                    }
+                }
+                Ok(Message::Close(_)) => {
+                    return Err(AsrError::Network("WebSocket 连接被关闭".to_string()));
+                }
+                Err(e) => {
+                    return Err(AsrError::Network(e.to_string()));
+                }
+                _ => {}
+            }
+        }
+
+        if !session_ready {
+            return Err(AsrError::Api("未收到 session 确认事件".to_string()));
+        }
+
+        // 发送音频的同时收集识别结果，音频发送完毕后发送 commit 信号
+        let mut audio_rx = Some(audio_rx);
+        let mut sent_bytes = 0usize;
+        let mut assembler = TranscriptAssembler::new(self.endpoint.dialect);
+        let mut final_text = String::new();
+        let mut completed = false;
+
+        loop {
+            // 音频发送完毕后才按超时等待服务端消息
+            let timeout = audio_rx.is_none().then_some(self.timeout);
+            let msg = tokio::select! {
+                chunk = recv_audio_chunk(&mut audio_rx) => {
+                    match chunk {
+                        Some(chunk) => {
+                            sent_bytes += chunk.len();
+                            let audio = resample_pcm16(&chunk, sample_rate, target_rate);
+                            let audio_append = AudioAppendEvent {
+                                event_id: generate_event_id(),
+                                event_type: "input_audio_buffer.append".to_string(),
+                                audio: BASE64.encode(&audio),
+                            };
+                            send_event(&mut write, &audio_append).await?;
+                        }
+                        None => {
+                            audio_rx = None;
+                            // 没有发送任何音频，不提交空缓冲区
+                            if sent_bytes == 0 {
+                                return Err(AsrError::NoAudio);
+                            }
+                            let commit = AudioCommitEvent {
+                                event_id: generate_event_id(),
+                                event_type: "input_audio_buffer.commit".to_string(),
+                            };
+                            send_event(&mut write, &commit).await?;
+                        }
+                    }
+                    continue;
+                }
+                msg = next_with_optional_timeout(&mut read, timeout) => msg?,
+            };
+            let Some(msg) = msg else {
+                break;
+            };
+
+            match msg {
+                Ok(Message::Text(text)) => {
+                    let response: ResponseEvent = serde_json::from_str(&text)
+                        .map_err(|e| AsrError::Api(format!("解析响应失败: {}", e)))?;
+
+                    match assembler.handle(response) {
+                        Some(TranscriptUpdate::Partial { text, stash }) => {
+                            let partial = text + &stash;
+                            if let Some(tx) = partial_tx {
+                                let _ = tx.try_send(partial.clone());
+                            }
+                            final_text = partial;
+                        }
+                        Some(TranscriptUpdate::Completed(text)) => {
+                            final_text = text;
+                            completed = true;
+                            break;
+                        }
+                        Some(TranscriptUpdate::Error(message)) => {
+                            return Err(AsrError::Api(message));
+                        }
+                        None => {}
+                    }
+                }
+                Ok(Message::Close(_)) => {
+                    break;
+                }
+                Err(e) => {
+                    return Err(AsrError::Network(e.to_string()));
+                }
+                _ => {}
+            }
+        }
+
+        // 识别正常完成时会话仍可用，放回连接池
+        if self.keep_alive && completed {
+            if let Ok(stream) = read.reunite(write) {
+                pool::put(&self.pool_key(), stream);
+            }
+        }
+
+        Ok(AsrResult {
+            text: final_text,
+            is_final: true,
+            confidence: None,
+            words: Vec::new(),
+            segments: Vec::new(),
+        })
+    }
+}
+
+#[async_trait]
+impl AsrService for OpenAiRealtimeAsr {
+    async fn recognize(&self, audio_data: &[u8], sample_rate: u32) -> Result<AsrResult, AsrError> {
+        self.recognize_buffer(audio_data, sample_rate, None).await
+    }
+
+    async fn recognize_streaming(
+        &self,
+        audio_data: &[u8],
+        sample_rate: u32,
+        partial_tx: mpsc::Sender<String>,
+    ) -> Result<AsrResult, AsrError> {
+        self.recognize_buffer(audio_data, sample_rate, Some(&partial_tx)).await
+    }
+
+    fn supported_sample_rates(&self) -> &'static [u32] {
+        self.endpoint.dialect.sample_rates()
+    }
+
+    fn supports_hotwords(&self) -> bool {
+        true
+    }
+
+    async fn preconnect(&self) -> Result<(), AsrError> {
+        pool::preconnect(&self.pool_key(), self.timeout, self.connect()).await
+    }
+
+    fn discard_preconnected(&self) {
+        pool::remove(&self.pool_key());
+    }
+
+    fn supports_streaming_input(&self) -> bool {
+        true
+    }
+
+    async fn start_streaming_recognition(
+        &self,
+        audio_rx: mpsc::Receiver<Vec<u8>>,
+        sample_rate: u32,
+        partial_tx: Option<mpsc::Sender<String>>,
+    ) -> Result<AsrResult, AsrError> {
+        self.run_recognition(audio_rx, sample_rate, partial_tx.as_ref()).await
+    }
+}
+
+// ============================================================================
+// 流式服务实现
+// ============================================================================
+
+#[async_trait]
+impl StreamingAsrService for OpenAiRealtimeAsr {
+    async fn start_streaming(
+        &self,
+        sample_rate: u32,
+    ) -> Result<(mpsc::Sender<StreamingControl>, mpsc::Receiver<StreamingAsrEvent>), AsrError> {
+        // 创建通道
+        let (control_tx, mut control_rx) = mpsc::channel::<StreamingControl>(32);
+        let (event_tx, event_rx) = mpsc::channel::<StreamingAsrEvent>(32);
+
+        // 连接 WebSocket（带超时）
+        let (mut write, mut read) = self.connect().await?.split();
+
+        // 服务端不接受录音采样率时逐块重采样
+        let target_rate = select_sample_rate(self.endpoint.dialect.sample_rates(), sample_rate);
+
+        // 发送会话配置（使用 VAD 模式实现实时识别）
+        let session_update = self.session_update(
+            target_rate,
+            // VAD 模式：服务端自动检测语音边界
+            Some(TurnDetection {
+                detection_type: "server_vad".to_string(),
+                threshold: 0.5,
+                silence_duration_ms: 500,
+            }),
+        );
+
+        let session_json =
+            serde_json::to_string(&session_update).map_err(|e| AsrError::Encoding(e.to_string()))?;
+
+        write
+            .send(Message::Text(session_json.into()))
+            .await
+            .map_err(|e| AsrError::Network(e.to_string()))?;
+
+        // 等待 session 确认（带超时）
+        let session_confirm_result = timeout(SESSION_CONFIRM_TIMEOUT, async {
+            while let Some(msg) = read.next().await {
+                match msg {
+                    Ok(Message::Text(text)) => {
+                        if let Ok(response) = serde_json::from_str::<ResponseEvent>(&text) {
+                            if let Some(error) = response.error {
+                                return Err(AsrError::Api(error.message));
+                            }
+                            if is_session_ready(&response.event_type) {
+                                return Ok(());
+                            }
+                        }
+                    }
+                    Ok(Message::Close(_)) => {
+                        return Err(AsrError::Network("WebSocket 连接被关闭".to_string()));
+                    }
+                    Err(e) => {
+                        return Err(AsrError::Network(e.to_string()));
+                    }
+                    _ => {}
+                }
+            }
+            Err(AsrError::Api("未收到 session 确认事件".to_string()))
+        })
+        .await;
+
+        match session_confirm_result {
+            Ok(Ok(())) => {} // session 确认成功
+            Ok(Err(e)) => return Err(e),
+            Err(_) => return Err(AsrError::Network("等待 session 确认超时".to_string())),
+        }
+
+        // 启动后台任务处理双向通信
+        let event_tx_clone = event_tx.clone();
+        let mut assembler = TranscriptAssembler::new(self.endpoint.dialect);
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    // 处理控制命令
+                    Some(control) = control_rx.recv() => {
+                        match control {
+                            StreamingControl::Audio(data) => {
+                                // 发送音频数据
+                                let audio = resample_pcm16(&data, sample_rate, target_rate);
+                                let audio_append = AudioAppendEvent {
+                                    event_id: generate_event_id(),
+                                    event_type: "input_audio_buffer.append".to_string(),
+                                    audio: BASE64.encode(&audio),
+                                };
+                                if let Ok(json) = serde_json::to_string(&audio_append) {
+                                    if write.send(Message::Text(json.into())).await.is_err() {
+                                        let _ = event_tx_clone.send(StreamingAsrEvent::Error(
+                                            "发送音频失败".to_string()
+                                        )).await;
+                                        break;
+                                    }
+                                }
+                            }
+                            StreamingControl::Commit => {
+                                // 提交音频缓冲区
+                                let commit = AudioCommitEvent {
+                                    event_id: generate_event_id(),
+                                    event_type: "input_audio_buffer.commit".to_string(),
+                                };
+                                if let Ok(json) = serde_json::to_string(&commit) {
+                                    let _ = write.send(Message::Text(json.into())).await;
+                                }
+                            }
+                            StreamingControl::Cancel => {
+                                // 取消并关闭连接
+                                let _ = write.close().await;
+                                break;
+                            }
+                        }
+                    }
+                    // 处理服务端响应
+                    Some(msg) = read.next() => {
+                        match msg {
+                            Ok(Message::Text(text)) => {
+                                let Ok(response) = serde_json::from_str::<ResponseEvent>(&text) else {
+                                    continue;
+                                };
+                                match assembler.handle(response) {
+                                    Some(TranscriptUpdate::Partial { text, stash }) => {
+                                        let _ = event_tx_clone
+                                            .send(StreamingAsrEvent::Partial { text, stash })
+                                            .await;
+                                    }
+                                    Some(TranscriptUpdate::Completed(text)) => {
+                                        let _ = event_tx_clone
+                                            .send(StreamingAsrEvent::Final { text })
+                                            .await;
+                                    }
+                                    Some(TranscriptUpdate::Error(message)) => {
+                                        let _ = event_tx_clone
+                                            .send(StreamingAsrEvent::Error(message))
+                                            .await;
+                                        break;
+                                    }
+                                    None => {}
+                                }
+                            }
+                            Ok(Message::Close(_)) => {
+                                break;
+                            }
+                            Err(e) => {
+                                let _ = event_tx_clone.send(StreamingAsrEvent::Error(
+                                    e.to_string()
+                                )).await;
+                                break;
+                            }
+                            _ => {}
+                        }
+                    }
+                    else => break,
+                }
+            }
+        });
+
+        Ok((control_tx, event_rx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed(dialect: RealtimeDialect, frames: &[&str]) -> Vec<TranscriptUpdate> {
+        let mut assembler = TranscriptAssembler::new(dialect);
+        frames
+            .iter()
+            .filter_map(|frame| assembler.handle(serde_json::from_str(frame).unwrap()))
+            .collect()
+    }
+
+    fn realtime_asr(dialect: RealtimeDialect) -> OpenAiRealtimeAsr {
+        OpenAiRealtimeAsr::new(
+            RealtimeEndpoint {
+                base_url: "wss://api.openai.com/v1/realtime".to_string(),
+                auth_header: "Authorization".to_string(),
+                beta_header: Some("realtime=v1".to_string()),
+                dialect,
+            },
+            "sk-test".to_string(),
+            "gpt-4o-transcribe".to_string(),
+            Some("en".to_string()),
+            vec!["Vhisper".to_string()],
+            false,
+            Duration::from_secs(10),
+        )
+    }
+
+    #[test]
+    fn openai_transcription_frames() {
+        let frames = [
+            r#"{"type":"transcription_session.created","event_id":"event_1","session":{"id":"sess_1","input_audio_format":"pcm16"}}"#,
+            r#"{"type":"input_audio_buffer.committed","event_id":"event_2","previous_item_id":null,"item_id":"item_1"}"#,
+            r#"{"type":"conversation.item.input_audio_transcription.delta","event_id":"event_3","item_id":"item_1","content_index":0,"delta":"Hello"}"#,
+            r#"{"type":"conversation.item.input_audio_transcription.delta","event_id":"event_4","item_id":"item_1","content_index":0,"delta":", world."}"#,
+            r#"{"type":"conversation.item.input_audio_transcription.completed","event_id":"event_5","item_id":"item_1","content_index":0,"transcript":"Hello, world."}"#,
+        ];
+        assert_eq!(
+            feed(RealtimeDialect::OpenAi, &frames),
+            [
+                TranscriptUpdate::Partial {
+                    text: "Hello".to_string(),
+                    stash: String::new(),
+                },
+                TranscriptUpdate::Partial {
+                    text: "Hello, world.".to_string(),
+                    stash: String::new(),
+                },
+                TranscriptUpdate::Completed("Hello, world.".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn openai_error_frame() {
+        let frames = [
+            r#"{"type":"error","event_id":"event_1","error":{"type":"invalid_request_error","code":"invalid_api_key","message":"Incorrect API key provided","param":null}}"#,
+        ];
+        assert_eq!(
+            feed(RealtimeDialect::OpenAi, &frames),
+            [TranscriptUpdate::Error(
+                "Incorrect API key provided".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn dashscope_text_and_stash_frames() {
+        let frames = [
+            r#"{"type":"conversation.item.input_audio_transcription.text","text":"你好","stash":"世"}"#,
+            r#"{"type":"conversation.item.input_audio_transcription.completed","transcript":"你好世界"}"#,
+        ];
+        assert_eq!(
+            feed(RealtimeDialect::DashScope, &frames),
+            [
+                TranscriptUpdate::Partial {
+                    text: "你好".to_string(),
+                    stash: "世".to_string(),
+                },
+                TranscriptUpdate::Completed("你好世界".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn openai_session_update_uses_transcription_session() {
+        let event = realtime_asr(RealtimeDialect::OpenAi).session_update(24000, None);
+        let json: serde_json::Value = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], "transcription_session.update");
+        assert_eq!(json["session"]["input_audio_format"], "pcm16");
+        assert_eq!(
+            json["session"]["input_audio_transcription"]["model"],
+            "gpt-4o-transcribe"
+        );
+        assert_eq!(
+            json["session"]["input_audio_transcription"]["prompt"],
+            "Vhisper"
+        );
+        assert!(json["session"].get("sample_rate").is_none());
+        assert!(json["session"].get("modalities").is_none());
+    }
+
+    #[test]
+    fn dashscope_session_update_keeps_sample_rate() {
+        let event = realtime_asr(RealtimeDialect::DashScope).session_update(16000, None);
+        let json: serde_json::Value = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], "session.update");
+        assert_eq!(json["session"]["input_audio_format"], "pcm");
+        assert_eq!(json["session"]["sample_rate"], 16000);
+        assert!(json["session"]["input_audio_transcription"]
+            .get("model")
+            .is_none());
+        assert_eq!(
+            json["session"]["input_audio_transcription"]["corpus"]["text"],
+            "Vhisper"
+        );
+    }
+
+    #[test]
+    fn odd_length_buffer_drops_trailing_byte() {
+        assert_eq!(whole_samples(&[1, 2, 3, 4, 5]), &[1, 2, 3, 4]);
+        assert_eq!(whole_samples(&[1, 2, 3, 4]), &[1, 2, 3, 4]);
+        assert!(whole_samples(&[1]).is_empty());
+    }
+
+    #[test]
+    fn chunks_split_on_sample_boundaries() {
+        for sample_rate in [8000, 11025, 16000, 22050, 44100, 5] {
+            assert_eq!(chunk_size(sample_rate) % BYTES_PER_SAMPLE, 0, "sample_rate={}", sample_rate);
+        }
+        assert_eq!(chunk_size(16000), 3200);
+    }
+
+    #[test]
+    fn audio_is_resampled_to_24khz() {
+        let chunk = encode_to_pcm(&vec![0.25; 1600]);
+        assert_eq!(resample_pcm16(&chunk, 16000, 24000).len(), 2400 * 2);
+        assert_eq!(resample_pcm16(&chunk, 16000, 16000), chunk);
+    }
+}
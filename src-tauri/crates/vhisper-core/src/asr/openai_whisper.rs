@@ -1,31 +1,79 @@
 use async_trait::async_trait;
 use reqwest::{multipart, Client};
 use serde::Deserialize;
+use std::time::Duration;
 
 use super::traits::{AsrError, AsrResult, AsrService};
 
 /// OpenAI Whisper ASR 服务
 pub struct OpenAiWhisper {
+    /// API 地址（如 https://api.openai.com/v1）
+    base_url: String,
     api_key: String,
     model: String,
-    language: String,
+    /// 识别语言，None 时自动检测
+    language: Option<String>,
     client: Client,
+    /// 等待响应的超时时间
+    timeout: Duration,
 }
 
 impl OpenAiWhisper {
-    pub fn new(api_key: String, model: String, language: String) -> Self {
+    pub fn new(
+        base_url: String,
+        api_key: String,
+        model: String,
+        language: Option<String>,
+        timeout: Duration,
+    ) -> Self {
         Self {
+            base_url,
             api_key,
             model,
             language,
             client: Client::new(),
+            timeout,
         }
     }
 }
 
+/// 拼接 API 地址和接口路径（兼容末尾带 `/` 的地址）
+fn endpoint(base_url: &str, path: &str) -> String {
+    format!("{}/{}", base_url.trim_end_matches('/'), path)
+}
+
+/// 请求错误转换为 ASR 错误，超时单独提示
+fn request_error(e: reqwest::Error) -> AsrError {
+    if e.is_timeout() {
+        AsrError::Network("识别超时".to_string())
+    } else {
+        AsrError::Network(e.to_string())
+    }
+}
+
 #[derive(Deserialize)]
 struct WhisperResponse {
     text: String,
+    /// verbose_json 格式才有分段信息
+    #[serde(default)]
+    segments: Vec<WhisperSegment>,
+}
+
+#[derive(Deserialize)]
+struct WhisperSegment {
+    avg_logprob: f32,
+}
+
+impl WhisperResponse {
+    /// 由各分段的平均对数概率换算整体置信度
+    fn confidence(&self) -> Option<f32> {
+        if self.segments.is_empty() {
+            return None;
+        }
+        let avg_logprob = self.segments.iter().map(|s| s.avg_logprob).sum::<f32>()
+            / self.segments.len() as f32;
+        Some(avg_logprob.exp().clamp(0.0, 1.0))
+    }
 }
 
 #[derive(Deserialize)]
@@ -41,32 +89,44 @@ struct WhisperErrorDetail {
 #[async_trait]
 impl AsrService for OpenAiWhisper {
     async fn recognize(&self, audio_data: &[u8], _sample_rate: u32) -> Result<AsrResult, AsrError> {
+        // 空音频直接返回，避免建立无意义的连接
+        if audio_data.is_empty() {
+            return Err(AsrError::NoAudio);
+        }
+
         // OpenAI Whisper API 需要 WAV 格式的文件
         let file_part = multipart::Part::bytes(audio_data.to_vec())
             .file_name("audio.wav")
             .mime_str("audio/wav")
             .map_err(|e| AsrError::Encoding(e.to_string()))?;
 
-        let form = multipart::Form::new()
+        // 只有 whisper 系列模型支持 verbose_json（带分段置信度）
+        let response_format = if self.model.starts_with("whisper") {
+            "verbose_json"
+        } else {
+            "json"
+        };
+
+        let mut form = multipart::Form::new()
             .part("file", file_part)
             .text("model", self.model.clone())
-            .text("language", self.language.clone())
-            .text("response_format", "json");
+            .text("response_format", response_format);
+        if let Some(language) = &self.language {
+            form = form.text("language", language.clone());
+        }
 
         let response = self
             .client
-            .post("https://api.openai.com/v1/audio/transcriptions")
+            .post(endpoint(&self.base_url, "audio/transcriptions"))
             .header("Authorization", format!("Bearer {}", self.api_key))
             .multipart(form)
+            .timeout(self.timeout)
             .send()
             .await
-            .map_err(|e| AsrError::Network(e.to_string()))?;
+            .map_err(request_error)?;
 
         let status = response.status();
-        let body = response
-            .text()
-            .await
-            .map_err(|e| AsrError::Network(e.to_string()))?;
+        let body = response.text().await.map_err(request_error)?;
 
         if !status.is_success() {
             if let Ok(error) = serde_json::from_str::<WhisperError>(&body) {
@@ -79,18 +139,26 @@ impl AsrService for OpenAiWhisper {
             serde_json::from_str(&body).map_err(|e| AsrError::Api(e.to_string()))?;
 
         Ok(AsrResult {
+            confidence: result.confidence(),
+            words: Vec::new(),
             text: result.text,
             is_final: true,
+            segments: Vec::new(),
         })
     }
+
+    fn supported_sample_rates(&self) -> &'static [u32] {
+        // WAV 文件自带采样率，Whisper 内部会重采样
+        &[16000, 8000, 22050, 24000, 32000, 44100, 48000]
+    }
 }
 
-/// 测试 OpenAI API 连接
-pub async fn test_api(api_key: &str) -> Result<String, AsrError> {
+/// 测试 OpenAI（或兼容服务）API 连接
+pub async fn test_api(base_url: &str, api_key: &str) -> Result<String, AsrError> {
     let client = Client::new();
 
     let response = client
-        .get("https://api.openai.com/v1/models")
+        .get(endpoint(base_url, "models"))
         .header("Authorization", format!("Bearer {}", api_key))
         .send()
         .await
@@ -0,0 +1,109 @@
+//! WebSocket 连接池
+//!
+//! 连续多次识别时复用上一次的 WebSocket 连接，省去每次建立连接和 TLS 握手的延迟。
+//! 超过空闲窗口或心跳检测失败的连接会被丢弃。
+
+use futures_util::{SinkExt, StreamExt};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream;
+use tokio_tungstenite::{tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+use super::traits::AsrError;
+
+pub(crate) type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// 空闲连接最长保留时间
+const IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 复用前心跳检测的超时时间
+const PING_TIMEOUT: Duration = Duration::from_millis(1000);
+
+struct IdleConnection {
+    stream: WsStream,
+    idle_since: Instant,
+}
+
+static POOL: OnceLock<Mutex<HashMap<String, IdleConnection>>> = OnceLock::new();
+
+fn pool() -> &'static Mutex<HashMap<String, IdleConnection>> {
+    POOL.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 取出可复用的空闲连接
+///
+/// 已过期或心跳无响应的连接会被丢弃并返回 None
+pub(crate) async fn take(key: &str) -> Option<WsStream> {
+    let idle = pool().lock().unwrap().remove(key)?;
+
+    if idle.idle_since.elapsed() > IDLE_TIMEOUT {
+        tracing::debug!("Pooled connection expired, discarding");
+        return None;
+    }
+
+    let mut stream = idle.stream;
+    if is_alive(&mut stream).await {
+        tracing::debug!("Reusing pooled WebSocket connection");
+        Some(stream)
+    } else {
+        tracing::debug!("Pooled connection is stale, discarding");
+        None
+    }
+}
+
+/// 归还连接，供下一次识别复用
+///
+/// 同一 key 只保留最近一个连接
+pub(crate) fn put(key: &str, stream: WsStream) {
+    pool().lock().unwrap().insert(
+        key.to_string(),
+        IdleConnection {
+            stream,
+            idle_since: Instant::now(),
+        },
+    );
+}
+
+/// 预先建立连接并放入连接池，池中已有可用连接时不重复建立
+pub(crate) async fn preconnect<F>(key: &str, timeout: Duration, connect: F) -> Result<(), AsrError>
+where
+    F: Future<Output = Result<WsStream, AsrError>>,
+{
+    let stream = match take(key).await {
+        Some(stream) => stream,
+        None => tokio::time::timeout(timeout, connect)
+            .await
+            .map_err(|_| AsrError::Network("WebSocket 连接超时".to_string()))??,
+    };
+    put(key, stream);
+    Ok(())
+}
+
+/// 丢弃空闲连接
+pub(crate) fn remove(key: &str) {
+    pool().lock().unwrap().remove(key);
+}
+
+/// 发送 Ping 并等待 Pong，确认连接仍然可用
+async fn is_alive(stream: &mut WsStream) -> bool {
+    if stream.send(Message::Ping(Vec::new().into())).await.is_err() {
+        return false;
+    }
+
+    let wait_pong = async {
+        while let Some(msg) = stream.next().await {
+            match msg {
+                Ok(Message::Pong(_)) => return true,
+                Ok(Message::Close(_)) | Err(_) => return false,
+                _ => {}
+            }
+        }
+        false
+    };
+
+    tokio::time::timeout(PING_TIMEOUT, wait_pong)
+        .await
+        .unwrap_or(false)
+}
@@ -0,0 +1,93 @@
+//! Sherpa-ONNX 本地离线 ASR 服务
+//!
+//! 从配置的模型目录加载 Paraformer ONNX 模型，完全离线识别。
+//! Paraformer 是非流式模型，只支持录音结束后整段识别，不提供实时识别
+
+use async_trait::async_trait;
+use sherpa_rs::paraformer::{ParaformerConfig, ParaformerRecognizer};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use super::traits::{AsrError, AsrResult, AsrService};
+
+/// Sherpa-ONNX 本地 ASR 服务（仅整段识别）
+pub struct SherpaAsr {
+    /// 模型只在创建服务时加载一次，推理时加锁独占使用
+    recognizer: Arc<Mutex<ParaformerRecognizer>>,
+}
+
+impl SherpaAsr {
+    /// 加载模型目录中的模型，目录缺少文件或模型无法加载时返回配置错误
+    pub fn new(model_dir: String, num_threads: i32) -> Result<Self, AsrError> {
+        let model_dir = PathBuf::from(model_dir);
+        let model = find_model_file(&model_dir)?;
+        let tokens = model_dir.join("tokens.txt");
+        if !tokens.exists() {
+            return Err(AsrError::Config(format!(
+                "模型目录中未找到 tokens.txt: {:?}",
+                model_dir
+            )));
+        }
+
+        let config = ParaformerConfig {
+            model: model.to_string_lossy().to_string(),
+            tokens: tokens.to_string_lossy().to_string(),
+            num_threads: Some(num_threads),
+            ..Default::default()
+        };
+        let recognizer = ParaformerRecognizer::new(config)
+            .map_err(|e| AsrError::Config(format!("加载 Sherpa 模型失败: {}", e)))?;
+        Ok(Self {
+            recognizer: Arc::new(Mutex::new(recognizer)),
+        })
+    }
+}
+
+/// 在模型目录中查找模型文件（优先使用 int8 量化模型）
+fn find_model_file(dir: &Path) -> Result<PathBuf, AsrError> {
+    ["model.int8.onnx", "model.onnx"]
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|p| p.exists())
+        .ok_or_else(|| AsrError::Config(format!("模型目录中未找到 model.onnx: {:?}", dir)))
+}
+
+/// 将 16-bit PCM 转换为 f32 采样
+fn pcm_to_samples(audio_data: &[u8]) -> Vec<f32> {
+    audio_data
+        .chunks_exact(2)
+        .map(|b| i16::from_le_bytes([b[0], b[1]]) as f32 / i16::MAX as f32)
+        .collect()
+}
+
+#[async_trait]
+impl AsrService for SherpaAsr {
+    async fn recognize(&self, audio_data: &[u8], sample_rate: u32) -> Result<AsrResult, AsrError> {
+        // 空音频直接返回，避免建立无意义的连接
+        if audio_data.is_empty() {
+            return Err(AsrError::NoAudio);
+        }
+
+        let samples = pcm_to_samples(audio_data);
+        let recognizer = Arc::clone(&self.recognizer);
+
+        // 模型推理是 CPU 密集型同步操作，放到阻塞线程池执行
+        let text = tokio::task::spawn_blocking(move || {
+            let mut recognizer = recognizer
+                .lock()
+                .map_err(|_| AsrError::Session("Sherpa 识别器不可用".to_string()))?;
+            let result = recognizer.transcribe(sample_rate, &samples);
+            Ok::<_, AsrError>(result.text)
+        })
+        .await
+        .map_err(|e| AsrError::Session(e.to_string()))??;
+
+        Ok(AsrResult {
+            text: text.trim().to_string(),
+            is_final: true,
+            confidence: None,
+            words: Vec::new(),
+            segments: Vec::new(),
+        })
+    }
+}
@@ -1,4 +1,7 @@
 use async_trait::async_trait;
+use futures_util::{Stream, StreamExt};
+use serde::Serialize;
+use std::time::Duration;
 use tokio::sync::mpsc;
 
 #[derive(Debug, thiserror::Error)]
@@ -13,6 +16,8 @@ pub enum AsrError {
     Config(String),
     #[error("Session error: {0}")]
     Session(String),
+    #[error("No audio captured")]
+    NoAudio,
     #[error("Cancelled")]
     Cancelled,
 }
@@ -22,6 +27,31 @@ pub enum AsrError {
 pub struct AsrResult {
     pub text: String,
     pub is_final: bool,
+    /// 整体置信度（0.0 - 1.0），服务商不提供时为 None
+    pub confidence: Option<f32>,
+    /// 词级识别信息，服务商不提供时为空
+    pub words: Vec<WordInfo>,
+    /// 按说话人划分的片段，服务商未开启说话人分离时为空
+    pub segments: Vec<SpeakerSegment>,
+}
+
+/// 带说话人标签的识别片段
+#[derive(Debug, Clone, Serialize)]
+pub struct SpeakerSegment {
+    /// 服务商返回的说话人标签（如 "A"、"B"）
+    pub speaker: String,
+    pub text: String,
+}
+
+/// 词级识别信息
+#[derive(Debug, Clone, Serialize)]
+pub struct WordInfo {
+    pub text: String,
+    /// 置信度（0.0 - 1.0），服务商只提供时间戳时为 None
+    pub confidence: Option<f32>,
+    /// 相对音频开头的起止时间（毫秒）
+    pub start_ms: u64,
+    pub end_ms: u64,
 }
 
 /// 流式识别事件
@@ -53,6 +83,131 @@ pub enum StreamingControl {
 pub trait AsrService: Send + Sync {
     /// 识别音频数据
     async fn recognize(&self, audio_data: &[u8], sample_rate: u32) -> Result<AsrResult, AsrError>;
+
+    /// 识别音频数据，识别过程中的中间结果通过 `partial_tx` 推送
+    ///
+    /// 默认不推送中间结果，直接调用 `recognize`
+    async fn recognize_streaming(
+        &self,
+        audio_data: &[u8],
+        sample_rate: u32,
+        partial_tx: mpsc::Sender<String>,
+    ) -> Result<AsrResult, AsrError> {
+        drop(partial_tx);
+        self.recognize(audio_data, sample_rate).await
+    }
+
+    /// 支持的采样率（Hz），按优先级从高到低排列
+    ///
+    /// 管道会在送入识别前将音频重采样到其中之一
+    fn supported_sample_rates(&self) -> &'static [u32] {
+        &[16000]
+    }
+
+    /// 是否将 `asr.hotwords` 直接传给服务商
+    ///
+    /// 不支持时管道将热词加入 LLM 术语表，由 LLM 优化时修正
+    fn supports_hotwords(&self) -> bool {
+        false
+    }
+
+    /// 预先建立识别连接（录音开始时调用），识别时直接使用
+    ///
+    /// 默认无操作，只有基于 WebSocket 的服务商需要实现
+    async fn preconnect(&self) -> Result<(), AsrError> {
+        Ok(())
+    }
+
+    /// 丢弃预先建立但未使用的连接（录音取消时调用）
+    fn discard_preconnected(&self) {}
+
+    /// 是否支持边录音边识别（音频到达时立即发送，而不是收集完整录音后再发送）
+    fn supports_streaming_input(&self) -> bool {
+        false
+    }
+
+    /// 边录音边识别：16-bit PCM 音频块陆续从 `audio_rx` 到达，发送端释放表示录音结束
+    ///
+    /// 识别过程中的中间结果通过 `partial_tx` 推送。
+    /// 默认收集全部音频后调用 `recognize` / `recognize_streaming`
+    async fn start_streaming_recognition(
+        &self,
+        mut audio_rx: mpsc::Receiver<Vec<u8>>,
+        sample_rate: u32,
+        partial_tx: Option<mpsc::Sender<String>>,
+    ) -> Result<AsrResult, AsrError> {
+        let mut audio_data = Vec::new();
+        while let Some(chunk) = audio_rx.recv().await {
+            audio_data.extend_from_slice(&chunk);
+        }
+        match partial_tx {
+            Some(tx) => self.recognize_streaming(&audio_data, sample_rate, tx).await,
+            None => self.recognize(&audio_data, sample_rate).await,
+        }
+    }
+}
+
+/// 将完整录音按 `chunk_size` 字节切分并放入通道，与边录边传共用同一发送流程
+pub(crate) fn chunk_channel(audio_data: &[u8], chunk_size: usize) -> mpsc::Receiver<Vec<u8>> {
+    let chunks = audio_data.chunks(chunk_size.max(1));
+    let (tx, rx) = mpsc::channel(chunks.len().max(1));
+    for chunk in chunks {
+        // 容量与块数一致，不会失败
+        let _ = tx.try_send(chunk.to_vec());
+    }
+    rx
+}
+
+/// 接收下一块待发送的音频，音频已发送完毕（`None`）时一直等待
+pub(crate) async fn recv_audio_chunk(
+    audio_rx: &mut Option<mpsc::Receiver<Vec<u8>>>,
+) -> Option<Vec<u8>> {
+    match audio_rx {
+        Some(rx) => rx.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// 等待流中的下一条消息，超过 `timeout` 未收到时返回超时错误
+///
+/// 每次调用重新计时，响应较慢但仍在推送消息的连接不会被中断
+pub(crate) async fn next_with_timeout<S>(
+    stream: &mut S,
+    timeout: Duration,
+) -> Result<Option<S::Item>, AsrError>
+where
+    S: Stream + Unpin,
+{
+    tokio::time::timeout(timeout, stream.next())
+        .await
+        .map_err(|_| AsrError::Network("识别超时".to_string()))
+}
+
+/// 等待流中的下一条消息，`timeout` 为 None 时不限制等待时间
+///
+/// 音频仍在发送时使用（用户停顿期间服务端可能长时间没有消息），发送完毕后再按超时计时
+pub(crate) async fn next_with_optional_timeout<S>(
+    stream: &mut S,
+    timeout: Option<Duration>,
+) -> Result<Option<S::Item>, AsrError>
+where
+    S: Stream + Unpin,
+{
+    match timeout {
+        Some(timeout) => next_with_timeout(stream, timeout).await,
+        None => Ok(stream.next().await),
+    }
+}
+
+/// 从服务支持的采样率中选择最合适的
+///
+/// - 源采样率受支持时直接使用，避免重采样
+/// - 否则使用优先级最高的采样率
+pub fn select_sample_rate(supported: &[u32], source_rate: u32) -> u32 {
+    if supported.contains(&source_rate) {
+        return source_rate;
+    }
+    supported.first().copied().unwrap_or(source_rate)
 }
 
 /// 流式 ASR 服务 trait
@@ -75,3 +230,36 @@ pub trait StreamingAsrService: Send + Sync {
         sample_rate: u32,
     ) -> Result<(mpsc::Sender<StreamingControl>, mpsc::Receiver<StreamingAsrEvent>), AsrError>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn select_sample_rate_matrix() {
+        let default_only: &[u32] = &[16000];
+        let dashscope: &[u32] = &[16000, 8000];
+        let whisper: &[u32] = &[16000, 8000, 22050, 24000, 32000, 44100, 48000];
+
+        let cases = [
+            (default_only, 16000, 16000),
+            (default_only, 48000, 16000),
+            (default_only, 8000, 16000),
+            (dashscope, 8000, 8000),
+            (dashscope, 44100, 16000),
+            (whisper, 44100, 44100),
+            (whisper, 48000, 48000),
+            (whisper, 96000, 16000),
+            (&[], 44100, 44100),
+        ];
+        for (supported, source, expected) in cases {
+            assert_eq!(
+                select_sample_rate(supported, source),
+                expected,
+                "supported={:?}, source={}",
+                supported,
+                source
+            );
+        }
+    }
+}
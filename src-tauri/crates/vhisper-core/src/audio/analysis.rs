@@ -0,0 +1,83 @@
+//! 录音质量分析
+//!
+//! 计算削波比例、直流偏移、RMS 等统计信息，并生成降采样波形用于界面绘制
+
+use serde::Serialize;
+
+/// 达到此幅度视为削波（i16 转换后正向最大值略小于 1.0）
+const CLIPPING_THRESHOLD: f32 = 0.999;
+
+/// 录音分析结果
+#[derive(Debug, Clone, Serialize)]
+pub struct AudioAnalysis {
+    pub sample_rate: u32,
+    pub channels: u16,
+    /// 录音时长（毫秒）
+    pub duration_ms: u64,
+    /// 峰值幅度（0.0 - 1.0）
+    pub max_amplitude: f32,
+    /// 均方根幅度
+    pub rms: f32,
+    /// 直流偏移（采样均值）
+    pub dc_offset: f32,
+    /// 削波采样占比（百分比）
+    pub clipping_percent: f32,
+    /// 降采样波形，每个点为对应区间的峰值幅度
+    pub waveform: Vec<f32>,
+}
+
+/// 峰值幅度
+pub fn peak_amplitude(samples: &[f32]) -> f32 {
+    samples.iter().map(|s| s.abs()).fold(0.0f32, f32::max)
+}
+
+/// 均方根幅度
+pub fn rms_amplitude(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let sum_sq: f64 = samples.iter().map(|&s| (s as f64) * (s as f64)).sum();
+    (sum_sq / samples.len() as f64).sqrt() as f32
+}
+
+/// 分析交错排列的多声道录音数据，波形降采样为 `waveform_points` 个点
+pub fn analyze_samples(
+    samples: &[f32],
+    sample_rate: u32,
+    channels: u16,
+    waveform_points: usize,
+) -> AudioAnalysis {
+    let channels = channels.max(1);
+    let frames = samples.len() / channels as usize;
+    let duration_ms = if sample_rate == 0 {
+        0
+    } else {
+        frames as u64 * 1000 / sample_rate as u64
+    };
+
+    let count = samples.len().max(1) as f64;
+    let sum: f64 = samples.iter().map(|&s| s as f64).sum();
+    let sum_sq: f64 = samples.iter().map(|&s| (s as f64) * (s as f64)).sum();
+    let clipped = samples
+        .iter()
+        .filter(|s| s.abs() >= CLIPPING_THRESHOLD)
+        .count();
+
+    // 按帧分桶，每个桶取所有声道的峰值
+    let bucket_frames = frames.div_ceil(waveform_points.max(1)).max(1);
+    let waveform = samples
+        .chunks(bucket_frames * channels as usize)
+        .map(peak_amplitude)
+        .collect();
+
+    AudioAnalysis {
+        sample_rate,
+        channels,
+        duration_ms,
+        max_amplitude: peak_amplitude(samples),
+        rms: (sum_sq / count).sqrt() as f32,
+        dc_offset: (sum / count) as f32,
+        clipping_percent: (clipped as f64 * 100.0 / count) as f32,
+        waveform,
+    }
+}
@@ -0,0 +1,103 @@
+//! 音频文件解码
+//!
+//! WAV 使用 hound 读取，MP3 等压缩格式使用 symphonia 解码，统一输出单声道 f32 采样
+
+use std::io::Cursor;
+use std::path::Path;
+
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+use super::{downmix_to_mono, read_wav, AudioError};
+
+/// 解码内存中的音频数据，返回单声道 f32 采样数据和采样率
+///
+/// `extension` 为文件扩展名提示（如 "mp3"），未知时传 `None` 由内容自动探测
+pub fn decode_audio(
+    data: &[u8],
+    extension: Option<&str>,
+) -> Result<(Vec<f32>, u32), AudioError> {
+    if data.starts_with(b"RIFF") {
+        return read_wav(Cursor::new(data));
+    }
+
+    let source = MediaSourceStream::new(
+        Box::new(Cursor::new(data.to_vec())),
+        Default::default(),
+    );
+    let mut hint = Hint::new();
+    if let Some(ext) = extension {
+        hint.with_extension(ext);
+    }
+
+    let probed = symphonia::default::get_probe()
+        .format(
+            &hint,
+            source,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|e| AudioError::Encoding(format!("Unsupported audio format: {}", e)))?;
+    let mut format = probed.format;
+
+    let track = format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| AudioError::Encoding("No audio track found".to_string()))?;
+    let track_id = track.id;
+    let mut sample_rate = track.codec_params.sample_rate;
+
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|e| AudioError::Encoding(e.to_string()))?;
+
+    let mut mono = Vec::new();
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                break
+            }
+            Err(e) => return Err(AudioError::Encoding(e.to_string())),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // 单个损坏的帧跳过即可
+            Err(SymphoniaError::DecodeError(e)) => {
+                tracing::warn!("Skipping undecodable audio packet: {}", e);
+                continue;
+            }
+            Err(e) => return Err(AudioError::Encoding(e.to_string())),
+        };
+
+        let spec = *decoded.spec();
+        sample_rate.get_or_insert(spec.rate);
+        let channels = spec.channels.count().max(1) as u16;
+
+        let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+        buffer.copy_interleaved_ref(decoded);
+        mono.extend(downmix_to_mono(buffer.samples(), channels));
+    }
+
+    let sample_rate =
+        sample_rate.ok_or_else(|| AudioError::Encoding("Unknown sample rate".to_string()))?;
+    Ok((mono, sample_rate))
+}
+
+/// 读取音频文件（WAV、MP3 等），返回单声道 f32 采样数据和采样率
+pub fn read_audio_file(path: impl AsRef<Path>) -> Result<(Vec<f32>, u32), AudioError> {
+    let path = path.as_ref();
+    let data = std::fs::read(path).map_err(|e| AudioError::Encoding(e.to_string()))?;
+    let extension = path.extension().and_then(|ext| ext.to_str());
+    decode_audio(&data, extension)
+}
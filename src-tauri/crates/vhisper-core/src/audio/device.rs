@@ -0,0 +1,75 @@
+//! 输入设备监控
+//!
+//! cpal 没有跨平台的设备变更通知，这里通过定期轮询默认输入设备名称实现
+
+use cpal::traits::{DeviceTrait, HostTrait};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// 获取当前默认输入设备名称
+pub fn default_input_device_name() -> Option<String> {
+    let host = cpal::default_host();
+    host.default_input_device().and_then(|d| d.name().ok())
+}
+
+/// 默认输入设备监控器
+///
+/// drop 时自动停止后台轮询线程
+pub struct DeviceMonitor {
+    stopped: Arc<AtomicBool>,
+    worker_handle: Option<JoinHandle<()>>,
+}
+
+impl DeviceMonitor {
+    /// 启动监控，默认输入设备变化时调用 `on_change`（参数为新设备名称）
+    pub fn spawn<F>(interval: Duration, on_change: F) -> Self
+    where
+        F: Fn(Option<String>) + Send + 'static,
+    {
+        let stopped = Arc::new(AtomicBool::new(false));
+        let stopped_clone = stopped.clone();
+
+        let handle = thread::spawn(move || {
+            let mut last_name = default_input_device_name();
+            tracing::info!("Device monitor started, current input: {:?}", last_name);
+
+            while !stopped_clone.load(Ordering::SeqCst) {
+                thread::sleep(interval);
+
+                let current_name = default_input_device_name();
+                if current_name != last_name {
+                    tracing::info!(
+                        "Default input device changed: {:?} -> {:?}",
+                        last_name,
+                        current_name
+                    );
+                    last_name = current_name.clone();
+                    on_change(current_name);
+                }
+            }
+
+            tracing::info!("Device monitor stopped");
+        });
+
+        Self {
+            stopped,
+            worker_handle: Some(handle),
+        }
+    }
+
+    /// 停止监控
+    pub fn stop(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.worker_handle.take() {
+            handle.join().ok();
+        }
+    }
+}
+
+impl Drop for DeviceMonitor {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
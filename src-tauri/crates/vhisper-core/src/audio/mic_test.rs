@@ -0,0 +1,44 @@
+//! 麦克风测试
+//!
+//! 使用独立的录音器录制一小段音频并统计幅度，不影响语音管道的录音会话
+
+use serde::Serialize;
+use std::thread;
+use std::time::Duration;
+
+use super::analysis::peak_amplitude;
+use super::{AudioError, AudioRecorder, StreamFormat};
+
+/// 麦克风测试结果
+#[derive(Debug, Clone, Serialize)]
+pub struct MicTestResult {
+    /// 峰值幅度（0.0 - 1.0）
+    pub max_amplitude: f32,
+    /// 平均幅度（绝对值均值）
+    pub avg_amplitude: f32,
+    pub sample_rate: u32,
+    /// 实际使用的输入设备名称
+    pub device_name: Option<String>,
+    /// 设备实际使用的录音格式
+    pub device_format: Option<StreamFormat>,
+}
+
+/// 录制 `duration` 时长的音频并返回幅度统计（阻塞调用线程）
+pub fn test_microphone(duration: Duration) -> Result<MicTestResult, AudioError> {
+    let mut recorder = AudioRecorder::new()?;
+    recorder.start()?;
+    thread::sleep(duration);
+    let samples = recorder.stop()?;
+
+    Ok(MicTestResult {
+        max_amplitude: peak_amplitude(&samples),
+        avg_amplitude: if samples.is_empty() {
+            0.0
+        } else {
+            samples.iter().map(|s| s.abs()).sum::<f32>() / samples.len() as f32
+        },
+        sample_rate: recorder.sample_rate(),
+        device_name: recorder.device_name(),
+        device_format: recorder.device_format().cloned(),
+    })
+}
@@ -1,8 +1,20 @@
+mod analysis;
+mod decode;
+mod device;
+mod mic_test;
 mod recorder;
 
-pub use recorder::AudioRecorder;
+pub use analysis::{analyze_samples, peak_amplitude, rms_amplitude, AudioAnalysis};
+pub use decode::{decode_audio, read_audio_file};
+pub use device::{default_input_device_name, DeviceMonitor};
+pub use mic_test::{test_microphone, MicTestResult};
+pub use recorder::{
+    AudioRecorder, ChunkSender, LevelCallback, MaxDurationCallback, SilenceCallback, StreamFormat,
+    SupportedConfig,
+};
 
 use std::io::Cursor;
+use std::path::Path;
 
 #[derive(Debug, thiserror::Error)]
 pub enum AudioError {
@@ -27,6 +39,17 @@ pub fn encode_to_pcm(samples: &[f32]) -> Vec<u8> {
     pcm_data
 }
 
+/// 将交错排列的多声道数据混合为单声道（各声道取平均），单声道数据原样返回
+pub fn downmix_to_mono(samples: &[f32], channels: u16) -> Vec<f32> {
+    if channels <= 1 {
+        return samples.to_vec();
+    }
+    samples
+        .chunks(channels as usize)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect()
+}
+
 /// 将 f32 采样数据编码为 WAV 格式
 pub fn encode_to_wav(samples: &[f32], sample_rate: u32, channels: u16) -> Result<Vec<u8>, AudioError> {
     let spec = hound::WavSpec {
@@ -54,3 +77,95 @@ pub fn encode_to_wav(samples: &[f32], sample_rate: u32, channels: u16) -> Result
 
     Ok(cursor.into_inner())
 }
+
+/// 线性插值重采样（单声道）
+pub fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    if from_rate == to_rate || samples.is_empty() || from_rate == 0 || to_rate == 0 {
+        return samples.to_vec();
+    }
+
+    let ratio = from_rate as f64 / to_rate as f64;
+    let output_len = (samples.len() as f64 / ratio).round() as usize;
+    let last = samples.len() - 1;
+
+    (0..output_len)
+        .map(|i| {
+            let pos = i as f64 * ratio;
+            let index = (pos.floor() as usize).min(last);
+            let next = (index + 1).min(last);
+            let frac = (pos - index as f64) as f32;
+            samples[index] + (samples[next] - samples[index]) * frac
+        })
+        .collect()
+}
+
+/// 读取 WAV 文件，返回单声道 f32 采样数据和采样率
+///
+/// 多声道文件会混合为单声道
+pub fn read_wav_file(path: impl AsRef<Path>) -> Result<(Vec<f32>, u32), AudioError> {
+    let file = std::fs::File::open(path).map_err(|e| AudioError::Encoding(e.to_string()))?;
+    read_wav(std::io::BufReader::new(file))
+}
+
+/// 从任意数据源读取 WAV，多声道混合为单声道
+pub(crate) fn read_wav<R: std::io::Read>(reader: R) -> Result<(Vec<f32>, u32), AudioError> {
+    let mut reader =
+        hound::WavReader::new(reader).map_err(|e| AudioError::Encoding(e.to_string()))?;
+    let spec = reader.spec();
+
+    let samples: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader
+            .samples::<f32>()
+            .collect::<Result<_, _>>()
+            .map_err(|e| AudioError::Encoding(e.to_string()))?,
+        hound::SampleFormat::Int => {
+            let scale = (1i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|s| s.map(|v| v as f32 / scale))
+                .collect::<Result<_, _>>()
+                .map_err(|e| AudioError::Encoding(e.to_string()))?
+        }
+    };
+
+    let mono = if spec.channels > 1 {
+        downmix_to_mono(&samples, spec.channels)
+    } else {
+        samples
+    };
+
+    Ok((mono, spec.sample_rate))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resample_length_matches_rate_ratio() {
+        let samples: Vec<f32> = (0..48000).map(|i| (i as f32 / 100.0).sin()).collect();
+        for (from_rate, to_rate, expected) in [
+            (48000, 16000, 16000),
+            (44100, 16000, 17415),
+            (16000, 48000, 144000),
+            (16000, 16000, 48000),
+        ] {
+            assert_eq!(
+                resample(&samples, from_rate, to_rate).len(),
+                expected,
+                "{} -> {}",
+                from_rate,
+                to_rate
+            );
+        }
+    }
+
+    #[test]
+    fn downmix_averages_interleaved_stereo() {
+        let stereo = [1.0, 0.0, 0.5, 0.5, -1.0, 0.0];
+        let mono = downmix_to_mono(&stereo, 2);
+        assert_eq!(mono.len(), stereo.len() / 2);
+        assert_eq!(mono, vec![0.5, 0.5, -0.5]);
+        assert_eq!(downmix_to_mono(&stereo, 1), stereo.to_vec());
+    }
+}
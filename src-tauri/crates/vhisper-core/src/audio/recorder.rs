@@ -1,15 +1,89 @@
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::SampleFormat;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, AtomicU16, Ordering};
 use std::sync::{Arc, Mutex, mpsc};
 use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
 
+use super::analysis::rms_amplitude;
+use super::device::default_input_device_name;
 use super::AudioError;
 
+/// 等待工作线程打开音频流的超时时间
+const STREAM_OPEN_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// 音量回调的最小间隔（约 20Hz）
+const LEVEL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 静音检测的 RMS 阈值，低于此值视为静音
+const SILENCE_RMS_THRESHOLD: f32 = 0.015;
+
+/// 可处理的采样格式，按优先级从高到低排列（16-bit 整数与识别所需格式一致）
+const PREFERRED_FORMATS: &[SampleFormat] = &[
+    SampleFormat::I16,
+    SampleFormat::F32,
+    SampleFormat::I32,
+    SampleFormat::U16,
+];
+
+/// 实时音量回调（参数为 0.0 - 1.0 的 RMS 幅度，在音频回调线程中调用）
+pub type LevelCallback = Arc<dyn Fn(f32) + Send + Sync>;
+
+/// 录音数据块发送端（在音频回调线程中发送新录到的样本，音频流停止时关闭）
+pub type ChunkSender = tokio::sync::mpsc::UnboundedSender<Vec<f32>>;
+
+/// 录音达到最长时长时的回调（在看门狗线程中调用）
+pub type MaxDurationCallback = Arc<dyn Fn() + Send + Sync>;
+
+/// 说话后持续静音、自动停止采集时的回调（在静音监视线程中调用）
+pub type SilenceCallback = Arc<dyn Fn() + Send + Sync>;
+
 /// 录音控制命令
 enum RecorderCommand {
     Start,
     Stop,
 }
 
+/// 输入设备支持的一组录音格式（采样率为范围）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SupportedConfig {
+    pub min_sample_rate: u32,
+    pub max_sample_rate: u32,
+    pub channels: u16,
+    /// 采样格式（如 "i16"、"f32"）
+    pub sample_format: String,
+}
+
+impl From<cpal::SupportedStreamConfigRange> for SupportedConfig {
+    fn from(range: cpal::SupportedStreamConfigRange) -> Self {
+        Self {
+            min_sample_rate: range.min_sample_rate().0,
+            max_sample_rate: range.max_sample_rate().0,
+            channels: range.channels(),
+            sample_format: range.sample_format().to_string(),
+        }
+    }
+}
+
+/// 输入设备实际使用的录音格式
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StreamFormat {
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub sample_format: String,
+}
+
+impl From<&cpal::SupportedStreamConfig> for StreamFormat {
+    fn from(config: &cpal::SupportedStreamConfig) -> Self {
+        Self {
+            sample_rate: config.sample_rate().0,
+            channels: config.channels(),
+            sample_format: config.sample_format().to_string(),
+        }
+    }
+}
+
 /// 录音状态
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum RecordingState {
@@ -21,10 +95,37 @@ pub enum RecordingState {
 pub struct AudioRecorder {
     buffer: Arc<Mutex<Vec<f32>>>,
     sample_rate: u32,
-    channels: u16,
+    /// 实际输出声道数（由工作线程按设备能力设置）
+    channels: Arc<AtomicU16>,
+    /// 设备为双声道时保留左右声道（不混合为单声道）
+    keep_stereo: bool,
     state: Arc<Mutex<RecordingState>>,
     command_tx: Option<mpsc::Sender<RecorderCommand>>,
     worker_handle: Option<JoinHandle<()>>,
+    /// 上次录音使用的输入设备名称
+    device_name: Arc<Mutex<Option<String>>>,
+    /// 默认输入设备已变更，下次 start() 时重新初始化
+    device_changed: Arc<AtomicBool>,
+    /// 缓冲区预分配容量（样本数）
+    buffer_capacity: usize,
+    /// 设备实际使用的录音格式（录音数据已转换为 f32 并重采样到 `sample_rate`）
+    device_format: Option<StreamFormat>,
+    /// 最长录音时长，None 表示不限制
+    max_duration: Option<Duration>,
+    max_duration_callback: Option<MaxDurationCallback>,
+    /// 说话后持续静音自动停止，None 表示不检测
+    silence_stop: Option<SilenceStop>,
+    silence_callback: Option<SilenceCallback>,
+    /// 看门狗取消通道，stop() 时释放
+    watchdog_tx: Option<mpsc::Sender<()>>,
+    /// 实时音量回调，随音频流一起释放
+    level_callback: Option<LevelCallback>,
+    /// 录音数据块发送端，仅对下一次 start() 生效
+    chunk_sender: Option<ChunkSender>,
+    /// 预录时长，0 表示不预录
+    preroll_ms: u32,
+    /// 空闲时运行的预录流
+    preroll: Option<Preroll>,
 }
 
 impl AudioRecorder {
@@ -33,13 +134,197 @@ impl AudioRecorder {
         Ok(Self {
             buffer: Arc::new(Mutex::new(Vec::new())),
             sample_rate: 16000, // Whisper 需要 16kHz
-            channels: 1,       // 单声道
+            channels: Arc::new(AtomicU16::new(1)), // 默认单声道
+            keep_stereo: false,
             state: Arc::new(Mutex::new(RecordingState::Idle)),
             command_tx: None,
             worker_handle: None,
+            device_name: Arc::new(Mutex::new(None)),
+            device_changed: Arc::new(AtomicBool::new(false)),
+            buffer_capacity: 0,
+            device_format: None,
+            max_duration: None,
+            max_duration_callback: None,
+            silence_stop: None,
+            silence_callback: None,
+            watchdog_tx: None,
+            level_callback: None,
+            chunk_sender: None,
+            preroll_ms: 0,
+            preroll: None,
         })
     }
 
+    /// 设置缓冲区预分配时长（秒），下次 start() 时生效
+    pub fn set_preallocate_secs(&mut self, secs: u32) {
+        let channels = if self.keep_stereo { 2 } else { 1 };
+        self.buffer_capacity = secs as usize * self.sample_rate as usize * channels;
+    }
+
+    /// 设置是否保留双声道数据，下次 start() 时生效
+    ///
+    /// 开启后若设备至少有两个声道，缓冲区中为交错排列的左右声道样本
+    pub fn set_keep_stereo(&mut self, keep: bool) {
+        self.keep_stereo = keep;
+    }
+
+    /// 设置最长录音时长（秒，0 表示不限制），下次 start() 时生效
+    ///
+    /// 超时后停止采集，已录数据保留到 stop() 时返回，并调用 `callback`
+    pub fn set_max_duration(&mut self, secs: u64, callback: Option<MaxDurationCallback>) {
+        self.max_duration = (secs > 0).then(|| Duration::from_secs(secs));
+        self.max_duration_callback = callback;
+    }
+
+    /// 设置静音自动停止（毫秒，`timeout_ms` 为 0 表示不检测），下次 start() 时生效
+    ///
+    /// 累计检测到 `min_speech_ms` 的语音后，持续静音超过 `timeout_ms` 时停止采集，
+    /// 已录数据保留到 stop() 时返回，并调用 `callback`
+    pub fn set_silence_stop(
+        &mut self,
+        timeout_ms: u64,
+        min_speech_ms: u64,
+        callback: Option<SilenceCallback>,
+    ) {
+        self.silence_stop = (timeout_ms > 0).then(|| SilenceStop {
+            timeout: Duration::from_millis(timeout_ms),
+            min_speech: Duration::from_millis(min_speech_ms),
+        });
+        self.silence_callback = callback;
+    }
+
+    /// 设置预录时长（毫秒，0 表示关闭）
+    ///
+    /// 开启后空闲时也保持麦克风打开，只保留最近 `ms` 毫秒的音频；start() 时补到录音开头，
+    /// 同时补上音频流启动期间的声音，避免丢掉第一个字
+    pub fn set_preroll_ms(&mut self, ms: u32) {
+        if ms == self.preroll_ms {
+            return;
+        }
+        self.preroll_ms = ms;
+        self.preroll = None;
+        if !self.is_recording() {
+            self.start_preroll();
+        }
+    }
+
+    /// 启动预录流（未开启预录时不做任何事）
+    fn start_preroll(&mut self) {
+        if self.preroll_ms == 0 {
+            return;
+        }
+        let channels = if self.keep_stereo { 2 } else { 1 };
+        let samples = self.preroll_ms as usize * self.sample_rate as usize / 1000 * channels;
+        self.preroll = Some(Preroll::spawn(self.sample_rate, self.keep_stereo, samples));
+    }
+
+    /// 将预录的音频补到录音缓冲区开头
+    ///
+    /// 预录流在录音流启动后才停止，两者重叠的部分（录音缓冲区中已有的样本）不重复补入；
+    /// 录音流此前暂存的数据块随后连同预录音频一次发送，保证接收端按时间顺序收到
+    fn prepend_preroll(&self, preroll: Preroll, live_chunks: Option<&LiveChunks>) {
+        let preroll_channels = preroll.channels.load(Ordering::SeqCst);
+        let samples = preroll.finish();
+        let channels = self.channels.load(Ordering::SeqCst);
+
+        // 持有缓冲区锁期间录音回调不会写入或发送，发送顺序与缓冲区一致
+        let mut buffer = self.buffer.lock().unwrap();
+        let mut len = if preroll_channels == channels {
+            samples.len().saturating_sub(buffer.len())
+        } else {
+            0
+        };
+        len -= len % channels.max(1) as usize;
+        if len > 0 {
+            buffer.splice(0..0, samples[..len].iter().copied());
+            tracing::info!("Prepended {} pre-roll samples", len);
+        }
+
+        if let Some(live) = live_chunks {
+            if !buffer.is_empty() {
+                let _ = live.sender.send(buffer.clone());
+            }
+            live.held.store(false, Ordering::SeqCst);
+        }
+    }
+
+    /// 设置实时音量回调，下次 start() 时生效
+    pub fn set_level_callback(&mut self, callback: Option<LevelCallback>) {
+        self.level_callback = callback;
+    }
+
+    /// 设置录音数据块发送端，仅对下一次 start() 生效（用于边录音边识别）
+    ///
+    /// 新录到的样本（重采样后，格式与缓冲区一致）在写入缓冲区的同时发送，
+    /// 录音停止后发送端随音频流释放，接收端随之结束
+    pub fn set_chunk_sender(&mut self, sender: Option<ChunkSender>) {
+        self.chunk_sender = sender;
+    }
+
+    /// 通知录音器默认输入设备已变更
+    ///
+    /// 不会打断当前录音，下次 start() 时重新初始化
+    pub fn notify_device_changed(&self) {
+        self.device_changed.store(true, Ordering::SeqCst);
+    }
+
+    /// 获取设备实际使用的采样率
+    pub fn device_sample_rate(&self) -> Option<u32> {
+        self.device_format.as_ref().map(|format| format.sample_rate)
+    }
+
+    /// 获取设备实际使用的录音格式
+    pub fn device_format(&self) -> Option<&StreamFormat> {
+        self.device_format.as_ref()
+    }
+
+    /// 默认输入设备支持的录音格式（没有设备或查询失败时为空）
+    pub fn supported_configs() -> Vec<SupportedConfig> {
+        let Some(device) = cpal::default_host().default_input_device() else {
+            return Vec::new();
+        };
+        match device.supported_input_configs() {
+            Ok(configs) => configs.map(SupportedConfig::from).collect(),
+            Err(e) => {
+                tracing::warn!("Failed to query supported input configs: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// 获取上次录音使用的输入设备名称
+    pub fn device_name(&self) -> Option<String> {
+        self.device_name.lock().unwrap().clone()
+    }
+
+    /// 停止旧的工作线程并清除设备信息
+    fn stop_worker(&mut self) {
+        if let Some(tx) = self.command_tx.take() {
+            tx.send(RecorderCommand::Stop).ok();
+        }
+        if let Some(handle) = self.worker_handle.take() {
+            handle.join().ok();
+        }
+        *self.device_name.lock().unwrap() = None;
+        self.device_format = None;
+    }
+
+    /// 默认输入设备变更后重新初始化
+    ///
+    /// 释放旧设备上的工作线程和预录流（预录数据丢弃），在新的默认设备上重建预录流；
+    /// 录音流由随后的 `spawn_worker` 在新设备上打开
+    fn reinit(&mut self) {
+        self.stop_worker();
+        if let Some(preroll) = self.preroll.take() {
+            preroll.finish();
+        }
+        self.start_preroll();
+        tracing::info!(
+            "Recorder re-initialized for new input device: {:?}",
+            default_input_device_name()
+        );
+    }
+
     /// 开始录音
     pub fn start(&mut self) -> Result<(), AudioError> {
         {
@@ -49,24 +334,148 @@ impl AudioRecorder {
             }
         }
 
-        // 清空缓冲区
+        // 发送端只交给本次录音的音频流，录音停止时随之释放
+        let chunk_sender = self.chunk_sender.take();
+
+        // 默认设备变更（收到通知或名称不一致）时重新初始化，旧设备的预录数据丢弃
+        let last_device = self.device_name();
+        let changed = self.device_changed.swap(false, Ordering::SeqCst)
+            || (last_device.is_some() && default_input_device_name() != last_device);
+        if changed {
+            self.reinit();
+        }
+
+        // 预录流保持运行到录音流启动，期间的音频全部保留
+        let preroll = self.preroll.take();
+        if let Some(preroll) = &preroll {
+            preroll.hold.store(true, Ordering::SeqCst);
+        }
+        // 有预录音频时先暂存录音流的数据块，等预录音频补到开头后再发送
+        let live_chunks = chunk_sender.map(|sender| LiveChunks {
+            sender,
+            held: Arc::new(AtomicBool::new(preroll.is_some())),
+        });
+
+        // 清空缓冲区（保留已分配的容量，复用于连续录音）
         {
             let mut buffer = self.buffer.lock().unwrap();
             buffer.clear();
+            buffer.reserve(self.buffer_capacity);
         }
 
+        // 静音检测器随音频流一起释放，监视线程随之结束
+        let (silence_detector, silence_rx) = match self.silence_stop {
+            Some(stop) => {
+                let (tx, rx) = mpsc::channel::<()>();
+                (Some(SilenceDetector::new(stop, tx)), Some(rx))
+            }
+            None => (None, None),
+        };
+
+        // 打开失败时（如设备能力变化）重新初始化并重试一次
+        if let Err(e) = self.spawn_worker(live_chunks.clone(), silence_detector.clone()) {
+            tracing::warn!("Failed to open input stream: {}, retrying once", e);
+            self.stop_worker();
+            if let Err(e) = self.spawn_worker(live_chunks.clone(), silence_detector) {
+                drop(preroll);
+                self.start_preroll();
+                return Err(e);
+            }
+        }
+
+        if let Some(preroll) = preroll {
+            self.prepend_preroll(preroll, live_chunks.as_ref());
+        }
+
+        {
+            let mut state = self.state.lock().unwrap();
+            *state = RecordingState::Recording;
+        }
+        self.spawn_watchdog();
+        if let Some(silence_rx) = silence_rx {
+            self.spawn_silence_monitor(silence_rx);
+        }
+
+        tracing::info!("Recording started");
+        Ok(())
+    }
+
+    /// 启动看门狗线程：录音超过最长时长时停止采集
+    fn spawn_watchdog(&mut self) {
+        let (Some(limit), Some(command_tx)) = (self.max_duration, self.command_tx.clone()) else {
+            return;
+        };
+        let (cancel_tx, cancel_rx) = mpsc::channel::<()>();
+        self.watchdog_tx = Some(cancel_tx);
+        let callback = self.max_duration_callback.clone();
+
+        thread::spawn(move || {
+            // stop() 释放发送端时立即返回（Disconnected）
+            if let Err(mpsc::RecvTimeoutError::Timeout) = cancel_rx.recv_timeout(limit) {
+                tracing::warn!("Recording exceeded {:?}, stopping capture", limit);
+                command_tx.send(RecorderCommand::Stop).ok();
+                if let Some(callback) = callback {
+                    callback();
+                }
+            }
+        });
+    }
+
+    /// 启动静音监视线程：检测器通知持续静音时停止采集
+    fn spawn_silence_monitor(&self, silence_rx: mpsc::Receiver<()>) {
+        let Some(command_tx) = self.command_tx.clone() else {
+            return;
+        };
+        let callback = self.silence_callback.clone();
+
+        thread::spawn(move || {
+            // 音频流释放（录音停止）时检测器随之释放，recv 返回错误
+            if silence_rx.recv().is_ok() {
+                tracing::info!("Trailing silence detected, stopping capture");
+                command_tx.send(RecorderCommand::Stop).ok();
+                if let Some(callback) = callback {
+                    callback();
+                }
+            }
+        });
+    }
+
+    /// 启动工作线程并等待音频流打开
+    fn spawn_worker(
+        &mut self,
+        live_chunks: Option<LiveChunks>,
+        silence_detector: Option<SilenceDetector>,
+    ) -> Result<(), AudioError> {
         // 创建命令通道
         let (tx, rx) = mpsc::channel::<RecorderCommand>();
         self.command_tx = Some(tx);
+        let (ready_tx, ready_rx) = mpsc::channel::<Result<StreamFormat, AudioError>>();
 
         // 克隆需要的数据给工作线程
         let buffer = self.buffer.clone();
         let state = self.state.clone();
+        let device_name = self.device_name.clone();
+        let channels = self.channels.clone();
+        let keep_stereo = self.keep_stereo;
         let target_sample_rate = self.sample_rate;
+        let level_callback = self.level_callback.clone();
 
         // 启动工作线程
         let handle = thread::spawn(move || {
-            if let Err(e) = run_recording_loop(rx, buffer, state, target_sample_rate) {
+            if let Err(e) = run_recording_loop(
+                rx,
+                ready_tx,
+                buffer,
+                state,
+                device_name,
+                channels,
+                keep_stereo,
+                target_sample_rate,
+                level_callback,
+                live_chunks,
+                silence_detector,
+                None,
+            ) {
                 tracing::error!("Recording thread error: {}", e);
             }
         });
@@ -78,13 +487,24 @@ impl AudioRecorder {
             tx.send(RecorderCommand::Start).ok();
         }
 
-        {
-            let mut state = self.state.lock().unwrap();
-            *state = RecordingState::Recording;
+        match ready_rx.recv_timeout(STREAM_OPEN_TIMEOUT) {
+            Ok(Ok(format)) => {
+                self.device_format = Some(format);
+                Ok(())
+            }
+            Ok(Err(e)) => {
+                self.command_tx = None;
+                if let Some(handle) = self.worker_handle.take() {
+                    handle.join().ok();
+                }
+                Err(e)
+            }
+            Err(_) => {
+                // 设备打开较慢时不阻塞录音，流打开后照常写入缓冲区
+                tracing::warn!("Input stream not confirmed within {:?}", STREAM_OPEN_TIMEOUT);
+                Ok(())
+            }
         }
-
-        tracing::info!("Recording started");
-        Ok(())
     }
 
     /// 停止录音并返回音频数据
@@ -96,6 +516,9 @@ impl AudioRecorder {
             }
         }
 
+        // 取消看门狗
+        self.watchdog_tx = None;
+
         // 发送停止命令
         if let Some(tx) = self.command_tx.take() {
             tx.send(RecorderCommand::Stop).ok();
@@ -111,6 +534,9 @@ impl AudioRecorder {
             *state = RecordingState::Idle;
         }
 
+        // 为下一次录音重新开始预录
+        self.start_preroll();
+
         // 获取录制的数据
         let buffer = self.buffer.lock().unwrap();
         let data = buffer.clone();
@@ -119,6 +545,11 @@ impl AudioRecorder {
         Ok(data)
     }
 
+    /// 是否正在采集音频（stop 返回前一直为 true）
+    pub fn is_recording(&self) -> bool {
+        *self.state.lock().unwrap() == RecordingState::Recording
+    }
+
     /// 获取采样率
     pub fn sample_rate(&self) -> u32 {
         self.sample_rate
@@ -126,7 +557,7 @@ impl AudioRecorder {
 
     /// 获取声道数
     pub fn channels(&self) -> u16 {
-        self.channels
+        self.channels.load(Ordering::SeqCst)
     }
 
     /// 获取并清空当前缓冲区中的音频数据（用于流式处理）
@@ -134,7 +565,8 @@ impl AudioRecorder {
     /// 返回自上次调用以来录制的音频数据，并清空缓冲区
     pub fn drain_buffer(&self) -> Vec<f32> {
         let mut buffer = self.buffer.lock().unwrap();
-        std::mem::take(&mut *buffer)
+        // 使用 drain 保留缓冲区容量，避免每次重新分配
+        buffer.drain(..).collect()
     }
 
     /// 获取当前缓冲区大小（样本数）
@@ -149,36 +581,211 @@ impl Default for AudioRecorder {
     }
 }
 
-/// 在单独线程中运行录音循环
-fn run_recording_loop(
-    rx: mpsc::Receiver<RecorderCommand>,
+/// 空闲时持续录音的预录流，缓冲区只保留最近的样本
+struct Preroll {
     buffer: Arc<Mutex<Vec<f32>>>,
-    _state: Arc<Mutex<RecordingState>>,
-    target_sample_rate: u32,
-) -> Result<(), AudioError> {
-    // 等待开始命令
-    match rx.recv() {
-        Ok(RecorderCommand::Start) => {}
-        _ => return Ok(()),
+    /// 预录流实际输出的声道数
+    channels: Arc<AtomicU16>,
+    /// 开始录音后停止裁剪，保留录音流启动期间的音频
+    hold: Arc<AtomicBool>,
+    command_tx: mpsc::Sender<RecorderCommand>,
+    handle: JoinHandle<()>,
+}
+
+impl Preroll {
+    /// 在后台线程中打开预录流，不等待音频流就绪
+    fn spawn(target_sample_rate: u32, keep_stereo: bool, max_samples: usize) -> Self {
+        let buffer = Arc::new(Mutex::new(Vec::with_capacity(max_samples)));
+        let channels = Arc::new(AtomicU16::new(1));
+        let hold = Arc::new(AtomicBool::new(false));
+        let (command_tx, command_rx) = mpsc::channel::<RecorderCommand>();
+        let ring = RingLimit {
+            max_samples,
+            hold: hold.clone(),
+        };
+
+        let thread_buffer = buffer.clone();
+        let thread_channels = channels.clone();
+        let handle = thread::spawn(move || {
+            let (ready_tx, _ready_rx) = mpsc::channel();
+            if let Err(e) = run_recording_loop(
+                command_rx,
+                ready_tx,
+                thread_buffer,
+                Arc::new(Mutex::new(RecordingState::Idle)),
+                Arc::new(Mutex::new(None)),
+                thread_channels,
+                keep_stereo,
+                target_sample_rate,
+                None,
+                None,
+                None,
+                Some(ring),
+            ) {
+                tracing::warn!("Pre-roll thread error: {}", e);
+            }
+        });
+        command_tx.send(RecorderCommand::Start).ok();
+
+        Self {
+            buffer,
+            channels,
+            hold,
+            command_tx,
+            handle,
+        }
     }
 
-    let host = cpal::default_host();
-    let device = host
-        .default_input_device()
-        .ok_or(AudioError::NoInputDevice)?;
+    /// 停止预录流，返回缓冲的音频
+    fn finish(self) -> Vec<f32> {
+        self.command_tx.send(RecorderCommand::Stop).ok();
+        self.handle.join().ok();
+        std::mem::take(&mut *self.buffer.lock().unwrap())
+    }
+}
 
-    let config = device
-        .default_input_config()
-        .map_err(|e| AudioError::Device(e.to_string()))?;
+/// 预录缓冲区长度限制：空闲时只保留最近 `max_samples` 个样本
+#[derive(Clone)]
+struct RingLimit {
+    max_samples: usize,
+    hold: Arc<AtomicBool>,
+}
 
-    tracing::info!(
-        "Using input device: {:?}, config: {:?}",
-        device.name(),
-        config
-    );
+/// 录音流的数据块发送端
+///
+/// `held` 为 true 时新样本只写入缓冲区、不发送，由 `prepend_preroll` 补入预录音频后统一发送
+#[derive(Clone)]
+struct LiveChunks {
+    sender: ChunkSender,
+    held: Arc<AtomicBool>,
+}
+
+/// 静音自动停止参数
+#[derive(Debug, Clone, Copy)]
+struct SilenceStop {
+    timeout: Duration,
+    min_speech: Duration,
+}
+
+/// 尾部静音检测（在音频回调线程中运行）
+///
+/// 累计语音时长达到 `min_speech` 后，持续静音超过 `timeout` 时通知一次；
+/// 开口前的静音和句中短暂停顿不会触发
+#[derive(Clone)]
+struct SilenceDetector {
+    stop: SilenceStop,
+    tx: mpsc::Sender<()>,
+    speech: Duration,
+    silence: Duration,
+    notified: bool,
+}
+
+impl SilenceDetector {
+    fn new(stop: SilenceStop, tx: mpsc::Sender<()>) -> Self {
+        Self {
+            stop,
+            tx,
+            speech: Duration::ZERO,
+            silence: Duration::ZERO,
+            notified: false,
+        }
+    }
+
+    /// 处理一段时长为 `duration` 的音频
+    fn process(&mut self, data: &[f32], duration: Duration) {
+        if self.notified {
+            return;
+        }
+        if rms_amplitude(data) >= SILENCE_RMS_THRESHOLD {
+            self.speech += duration;
+            self.silence = Duration::ZERO;
+            return;
+        }
+        self.silence += duration;
+        if self.speech >= self.stop.min_speech && self.silence >= self.stop.timeout {
+            self.notified = true;
+            self.tx.send(()).ok();
+        }
+    }
+}
 
+/// 从设备支持的配置中选择最接近目标的配置
+///
+/// 依次比较：声道数是否足够（保留双声道时需要两个声道）、与目标采样率的差距、
+/// 多余的声道数、`PREFERRED_FORMATS` 中的顺序；即优先 16kHz 单声道 i16
+fn negotiate_input_config(
+    device: &cpal::Device,
+    target_sample_rate: u32,
+    keep_stereo: bool,
+) -> Option<cpal::SupportedStreamConfig> {
+    let wanted_channels = if keep_stereo { 2 } else { 1 };
+    device
+        .supported_input_configs()
+        .ok()?
+        .filter_map(|range| {
+            let format_rank = PREFERRED_FORMATS
+                .iter()
+                .position(|format| *format == range.sample_format())?;
+            let rate = target_sample_rate.clamp(range.min_sample_rate().0, range.max_sample_rate().0);
+            Some((format_rank, range.with_sample_rate(cpal::SampleRate(rate))))
+        })
+        .min_by_key(|(format_rank, config)| {
+            (
+                config.channels() < wanted_channels,
+                config.sample_rate().0.abs_diff(target_sample_rate),
+                config.channels().abs_diff(wanted_channels),
+                *format_rank,
+            )
+        })
+        .map(|(_, config)| config)
+}
+
+/// 按设备的采样格式构建输入流，采样统一转换为 f32 后交给 `process`
+fn build_typed_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    mut process: impl FnMut(&[f32]) + Send + 'static,
+) -> Result<cpal::Stream, cpal::BuildStreamError>
+where
+    T: cpal::SizedSample,
+    f32: cpal::FromSample<T>,
+{
+    // 复用转换缓冲区，避免每次回调分配内存
+    let mut converted = Vec::new();
+    device.build_input_stream(
+        config,
+        move |data: &[T], _: &cpal::InputCallbackInfo| {
+            converted.clear();
+            converted.extend(data.iter().map(|&sample| sample.to_sample::<f32>()));
+            process(&converted);
+        },
+        |err| {
+            tracing::error!("Audio stream error: {}", err);
+        },
+        None,
+    )
+}
+
+/// 按给定配置构建输入流，数据转换为单声道（或保留左右声道）并重采样到目标采样率
+///
+/// 设置了 `level_callback` 时按 `LEVEL_INTERVAL` 汇报这段时间内的 RMS 音量
+#[allow(clippy::too_many_arguments)]
+fn build_stream(
+    device: &cpal::Device,
+    config: &cpal::SupportedStreamConfig,
+    buffer: Arc<Mutex<Vec<f32>>>,
+    output_channels: &AtomicU16,
+    keep_stereo: bool,
+    target_sample_rate: u32,
+    level_callback: Option<LevelCallback>,
+    live_chunks: Option<LiveChunks>,
+    mut silence_detector: Option<SilenceDetector>,
+    ring_limit: Option<RingLimit>,
+) -> Result<cpal::Stream, cpal::BuildStreamError> {
     let source_sample_rate = config.sample_rate().0;
     let channels = config.channels() as usize;
+    let stereo = keep_stereo && channels >= 2;
+    output_channels.store(if stereo { 2 } else { 1 }, Ordering::SeqCst);
 
     // 计算精确的重采样比率
     let resample_ratio = source_sample_rate as f64 / target_sample_rate as f64;
@@ -190,40 +797,205 @@ fn run_recording_loop(
         resample_ratio
     );
 
-    let buffer_clone = buffer.clone();
     // 使用浮点累加器实现精确重采样
     let accumulator = Arc::new(Mutex::new(0.0f64));
-    let accumulator_clone = accumulator.clone();
-
-    // 构建输入流
-    let stream = device
-        .build_input_stream(
-            &config.into(),
-            move |data: &[f32], _: &cpal::InputCallbackInfo| {
-                let mut buffer = buffer_clone.lock().unwrap();
-                let mut acc = accumulator_clone.lock().unwrap();
-
-                // 转换为单声道并精确重采样
-                for frame in data.chunks(channels) {
-                    let mono: f32 = frame.iter().sum::<f32>() / channels as f32;
-
-                    // 当累加器 >= 1.0 时输出一个样本
-                    *acc += 1.0 / resample_ratio;
-                    while *acc >= 1.0 {
-                        buffer.push(mono);
-                        *acc -= 1.0;
-                    }
+
+    // 上次汇报音量的时间，以及之后各回调缓冲区的 RMS（取最大值，避免短促语音被平均掉）
+    let mut last_level_at = Instant::now();
+    let mut pending_level = 0.0f32;
+
+    let process = move |data: &[f32]| {
+        if let Some(detector) = &mut silence_detector {
+            let frames = data.len() / channels.max(1);
+            let duration = Duration::from_secs_f64(frames as f64 / source_sample_rate as f64);
+            detector.process(data, duration);
+        }
+
+        if let Some(callback) = &level_callback {
+            pending_level = pending_level.max(rms_amplitude(data));
+            if last_level_at.elapsed() >= LEVEL_INTERVAL {
+                callback(pending_level.min(1.0));
+                pending_level = 0.0;
+                last_level_at = Instant::now();
+            }
+        }
+
+        let mut buffer = buffer.lock().unwrap();
+        let mut acc = accumulator.lock().unwrap();
+        let appended_from = buffer.len();
+
+        // 转换为单声道（或保留左右声道）并精确重采样
+        for frame in data.chunks(channels) {
+            let mono: f32 = frame.iter().sum::<f32>() / frame.len() as f32;
+
+            // 当累加器 >= 1.0 时输出一帧
+            *acc += 1.0 / resample_ratio;
+            while *acc >= 1.0 {
+                if stereo && frame.len() >= 2 {
+                    buffer.push(frame[0]);
+                    buffer.push(frame[1]);
+                } else if stereo {
+                    // 不完整的尾帧，复制到两个声道
+                    buffer.push(mono);
+                    buffer.push(mono);
+                } else {
+                    buffer.push(mono);
+                }
+                *acc -= 1.0;
+            }
+        }
+
+        // 预录：丢弃超出长度的旧样本（保持声道对齐）
+        if let Some(ring) = &ring_limit {
+            if !ring.hold.load(Ordering::Relaxed) {
+                let mut excess = buffer.len().saturating_sub(ring.max_samples);
+                if stereo {
+                    excess += excess % 2;
                 }
-            },
-            |err| {
-                tracing::error!("Audio stream error: {}", err);
-            },
-            None,
+                let excess = excess.min(buffer.len());
+                buffer.drain(..excess);
+            }
+        }
+
+        // 接收端已关闭（识别结束或取消）时忽略
+        if let Some(live) = &live_chunks {
+            if !live.held.load(Ordering::SeqCst) && buffer.len() > appended_from {
+                let _ = live.sender.send(buffer[appended_from..].to_vec());
+            }
+        }
+    };
+
+    let stream_config = config.config();
+    match config.sample_format() {
+        SampleFormat::I16 => build_typed_stream::<i16>(device, &stream_config, process),
+        SampleFormat::F32 => build_typed_stream::<f32>(device, &stream_config, process),
+        SampleFormat::I32 => build_typed_stream::<i32>(device, &stream_config, process),
+        SampleFormat::U16 => build_typed_stream::<u16>(device, &stream_config, process),
+        _ => Err(cpal::BuildStreamError::StreamConfigNotSupported),
+    }
+}
+
+/// 打开默认输入设备并开始录音，返回设备实际使用的录音格式
+#[allow(clippy::too_many_arguments)]
+fn open_stream(
+    buffer: &Arc<Mutex<Vec<f32>>>,
+    device_name: &Mutex<Option<String>>,
+    output_channels: &AtomicU16,
+    keep_stereo: bool,
+    target_sample_rate: u32,
+    level_callback: Option<LevelCallback>,
+    live_chunks: Option<LiveChunks>,
+    silence_detector: Option<SilenceDetector>,
+    ring_limit: Option<RingLimit>,
+) -> Result<(cpal::Stream, StreamFormat), AudioError> {
+    let host = cpal::default_host();
+    let device = host
+        .default_input_device()
+        .ok_or(AudioError::NoInputDevice)?;
+
+    tracing::info!("Using input device: {:?}", device.name());
+    *device_name.lock().unwrap() = device.name().ok();
+
+    // 优先使用协商出的配置（尽量 16kHz 单声道 i16），失败时退回设备默认配置
+    let mut candidates: Vec<cpal::SupportedStreamConfig> =
+        negotiate_input_config(&device, target_sample_rate, keep_stereo)
+            .into_iter()
+            .collect();
+    match device.default_input_config() {
+        Ok(config) if !candidates.contains(&config) => candidates.push(config),
+        Ok(_) => {}
+        Err(e) => tracing::warn!("Failed to get default input config: {}", e),
+    }
+    if candidates.is_empty() {
+        return Err(AudioError::Device("输入设备没有可用的录音格式".to_string()));
+    }
+
+    let build = |config: &cpal::SupportedStreamConfig| {
+        build_stream(
+            &device,
+            config,
+            buffer.clone(),
+            output_channels,
+            keep_stereo,
+            target_sample_rate,
+            level_callback.clone(),
+            live_chunks.clone(),
+            silence_detector.clone(),
+            ring_limit.clone(),
         )
-        .map_err(|e| AudioError::Stream(e.to_string()))?;
+    };
+
+    let mut last_error = String::new();
+    for config in &candidates {
+        let started = build(config)
+            .map_err(|e| e.to_string())
+            .and_then(|stream| stream.play().map(|()| stream).map_err(|e| e.to_string()));
+        match started {
+            Ok(stream) => {
+                let format = StreamFormat::from(config);
+                tracing::info!(
+                    "Audio stream playing with negotiated format: {}Hz, {} channels, {}",
+                    format.sample_rate,
+                    format.channels,
+                    format.sample_format
+                );
+                return Ok((stream, format));
+            }
+            Err(e) => {
+                tracing::warn!("Input config {:?} not usable: {}", config, e);
+                last_error = e;
+            }
+        }
+    }
+
+    Err(AudioError::Stream(last_error))
+}
 
-    stream.play().map_err(|e| AudioError::Stream(e.to_string()))?;
-    tracing::info!("Audio stream playing");
+/// 在单独线程中运行录音循环
+///
+/// 音频流打开的结果（设备实际使用的录音格式或错误）通过 `ready_tx` 通知调用方
+#[allow(clippy::too_many_arguments)]
+fn run_recording_loop(
+    rx: mpsc::Receiver<RecorderCommand>,
+    ready_tx: mpsc::Sender<Result<StreamFormat, AudioError>>,
+    buffer: Arc<Mutex<Vec<f32>>>,
+    _state: Arc<Mutex<RecordingState>>,
+    device_name: Arc<Mutex<Option<String>>>,
+    output_channels: Arc<AtomicU16>,
+    keep_stereo: bool,
+    target_sample_rate: u32,
+    level_callback: Option<LevelCallback>,
+    live_chunks: Option<LiveChunks>,
+    silence_detector: Option<SilenceDetector>,
+    ring_limit: Option<RingLimit>,
+) -> Result<(), AudioError> {
+    // 等待开始命令
+    match rx.recv() {
+        Ok(RecorderCommand::Start) => {}
+        _ => return Ok(()),
+    }
+
+    let stream = match open_stream(
+        &buffer,
+        &device_name,
+        &output_channels,
+        keep_stereo,
+        target_sample_rate,
+        level_callback,
+        live_chunks,
+        silence_detector,
+        ring_limit,
+    ) {
+        Ok((stream, format)) => {
+            ready_tx.send(Ok(format)).ok();
+            stream
+        }
+        Err(e) => {
+            tracing::error!("Failed to open input stream: {}", e);
+            ready_tx.send(Err(e)).ok();
+            return Ok(());
+        }
+    };
 
     // 等待停止命令
     loop {
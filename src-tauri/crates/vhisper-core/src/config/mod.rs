@@ -1,5 +1,12 @@
+pub mod provider;
 pub mod settings;
 pub mod storage;
 
-pub use settings::{AppConfig, HotkeyBinding, KeyCode};
-pub use storage::{load_config, save_config};
+pub use provider::{resolve_asr_provider, resolve_llm_provider};
+pub use settings::{
+    AppConfig, AppProfile, HotkeyAction, HotkeyBinding, HotkeyConfig, HotkeyMode, KeyCode,
+    OutputConfig, OutputMode, PastePosition, PasteShortcut, TextRules,
+};
+pub use storage::{
+    data_dir, load_config, resolve_data_path, save_config, set_data_dir, DATA_DIR_ENV,
+};
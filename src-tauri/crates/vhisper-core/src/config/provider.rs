@@ -0,0 +1,71 @@
+//! 服务商名称解析
+//!
+//! 配置文件可能被手动编辑，这里忽略大小写和分隔符，并接受常见别名
+
+/// ASR 服务商：规范名称及别名（别名已规范化为小写字母数字）
+const ASR_PROVIDERS: &[(&str, &[&str])] = &[
+    ("Qwen", &["qwenasr", "tongyi"]),
+    ("DashScope", &["paraformer", "aliyun"]),
+    ("OpenAIWhisper", &["openai", "whisper"]),
+    ("OpenAIRealtime", &["realtime"]),
+    ("Deepgram", &["nova"]),
+    ("Azure", &["azurespeech", "microsoft"]),
+    ("AssemblyAI", &["assembly"]),
+    ("FunAsr", &[]),
+    ("Sherpa", &["sherpaonnx", "local"]),
+];
+
+/// LLM 服务商：规范名称及别名
+const LLM_PROVIDERS: &[(&str, &[&str])] = &[
+    ("DashScope", &["qwen", "aliyun", "tongyi"]),
+    ("OpenAI", &["gpt", "chatgpt"]),
+    ("Ollama", &[]),
+    ("Claude", &["anthropic"]),
+    ("Gemini", &["google"]),
+];
+
+/// 去掉大小写和分隔符差异
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn resolve(name: &str, providers: &[(&'static str, &[&str])]) -> Option<&'static str> {
+    let normalized = normalize(name);
+    providers
+        .iter()
+        .find(|(canonical, aliases)| {
+            normalize(canonical) == normalized || aliases.contains(&normalized.as_str())
+        })
+        .map(|(canonical, _)| *canonical)
+}
+
+fn names(providers: &[(&'static str, &[&str])]) -> String {
+    providers
+        .iter()
+        .map(|(canonical, _)| *canonical)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// 解析 ASR 服务商名称为规范名称
+pub fn resolve_asr_provider(name: &str) -> Option<&'static str> {
+    resolve(name, ASR_PROVIDERS)
+}
+
+/// 解析 LLM 服务商名称为规范名称
+pub fn resolve_llm_provider(name: &str) -> Option<&'static str> {
+    resolve(name, LLM_PROVIDERS)
+}
+
+/// 所有 ASR 服务商的规范名称（用于错误提示）
+pub fn asr_provider_names() -> String {
+    names(ASR_PROVIDERS)
+}
+
+/// 所有 LLM 服务商的规范名称（用于错误提示）
+pub fn llm_provider_names() -> String {
+    names(LLM_PROVIDERS)
+}
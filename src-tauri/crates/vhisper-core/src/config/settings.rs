@@ -1,4 +1,6 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
 
 /// 键码枚举 - 支持所有常用键
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -87,6 +89,25 @@ impl KeyCode {
         )
     }
 
+    /// 判断是否是功能键（F1 - F12）
+    pub fn is_function_key(&self) -> bool {
+        matches!(
+            self,
+            KeyCode::F1
+                | KeyCode::F2
+                | KeyCode::F3
+                | KeyCode::F4
+                | KeyCode::F5
+                | KeyCode::F6
+                | KeyCode::F7
+                | KeyCode::F8
+                | KeyCode::F9
+                | KeyCode::F10
+                | KeyCode::F11
+                | KeyCode::F12
+        )
+    }
+
     /// 获取显示名称
     pub fn display_name(&self) -> &'static str {
         match self {
@@ -161,6 +182,43 @@ pub struct HotkeyBinding {
     /// 修饰键 (可选) - 需要同时按住的修饰键
     #[serde(default)]
     pub modifiers: Vec<KeyCode>,
+
+    /// 输出后保留识别结果在剪贴板（覆盖全局 restore_clipboard）
+    #[serde(default)]
+    pub keep_on_clipboard: bool,
+
+    /// 追加模式：粘贴前收起选区，始终在光标处插入（覆盖全局 paste_position）
+    #[serde(default)]
+    pub append_mode: bool,
+
+    /// 替换模式：用本次识别结果替换上次输出（覆盖全局 replace_last_output）
+    #[serde(default)]
+    pub replace_last: bool,
+
+    /// LLM 模型覆盖（为空时使用服务商配置中的模型）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_override: Option<String>,
+
+    /// 快捷键动作（默认按住录音）
+    #[serde(default)]
+    pub action: HotkeyAction,
+
+    /// 允许使用系统保留或影响正常输入的按键（跳过 `validate` 检查）
+    #[serde(default)]
+    pub allow_reserved: bool,
+}
+
+/// 快捷键动作
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "PascalCase")]
+pub enum HotkeyAction {
+    /// 按住录音，松开识别并输出
+    #[default]
+    Record,
+    /// 重新输出最近一次识别结果（粘贴未成功时使用）
+    RepeatLastOutput,
+    /// 取消当前录音或正在进行的识别（如绑定 Escape），不输出结果
+    Cancel,
 }
 
 impl Default for HotkeyBinding {
@@ -168,17 +226,114 @@ impl Default for HotkeyBinding {
         Self {
             key: KeyCode::Alt,
             modifiers: vec![],
+            keep_on_clipboard: false,
+            append_mode: false,
+            replace_last: false,
+            model_override: None,
+            action: HotkeyAction::default(),
+            allow_reserved: false,
         }
     }
 }
 
+/// 系统占用的组合键（各平台合并），绑定后会抢走系统功能或常用编辑操作
+const RESERVED_COMBOS: &[(&[KeyCode], KeyCode)] = &[
+    // Spotlight / Windows 搜索
+    (&[KeyCode::Meta], KeyCode::Space),
+    // 切换窗口
+    (&[KeyCode::Meta], KeyCode::Tab),
+    (&[KeyCode::Alt], KeyCode::Tab),
+    (&[KeyCode::Meta], KeyCode::Backquote),
+    // 关闭窗口、退出应用、锁屏
+    (&[KeyCode::Alt], KeyCode::F4),
+    (&[KeyCode::Meta], KeyCode::KeyQ),
+    (&[KeyCode::Meta], KeyCode::KeyW),
+    (&[KeyCode::Meta], KeyCode::KeyL),
+    (&[KeyCode::Control, KeyCode::Meta], KeyCode::KeyQ),
+    // 开始菜单
+    (&[KeyCode::Control], KeyCode::Escape),
+    // 全选、复制、粘贴、剪切、撤销
+    (&[KeyCode::Meta], KeyCode::KeyA),
+    (&[KeyCode::Meta], KeyCode::KeyC),
+    (&[KeyCode::Meta], KeyCode::KeyV),
+    (&[KeyCode::Meta], KeyCode::KeyX),
+    (&[KeyCode::Meta], KeyCode::KeyZ),
+    (&[KeyCode::Control], KeyCode::KeyA),
+    (&[KeyCode::Control], KeyCode::KeyC),
+    (&[KeyCode::Control], KeyCode::KeyV),
+    (&[KeyCode::Control], KeyCode::KeyX),
+    (&[KeyCode::Control], KeyCode::KeyZ),
+];
+
 impl HotkeyBinding {
+    /// 检查绑定是否可用：拒绝不合法的组合、单独的 Tab / Escape 等会干扰正常输入的按键和系统占用的组合键
+    ///
+    /// 开启 `allow_reserved` 时只检查组合是否合法
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(key) = self.modifiers.iter().find(|k| !k.is_modifier()) {
+            return Err(format!("{} 不是修饰键，不能用于组合键", key.display_name()));
+        }
+        let mut seen = Vec::with_capacity(self.modifiers.len());
+        for modifier in &self.modifiers {
+            if *modifier == self.key || seen.contains(&modifier) {
+                return Err(format!("快捷键 {} 中有重复的按键", self.display_text()));
+            }
+            seen.push(modifier);
+        }
+        if self.allow_reserved {
+            return Ok(());
+        }
+
+        if self.modifiers.is_empty() {
+            let blocked = match &self.key {
+                // 取消动作通常就是绑定 Escape
+                KeyCode::Escape => self.action != HotkeyAction::Cancel,
+                // 单独的修饰键、功能键和 CapsLock 不影响输入
+                key if key.is_modifier() || key.is_function_key() => false,
+                KeyCode::CapsLock => false,
+                // 字母、数字、Tab、空格等普通输入键
+                _ => true,
+            };
+            if blocked {
+                return Err(format!(
+                    "单独的 {} 会影响正常输入，请添加修饰键或换用其他按键",
+                    self.key.display_name()
+                ));
+            }
+        }
+
+        let reserved = RESERVED_COMBOS.iter().any(|(modifiers, key)| {
+            *key == self.key
+                && modifiers.len() == self.modifiers.len()
+                && modifiers.iter().all(|m| self.modifiers.contains(m))
+        });
+        if reserved {
+            return Err(format!("{} 是系统快捷键，请换用其他组合", self.display_text()));
+        }
+        Ok(())
+    }
+
     /// 获取显示文本
     pub fn display_text(&self) -> String {
         let mut parts: Vec<&str> = self.modifiers.iter().map(|k| k.display_name()).collect();
         parts.push(self.key.display_name());
         parts.join(" + ")
     }
+
+    /// 按此快捷键的设置覆盖全局输出配置
+    pub fn output_config(&self, base: &OutputConfig) -> OutputConfig {
+        let mut config = base.clone();
+        if self.keep_on_clipboard {
+            config.restore_clipboard = false;
+        }
+        if self.append_mode {
+            config.paste_position = PastePosition::AfterSelection;
+        }
+        if self.replace_last {
+            config.replace_last_output = true;
+        }
+        config
+    }
 }
 
 /// 应用配置
@@ -192,6 +347,27 @@ pub struct AppConfig {
     pub llm: LlmConfig,
     #[serde(default)]
     pub output: OutputConfig,
+    #[serde(default)]
+    pub streaming: StreamingConfig,
+    #[serde(default)]
+    pub audio: AudioConfig,
+    #[serde(default)]
+    pub postprocess: PostProcessConfig,
+    #[serde(default)]
+    pub feedback: FeedbackConfig,
+    #[serde(default)]
+    pub debug: DebugConfig,
+    #[serde(default)]
+    pub history: HistoryConfig,
+    /// 按目标应用设置识别语言（key 为 bundle id 或应用名，value 为语言代码如 "zh"、"en"）
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub app_languages: HashMap<String, String>,
+    /// 按目标应用覆盖 LLM 优化和输出方式（按顺序匹配第一个），未匹配时使用全局配置
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub app_profiles: Vec<AppProfile>,
+    /// 是否已完成首次启动引导
+    #[serde(default)]
+    pub setup_done: bool,
 }
 
 impl Default for AppConfig {
@@ -201,10 +377,77 @@ impl Default for AppConfig {
             asr: AsrConfig::default(),
             llm: LlmConfig::default(),
             output: OutputConfig::default(),
+            streaming: StreamingConfig::default(),
+            audio: AudioConfig::default(),
+            postprocess: PostProcessConfig::default(),
+            feedback: FeedbackConfig::default(),
+            debug: DebugConfig::default(),
+            history: HistoryConfig::default(),
+            app_languages: HashMap::new(),
+            app_profiles: vec![],
+            setup_done: false,
         }
     }
 }
 
+impl AppConfig {
+    /// 查找目标应用的语言设置（按 bundle id 或应用名匹配，不区分大小写）
+    pub fn app_language(&self, app_id: Option<&str>, app_name: Option<&str>) -> Option<&str> {
+        self.app_languages
+            .iter()
+            .find(|(key, _)| {
+                [app_id, app_name]
+                    .into_iter()
+                    .flatten()
+                    .any(|name| name.eq_ignore_ascii_case(key))
+            })
+            .map(|(_, language)| language.as_str())
+    }
+
+    /// 将识别语言应用到 ASR 语言提示和 LLM 提示词
+    pub fn apply_language(&mut self, language: &str) {
+        self.asr.language = Some(language.to_string());
+        self.llm.language = Some(language.to_string());
+    }
+
+    /// 查找目标应用的配置方案（按 bundle id 或可执行文件名匹配，不区分大小写）
+    pub fn app_profile(
+        &self,
+        app_id: Option<&str>,
+        app_name: Option<&str>,
+    ) -> Option<&AppProfile> {
+        self.app_profiles.iter().find(|profile| {
+            [app_id, app_name]
+                .into_iter()
+                .flatten()
+                .any(|name| name.eq_ignore_ascii_case(&profile.bundle_id_or_exe))
+        })
+    }
+
+    /// 用应用配置方案覆盖全局的 LLM 和输出设置（方案未设置的项保持不变）
+    pub fn apply_app_profile(&mut self, profile: &AppProfile) {
+        if let Some(enabled) = profile.llm_enabled {
+            self.llm.enabled = enabled;
+        }
+        if let Some(mode) = profile.output_mode {
+            self.output.mode = mode;
+        }
+    }
+}
+
+/// 单个应用的配置方案
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppProfile {
+    /// bundle id（macOS）、窗口类名（Linux）或可执行文件名（Windows）
+    pub bundle_id_or_exe: String,
+    /// 是否启用 LLM 优化，未设置时使用全局配置（如代码编辑器中关闭，避免改写代码片段）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub llm_enabled: Option<bool>,
+    /// 输出方式，未设置时使用全局配置
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_mode: Option<OutputMode>,
+}
+
 /// 快捷键配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HotkeyConfig {
@@ -212,29 +455,135 @@ pub struct HotkeyConfig {
     #[serde(default)]
     pub binding: HotkeyBinding,
 
+    /// 额外的快捷键绑定（可带独立的输出设置）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_bindings: Vec<HotkeyBinding>,
+
+    /// 按平台覆盖的快捷键绑定（同一份配置在多台设备间同步时使用）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub platform_bindings: Option<PlatformBindings>,
+
     /// 兼容旧配置: 旧的 trigger_key 字段
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub trigger_key: Option<String>,
 
     #[serde(default = "default_true")]
     pub enabled: bool,
+
+    /// 排除的应用（bundle id 或应用名）：这些应用在前台时快捷键不触发录音
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub excluded_apps: Vec<String>,
+
+    /// 触发方式：按住说话、按一次开始再按一次结束、双击开始再双击结束，或说完后静音自动结束
+    #[serde(default)]
+    pub mode: HotkeyMode,
+
+    /// 双击模式下两次按下的最大间隔（毫秒）
+    #[serde(default = "default_double_tap_window_ms")]
+    pub double_tap_window_ms: u64,
+
+    /// 按住说话模式下按键短于此时长（毫秒）视为误触，丢弃录音且不提示错误；0 表示不检查
+    #[serde(default = "default_min_press_ms")]
+    pub min_press_ms: u64,
+}
+
+/// 快捷键触发方式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "PascalCase")]
+pub enum HotkeyMode {
+    /// 按住录音，松开结束
+    #[default]
+    PushToTalk,
+    /// 按一次开始录音，再按一次结束
+    Toggle,
+    /// 快速双击开始录音，再次双击结束（单击不影响按键原有用途）
+    DoubleTap,
+    /// 按一次开始录音，说完后持续静音自动结束（也可再按一次结束）
+    AutoStop,
+}
+
+/// 按平台的快捷键绑定，未设置的平台使用通用 `binding`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PlatformBindings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub macos: Option<HotkeyBinding>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub windows: Option<HotkeyBinding>,
 }
 
 fn default_true() -> bool {
     true
 }
 
+fn default_double_tap_window_ms() -> u64 {
+    300
+}
+
+fn default_min_press_ms() -> u64 {
+    150
+}
+
 impl Default for HotkeyConfig {
     fn default() -> Self {
         Self {
             binding: HotkeyBinding::default(),
+            extra_bindings: vec![],
+            platform_bindings: None,
             trigger_key: None,
             enabled: true,
+            excluded_apps: vec![],
+            mode: HotkeyMode::default(),
+            double_tap_window_ms: default_double_tap_window_ms(),
+            min_press_ms: default_min_press_ms(),
         }
     }
 }
 
 impl HotkeyConfig {
+    /// 应用是否在排除列表中（按 bundle id 或应用名匹配，不区分大小写）
+    pub fn is_excluded(&self, app_id: Option<&str>, app_name: Option<&str>) -> bool {
+        self.excluded_apps.iter().any(|excluded| {
+            [app_id, app_name]
+                .into_iter()
+                .flatten()
+                .any(|name| name.eq_ignore_ascii_case(excluded))
+        })
+    }
+
+    /// 获取当前平台生效的快捷键绑定
+    pub fn effective_binding(&self) -> HotkeyBinding {
+        let platform_binding = self.platform_bindings.as_ref().and_then(|p| {
+            if cfg!(target_os = "macos") {
+                p.macos.clone()
+            } else if cfg!(target_os = "windows") {
+                p.windows.clone()
+            } else {
+                None
+            }
+        });
+        platform_binding.unwrap_or_else(|| self.binding.clone())
+    }
+
+    /// 检查所有快捷键绑定（包括其他平台的绑定），返回第一个错误
+    pub fn validate(&self) -> Result<(), String> {
+        let platform_bindings = self
+            .platform_bindings
+            .iter()
+            .flat_map(|p| [p.macos.as_ref(), p.windows.as_ref()])
+            .flatten();
+        std::iter::once(&self.binding)
+            .chain(&self.extra_bindings)
+            .chain(platform_bindings)
+            .try_for_each(HotkeyBinding::validate)
+    }
+
+    /// 获取当前平台所有需要监听的快捷键（主快捷键在前）
+    pub fn all_bindings(&self) -> Vec<HotkeyBinding> {
+        let mut bindings = vec![self.effective_binding()];
+        bindings.extend(self.extra_bindings.iter().cloned());
+        bindings
+    }
+
     /// 从旧配置迁移
     pub fn migrate(&mut self) {
         if let Some(ref old_key) = self.trigger_key {
@@ -242,11 +591,11 @@ impl HotkeyConfig {
             self.binding = match old_key.as_str() {
                 "Alt" => HotkeyBinding {
                     key: KeyCode::Alt,
-                    modifiers: vec![],
+                    ..Default::default()
                 },
                 "Control" => HotkeyBinding {
                     key: KeyCode::Control,
-                    modifiers: vec![],
+                    ..Default::default()
                 },
                 _ => HotkeyBinding::default(),
             };
@@ -267,13 +616,60 @@ pub struct AsrConfig {
     #[serde(default)]
     pub openai: Option<OpenAiAsrConfig>,
     #[serde(default)]
+    pub openai_realtime: Option<OpenAiRealtimeAsrConfig>,
+    #[serde(default)]
+    pub deepgram: Option<DeepgramAsrConfig>,
+    #[serde(default)]
+    pub azure: Option<AzureAsrConfig>,
+    #[serde(default)]
+    pub assemblyai: Option<AssemblyAiConfig>,
+    #[serde(default)]
     pub funasr: Option<FunAsrConfig>,
+    #[serde(default)]
+    pub sherpa: Option<SherpaConfig>,
+    /// 识别语言（如 "zh"、"en"），未设置时使用各服务商的默认值
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// 最低置信度（0.0 - 1.0），低于此值的识别结果不输出并提示重说；服务商不提供置信度时不检查
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_confidence: Option<f32>,
+    /// 词级最低置信度（0.0 - 1.0），有词低于此值时发送 `low-confidence-words` 事件供复核，不影响输出
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_word_confidence: Option<f32>,
+    /// 识别超时（秒）：等待服务端响应超过此时间视为失败，每收到一条消息重新计时
+    #[serde(default = "default_asr_timeout_secs")]
+    pub timeout_secs: u64,
+    /// 网络错误（连接失败、超时等）时的最大重试次数，认证或模型等服务端错误不重试
+    #[serde(default = "default_asr_max_retries")]
+    pub max_retries: u32,
+    /// 开始录音时在后台预先建立 WebSocket 连接（DashScope、通义千问），松开快捷键后直接开始识别
+    #[serde(default)]
+    pub preconnect: bool,
+    /// 边录音边将音频发送给 ASR（DashScope、通义千问、AssemblyAI），松开快捷键时大部分音频已识别完成
+    ///
+    /// 服务商不支持或按声道识别时照常在录音结束后识别；边录边传出现网络错误时用完整录音重新识别
+    #[serde(default)]
+    pub stream_during_recording: bool,
+    /// 备用服务商（按顺序尝试）：前一个服务商重试后仍为网络错误时切换，服务端错误不切换
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fallback_providers: Vec<String>,
+    /// 热词（产品名、缩写等易识别错的词）：Qwen、FunASR 直接传给服务商，其他服务商加入 LLM 术语表
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hotwords: Vec<String>,
 }
 
 fn default_asr_provider() -> String {
     "Qwen".to_string()
 }
 
+fn default_asr_timeout_secs() -> u64 {
+    30
+}
+
+fn default_asr_max_retries() -> u32 {
+    2
+}
+
 impl Default for AsrConfig {
     fn default() -> Self {
         Self {
@@ -281,7 +677,21 @@ impl Default for AsrConfig {
             dashscope: None,
             qwen: None,
             openai: None,
+            openai_realtime: None,
+            deepgram: None,
+            azure: None,
+            assemblyai: None,
             funasr: None,
+            sherpa: None,
+            language: None,
+            min_confidence: None,
+            min_word_confidence: None,
+            timeout_secs: default_asr_timeout_secs(),
+            max_retries: default_asr_max_retries(),
+            preconnect: false,
+            stream_during_recording: false,
+            fallback_providers: vec![],
+            hotwords: vec![],
         }
     }
 }
@@ -292,6 +702,49 @@ pub struct DashScopeAsrConfig {
     pub api_key: String,
     #[serde(default = "default_dashscope_model")]
     pub model: String,
+    /// 识别完成后保持 WebSocket 连接，供短时间内的下一次识别复用
+    #[serde(default)]
+    pub keep_alive: bool,
+    /// 自动添加标点
+    #[serde(default = "default_true")]
+    pub enable_punctuation: bool,
+    /// 语义断句（按语义而不是停顿添加标点，延迟略高）
+    #[serde(default)]
+    pub enable_semantic_punctuation: bool,
+    /// 过滤语气词（"嗯"、"啊"等）
+    #[serde(default)]
+    pub enable_disfluency_removal: bool,
+    /// 逆文本标准化（中文数字转为阿拉伯数字等）
+    #[serde(default = "default_true")]
+    pub enable_itn: bool,
+    /// 识别语言提示，包含 "auto" 时不设置提示（自动检测）
+    #[serde(default = "default_asr_languages", deserialize_with = "deserialize_languages")]
+    pub languages: Vec<String>,
+    /// 热词表 ID（在 DashScope 控制台或定制热词 API 中创建），DashScope 不支持在请求中直接传热词
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vocabulary_id: Option<String>,
+}
+
+fn default_asr_languages() -> Vec<String> {
+    vec!["zh".to_string(), "en".to_string()]
+}
+
+/// 语言列表同时接受字符串（"zh"）和数组（["zh", "en"]）写法
+fn deserialize_languages<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Languages {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(match Languages::deserialize(deserializer)? {
+        Languages::One(language) => vec![language],
+        Languages::Many(languages) => languages,
+    })
 }
 
 fn default_dashscope_model() -> String {
@@ -304,6 +757,12 @@ pub struct QwenAsrConfig {
     pub api_key: String,
     #[serde(default = "default_qwen_asr_model")]
     pub model: String,
+    /// 识别完成后保持 WebSocket 连接，供短时间内的下一次识别复用
+    #[serde(default)]
+    pub keep_alive: bool,
+    /// 识别语言，只支持单一语言，取第一项；包含 "auto" 时自动检测
+    #[serde(default = "default_asr_languages", deserialize_with = "deserialize_languages")]
+    pub languages: Vec<String>,
 }
 
 fn default_qwen_asr_model() -> String {
@@ -316,16 +775,158 @@ pub struct OpenAiAsrConfig {
     pub api_key: String,
     #[serde(default = "default_whisper_model")]
     pub model: String,
-    #[serde(default = "default_language")]
-    pub language: String,
+    /// 识别语言，只支持单一语言，取第一项；包含 "auto" 时自动检测
+    ///
+    /// 兼容旧配置的 `language` 字符串写法
+    #[serde(
+        default = "default_asr_languages",
+        alias = "language",
+        deserialize_with = "deserialize_languages"
+    )]
+    pub languages: Vec<String>,
+    /// OpenAI 兼容服务的 API 地址（如 Groq、自建 vLLM），未设置时使用 OpenAI 官方地址
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
 }
 
+/// OpenAI 官方 API 地址
+pub const OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+
 fn default_whisper_model() -> String {
     "whisper-1".to_string()
 }
 
-fn default_language() -> String {
-    "zh".to_string()
+/// Deepgram ASR 配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeepgramAsrConfig {
+    pub api_key: String,
+    #[serde(default = "default_deepgram_model")]
+    pub model: String,
+    /// 识别语言，只支持单一语言，取第一项；包含 "auto" 时自动检测
+    #[serde(
+        default = "default_asr_languages",
+        alias = "language",
+        deserialize_with = "deserialize_languages"
+    )]
+    pub languages: Vec<String>,
+}
+
+fn default_deepgram_model() -> String {
+    "nova-2".to_string()
+}
+
+/// Azure Speech ASR 配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AzureAsrConfig {
+    pub subscription_key: String,
+    /// 服务区域（如 eastasia、westus2）
+    pub region: String,
+    /// 识别语言（如 zh-CN、en-US，"zh" 等简写会自动补全区域）
+    #[serde(default = "default_azure_language")]
+    pub language: String,
+}
+
+fn default_azure_language() -> String {
+    "zh-CN".to_string()
+}
+
+/// AssemblyAI 实时 ASR 配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssemblyAiConfig {
+    pub api_key: String,
+    /// 说话人分离：输出按说话人分行并标注（如 `Speaker A: ...`），适合会议记录
+    #[serde(default)]
+    pub enable_diarization: bool,
+}
+
+/// OpenAI Realtime 兼容的流式 ASR 配置（可对接 OpenAI、Azure 及本地兼容服务）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenAiRealtimeAsrConfig {
+    /// WebSocket 地址（OpenAI 方言附加 `intent=transcription`，DashScope 方言附加 `model` 查询参数）
+    #[serde(default = "default_realtime_base_url")]
+    pub base_url: String,
+    #[serde(default)]
+    pub api_key: String,
+    #[serde(default = "default_realtime_model")]
+    pub model: String,
+    /// 认证头名称（Azure 使用 api-key）
+    #[serde(default = "default_realtime_auth_header")]
+    pub auth_header: String,
+    /// OpenAI-Beta 头的值，为空时不发送
+    #[serde(default = "default_realtime_beta_header")]
+    pub beta_header: Option<String>,
+    /// 服务端使用的协议方言
+    #[serde(default)]
+    pub dialect: RealtimeDialect,
+    /// 识别语言，只支持单一语言，取第一项；包含 "auto" 时自动检测
+    #[serde(default = "default_asr_languages", deserialize_with = "deserialize_languages")]
+    pub languages: Vec<String>,
+}
+
+/// OpenAI Realtime 协议方言
+///
+/// 各服务商在会话配置、音频格式和中间结果事件上不完全一致
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "PascalCase")]
+pub enum RealtimeDialect {
+    /// OpenAI 转写会话（Azure OpenAI 及多数兼容服务相同），24kHz pcm16，中间结果为增量文本
+    #[default]
+    OpenAi,
+    /// DashScope（通义千问），会话带采样率，中间结果分为已确认文本和暂定文本
+    DashScope,
+}
+
+impl RealtimeDialect {
+    /// 会话配置事件的类型
+    pub fn session_update_event(&self) -> &'static str {
+        match self {
+            RealtimeDialect::OpenAi => "transcription_session.update",
+            RealtimeDialect::DashScope => "session.update",
+        }
+    }
+
+    /// 会话配置中的 input_audio_format
+    pub fn input_audio_format(&self) -> &'static str {
+        match self {
+            RealtimeDialect::OpenAi => "pcm16",
+            RealtimeDialect::DashScope => "pcm",
+        }
+    }
+
+    /// 服务端接受的采样率（Hz），按优先级从高到低排列
+    pub fn sample_rates(&self) -> &'static [u32] {
+        match self {
+            RealtimeDialect::OpenAi => &[24000],
+            RealtimeDialect::DashScope => &[16000, 8000],
+        }
+    }
+}
+
+fn default_realtime_base_url() -> String {
+    "wss://api.openai.com/v1/realtime".to_string()
+}
+
+fn default_realtime_model() -> String {
+    "gpt-4o-transcribe".to_string()
+}
+
+fn default_realtime_auth_header() -> String {
+    "Authorization".to_string()
+}
+
+fn default_realtime_beta_header() -> Option<String> {
+    Some("realtime=v1".to_string())
+}
+
+/// FunASR 2pass 结果选择策略
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "PascalCase")]
+pub enum FunAsrResultStrategy {
+    /// 优先使用离线（精确）结果，等待离线结果到达
+    #[default]
+    PreferOffline,
+    /// 优先使用在线（低延迟）结果，离线结果仅作回退
+    PreferOnline,
 }
 
 /// FunASR 配置
@@ -333,12 +934,60 @@ fn default_language() -> String {
 pub struct FunAsrConfig {
     #[serde(default = "default_funasr_endpoint")]
     pub endpoint: String,
+    #[serde(default)]
+    pub result_strategy: FunAsrResultStrategy,
+    /// 发送的音频格式（开始消息中的 wav_format）
+    #[serde(default)]
+    pub audio_format: FunAsrAudioFormat,
+    /// 分块发送时每块的时长（毫秒），为 0 时整段发送
+    #[serde(default = "default_funasr_chunk_ms")]
+    pub chunk_ms: u32,
 }
 
 fn default_funasr_endpoint() -> String {
     "http://localhost:10096".to_string()
 }
 
+fn default_funasr_chunk_ms() -> u32 {
+    200
+}
+
+/// 发送给 FunASR 的音频格式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "PascalCase")]
+pub enum FunAsrAudioFormat {
+    /// 裸 16-bit PCM 数据
+    #[default]
+    Pcm,
+    /// 带文件头的 WAV 数据（需整段发送）
+    Wav,
+}
+
+impl FunAsrAudioFormat {
+    /// 开始消息中的 wav_format 字段值
+    pub fn wav_format(&self) -> &'static str {
+        match self {
+            FunAsrAudioFormat::Pcm => "pcm",
+            FunAsrAudioFormat::Wav => "wav",
+        }
+    }
+}
+
+/// Sherpa-ONNX 本地离线 ASR 配置（需启用 `sherpa` 功能编译）
+///
+/// 使用非流式 Paraformer 模型，只在录音结束后整段识别，不支持实时识别
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SherpaConfig {
+    /// 模型目录，需包含 model.onnx (或 model.int8.onnx) 和 tokens.txt
+    pub model_dir: String,
+    #[serde(default = "default_sherpa_threads")]
+    pub num_threads: i32,
+}
+
+fn default_sherpa_threads() -> i32 {
+    2
+}
+
 /// LLM 配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LlmConfig {
@@ -352,6 +1001,19 @@ pub struct LlmConfig {
     pub openai: Option<OpenAiLlmConfig>,
     #[serde(default)]
     pub ollama: Option<OllamaConfig>,
+    #[serde(default)]
+    pub claude: Option<ClaudeLlmConfig>,
+    #[serde(default)]
+    pub gemini: Option<GeminiLlmConfig>,
+    /// 文本语言，设置后提示 LLM 保持该语言输出
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// 自定义系统提示词，设置后替代默认的文本修正提示词
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_prompt: Option<String>,
+    /// 术语表，加入默认提示词要求 LLM 按此写法修正（使用自定义提示词时不生效）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub glossary: Vec<String>,
 }
 
 fn default_llm_provider() -> String {
@@ -366,6 +1028,11 @@ impl Default for LlmConfig {
             dashscope: None,
             openai: None,
             ollama: None,
+            claude: None,
+            gemini: None,
+            language: None,
+            custom_prompt: None,
+            glossary: vec![],
         }
     }
 }
@@ -392,6 +1059,9 @@ pub struct OpenAiLlmConfig {
     pub temperature: f32,
     #[serde(default = "default_max_tokens")]
     pub max_tokens: u32,
+    /// OpenAI 兼容服务的 API 地址（如 OpenRouter、Together、自建 vLLM），未设置时使用 OpenAI 官方地址
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
 }
 
 fn default_gpt_model() -> String {
@@ -406,6 +1076,32 @@ fn default_max_tokens() -> u32 {
     2000
 }
 
+/// Anthropic Claude LLM 配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClaudeLlmConfig {
+    pub api_key: String,
+    #[serde(default = "default_claude_model")]
+    pub model: String,
+    #[serde(default = "default_max_tokens")]
+    pub max_tokens: u32,
+}
+
+fn default_claude_model() -> String {
+    "claude-3-5-haiku-latest".to_string()
+}
+
+/// Google Gemini LLM 配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeminiLlmConfig {
+    pub api_key: String,
+    #[serde(default = "default_gemini_model")]
+    pub model: String,
+}
+
+fn default_gemini_model() -> String {
+    "gemini-1.5-flash".to_string()
+}
+
 /// Ollama 配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OllamaConfig {
@@ -413,6 +1109,15 @@ pub struct OllamaConfig {
     pub endpoint: String,
     #[serde(default = "default_ollama_model")]
     pub model: String,
+    /// 采样温度，越低输出越稳定
+    #[serde(default = "default_temperature")]
+    pub temperature: f32,
+    /// 上下文窗口大小（token），未设置时使用模型默认值
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub num_ctx: Option<u32>,
+    /// 模型在内存中的保留时间（如 "30m"、"-1" 表示常驻），未设置时使用 Ollama 默认值
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keep_alive: Option<String>,
 }
 
 fn default_ollama_endpoint() -> String {
@@ -423,24 +1128,361 @@ fn default_ollama_model() -> String {
     "qwen3:8b".to_string()
 }
 
+/// 粘贴位置
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "PascalCase")]
+pub enum PastePosition {
+    /// 当前光标处（默认）
+    #[default]
+    Cursor,
+    /// 文本框开头
+    Start,
+    /// 文本框末尾
+    End,
+    /// 全选后替换（适合单行输入框）
+    ReplaceAll,
+    /// 先按右方向键收起选区再插入，不会覆盖选中的文本
+    AfterSelection,
+}
+
+/// 模拟粘贴使用的快捷键
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub enum PasteShortcut {
+    /// Cmd+V（macOS 标准）
+    CmdV,
+    /// Ctrl+V（Windows / Linux 标准）
+    CtrlV,
+    /// Shift+Insert（部分终端和 X11 应用）
+    ShiftInsert,
+    /// Ctrl+Shift+V（Linux 终端）
+    CtrlShiftV,
+}
+
+impl PasteShortcut {
+    /// 当前平台的标准粘贴快捷键
+    pub fn platform_default() -> Self {
+        if cfg!(target_os = "macos") {
+            PasteShortcut::CmdV
+        } else {
+            PasteShortcut::CtrlV
+        }
+    }
+}
+
+/// 输出方式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "PascalCase")]
+pub enum OutputMode {
+    /// 写入剪贴板并模拟粘贴（默认）
+    #[default]
+    Paste,
+    /// 只写入剪贴板，不模拟按键，由用户自行粘贴
+    ClipboardOnly,
+    /// 逐字模拟键入（适合忽略粘贴的终端、远程桌面），失败时回退到粘贴
+    Type,
+}
+
 /// 输出配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OutputConfig {
+    /// 输出方式，`ClipboardOnly` 下忽略 `restore_clipboard`
+    #[serde(default)]
+    pub mode: OutputMode,
     #[serde(default = "default_true")]
     pub restore_clipboard: bool,
+    /// 模拟粘贴前等待焦点稳定的时间（毫秒）
     #[serde(default = "default_paste_delay")]
     pub paste_delay_ms: u64,
+    /// 模拟粘贴后等待多久（毫秒）再恢复原剪贴板内容，过短时目标应用可能粘贴出恢复后的旧内容
+    #[serde(default = "default_clipboard_restore_delay")]
+    pub clipboard_restore_delay_ms: u64,
+    /// 模拟粘贴的快捷键，未设置时使用平台标准快捷键（终端中可改为 Ctrl+Shift+V 等）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paste_shortcut: Option<PasteShortcut>,
+    /// 粘贴前先移动光标到指定位置
+    #[serde(default)]
+    pub paste_position: PastePosition,
+    /// 上次输出后几秒内重新录音、且仍在同一窗口时，先删除上次输出再粘贴（用于重新口述修正）
+    #[serde(default)]
+    pub replace_last_output: bool,
+    /// 未经 LLM 优化时的基础文本规整
+    #[serde(default)]
+    pub text_rules: TextRules,
+}
+
+/// 基础文本规整规则（不依赖 LLM）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextRules {
+    /// 英文句首字母大写
+    #[serde(default)]
+    pub auto_capitalize: bool,
+    /// 句末没有标点时补全（中文补 "。"，英文补 "."）
+    #[serde(default)]
+    pub auto_period: bool,
+    /// 去掉 `filler_words` 中的语气词
+    #[serde(default)]
+    pub trim_filler_words: bool,
+    /// 语气词，英文按整词匹配（不区分大小写）
+    #[serde(default = "default_filler_words")]
+    pub filler_words: Vec<String>,
+}
+
+fn default_filler_words() -> Vec<String> {
+    ["um", "uh", "嗯", "呃"].map(str::to_string).to_vec()
+}
+
+impl Default for TextRules {
+    fn default() -> Self {
+        Self {
+            auto_capitalize: false,
+            auto_period: false,
+            trim_filler_words: false,
+            filler_words: default_filler_words(),
+        }
+    }
 }
 
 fn default_paste_delay() -> u64 {
     50
 }
 
+fn default_clipboard_restore_delay() -> u64 {
+    150
+}
+
 impl Default for OutputConfig {
     fn default() -> Self {
         Self {
+            mode: OutputMode::default(),
             restore_clipboard: true,
             paste_delay_ms: default_paste_delay(),
+            clipboard_restore_delay_ms: default_clipboard_restore_delay(),
+            paste_shortcut: None,
+            paste_position: PastePosition::default(),
+            replace_last_output: false,
+            text_rules: TextRules::default(),
+        }
+    }
+}
+
+/// 音频录制配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioConfig {
+    /// 录音缓冲区预分配时长（秒），减少长录音时的重新分配
+    #[serde(default = "default_buffer_preallocate_secs")]
+    pub buffer_preallocate_secs: u32,
+
+    /// 双声道设备分声道识别（左右声道分别转写并标注说话人）
+    #[serde(default)]
+    pub diarize_channels: bool,
+
+    /// 保留最近一次录音用于质量分析（默认关闭）
+    #[serde(default)]
+    pub keep_last_recording: bool,
+
+    /// 最长录音时长（秒），超过后自动停止录音并处理已录内容；0 表示不限制
+    #[serde(default = "default_max_recording_secs")]
+    pub max_recording_secs: u64,
+
+    /// 预录时长（毫秒）：空闲时保持麦克风打开并缓冲最近的音频，开始录音时补到开头，
+    /// 避免按下快捷键后音频流启动期间丢掉第一个字；0 表示关闭
+    #[serde(default = "default_preroll_ms")]
+    pub preroll_ms: u32,
+
+    /// 自动结束模式下说话后持续静音多久（毫秒）自动停止录音
+    #[serde(default = "default_silence_timeout_ms")]
+    pub silence_timeout_ms: u64,
+
+    /// 自动结束模式下至少检测到多长的语音（毫秒）才开始计算静音，避免开口前就结束
+    #[serde(default = "default_min_speech_ms")]
+    pub min_speech_ms: u64,
+}
+
+fn default_buffer_preallocate_secs() -> u32 {
+    30
+}
+
+fn default_max_recording_secs() -> u64 {
+    120
+}
+
+fn default_preroll_ms() -> u32 {
+    200
+}
+
+fn default_silence_timeout_ms() -> u64 {
+    1500
+}
+
+fn default_min_speech_ms() -> u64 {
+    300
+}
+
+impl Default for AudioConfig {
+    fn default() -> Self {
+        Self {
+            buffer_preallocate_secs: default_buffer_preallocate_secs(),
+            diarize_channels: false,
+            keep_last_recording: false,
+            max_recording_secs: default_max_recording_secs(),
+            preroll_ms: default_preroll_ms(),
+            silence_timeout_ms: default_silence_timeout_ms(),
+            min_speech_ms: default_min_speech_ms(),
+        }
+    }
+}
+
+/// 流式（连续输入）配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamingConfig {
+    /// 是否启用结束口令
+    #[serde(default)]
+    pub commit_phrase_enabled: bool,
+    /// 结束口令：连续输入时说出该口令即结束会话并输出（口令本身不会输出）
+    #[serde(default = "default_commit_phrase")]
+    pub commit_phrase: String,
+    /// 结束录音后如何确定最终结果
+    #[serde(default)]
+    pub final_result_policy: FinalResultPolicy,
+    /// `AcceptInterim` 策略下等待最终结果的时间（毫秒）
+    #[serde(default = "default_interim_grace_ms")]
+    pub interim_grace_ms: u64,
+    /// 逐句 LLM 优化：每句识别完成后立即优化并输出（需启用 LLM）
+    #[serde(default)]
+    pub refine_segments: bool,
+}
+
+fn default_commit_phrase() -> String {
+    "结束".to_string()
+}
+
+fn default_interim_grace_ms() -> u64 {
+    500
+}
+
+impl Default for StreamingConfig {
+    fn default() -> Self {
+        Self {
+            commit_phrase_enabled: false,
+            commit_phrase: default_commit_phrase(),
+            final_result_policy: FinalResultPolicy::default(),
+            interim_grace_ms: default_interim_grace_ms(),
+            refine_segments: false,
+        }
+    }
+}
+
+/// 流式识别最终结果策略
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "PascalCase")]
+pub enum FinalResultPolicy {
+    /// 等待服务端的最终结果（更准确）
+    #[default]
+    WaitForFinal,
+    /// 最终结果超时未到达时，使用最近一次中间结果（更快，可能漏掉最后一个词）
+    AcceptInterim,
+}
+
+impl StreamingConfig {
+    /// 获取生效的结束口令（未启用或为空时返回 None）
+    pub fn active_commit_phrase(&self) -> Option<&str> {
+        let phrase = self.commit_phrase.trim();
+        if self.commit_phrase_enabled && !phrase.is_empty() {
+            Some(phrase)
+        } else {
+            None
+        }
+    }
+}
+
+/// 数字格式转换方向
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "PascalCase")]
+pub enum NumberFormat {
+    /// 不转换
+    #[default]
+    Off,
+    /// 转换为阿拉伯数字（"三点五" → "3.5"，"twenty five percent" → "25%"）
+    Digits,
+    /// 转换为中文数字（"3.5" → "三点五"）
+    Chinese,
+}
+
+/// 识别结果后处理配置（独立于 LLM）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PostProcessConfig {
+    #[serde(default)]
+    pub number_format: NumberFormat,
+    /// 去掉 LLM 优化结果中的 Markdown 格式（加粗、列表等），以纯文本输出
+    #[serde(default)]
+    pub strip_markdown: bool,
+}
+
+/// 录音反馈配置（提示音、通知、悬浮窗）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedbackConfig {
+    /// 静音模式：关闭所有提示音、通知和悬浮窗（会议中使用）
+    #[serde(default)]
+    pub silent_mode: bool,
+    /// 录音开始、结束和处理完成时播放提示音
+    #[serde(default)]
+    pub play_sounds: bool,
+    /// 提示音音量（0.0 ~ 1.0）
+    #[serde(default = "default_sound_volume")]
+    pub sound_volume: f32,
+    /// 录音时在菜单栏图标旁显示 "🔴"（仅 macOS）
+    #[serde(default)]
+    pub show_menubar_recording_text: bool,
+}
+
+fn default_sound_volume() -> f32 {
+    0.5
+}
+
+impl Default for FeedbackConfig {
+    fn default() -> Self {
+        Self {
+            silent_mode: false,
+            play_sounds: false,
+            sound_volume: default_sound_volume(),
+            show_menubar_recording_text: false,
+        }
+    }
+}
+
+/// 调试配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DebugConfig {
+    /// 录音保存目录（相对路径基于数据目录），设置后每次录音在送 ASR 前另存为 WAV
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub save_recordings_dir: Option<PathBuf>,
+}
+
+/// 识别历史配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryConfig {
+    /// 是否记录识别历史（关闭后不再写入新记录）
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// 最多保留的记录条数，超出后丢弃最早的记录
+    #[serde(default = "default_history_max_entries")]
+    pub max_entries: usize,
+    /// 加密保存历史记录（每次启动后需输入口令解锁，口令不会保存）
+    #[serde(default)]
+    pub encrypt: bool,
+}
+
+fn default_history_max_entries() -> usize {
+    50
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_entries: default_history_max_entries(),
+            encrypt: false,
         }
     }
 }
@@ -1,24 +1,93 @@
 use std::fs;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
 use crate::config::settings::AppConfig;
 
+/// 覆盖数据目录的环境变量（便携安装、多配置场景）
+pub const DATA_DIR_ENV: &str = "VHISPER_DATA_DIR";
+
+/// 启动参数指定的数据目录（优先于环境变量）
+static DATA_DIR_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
 #[derive(Debug, thiserror::Error)]
 pub enum ConfigError {
     #[error("Config directory not found")]
     DirNotFound,
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
+    #[error("Failed to write {path:?}: {source}")]
+    Write {
+        path: PathBuf,
+        source: std::io::Error,
+    },
     #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
+    #[error("Invalid data directory {0:?}: not a directory")]
+    InvalidDataDir(PathBuf),
+    #[error("Data directory already set to {0:?}")]
+    DataDirAlreadySet(PathBuf),
+    #[error("History encryption error: {0}")]
+    Encryption(String),
+    #[error("Wrong history passphrase or corrupted history file")]
+    WrongPassphrase,
+}
+
+/// 设置自定义数据目录（需在首次读取配置前调用，只能设置一次）
+///
+/// 目录不存在时自动创建，返回规范化后的路径
+pub fn set_data_dir(path: impl AsRef<Path>) -> Result<PathBuf, ConfigError> {
+    let dir = prepare_dir(path.as_ref())?;
+    DATA_DIR_OVERRIDE.set(dir.clone()).map_err(|_| {
+        ConfigError::DataDirAlreadySet(DATA_DIR_OVERRIDE.get().cloned().unwrap_or_default())
+    })?;
+    tracing::info!("Using custom data directory: {:?}", dir);
+    Ok(dir)
+}
+
+/// 获取数据目录（存放配置等数据）
+///
+/// 优先级：启动参数 > `VHISPER_DATA_DIR` 环境变量 > 系统配置目录下的 com.vhisper.app
+pub fn data_dir() -> Result<PathBuf, ConfigError> {
+    if let Some(dir) = DATA_DIR_OVERRIDE.get() {
+        return Ok(dir.clone());
+    }
+    if let Some(dir) = std::env::var_os(DATA_DIR_ENV).filter(|dir| !dir.is_empty()) {
+        return prepare_dir(Path::new(&dir));
+    }
+
+    let config_dir = dirs::config_dir().ok_or(ConfigError::DirNotFound)?;
+    prepare_dir(&config_dir.join("com.vhisper.app"))
+}
+
+/// 将相对路径解析到数据目录下，绝对路径原样返回
+pub fn resolve_data_path(path: impl AsRef<Path>) -> PathBuf {
+    let path = path.as_ref();
+    if path.is_absolute() {
+        return path.to_path_buf();
+    }
+    match data_dir() {
+        Ok(dir) => dir.join(path),
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+/// 校验并创建目录（首次启动或目录被删除时自动创建）
+fn prepare_dir(dir: &Path) -> Result<PathBuf, ConfigError> {
+    if dir.exists() && !dir.is_dir() {
+        return Err(ConfigError::InvalidDataDir(dir.to_path_buf()));
+    }
+    fs::create_dir_all(dir).map_err(|source| ConfigError::Write {
+        path: dir.to_path_buf(),
+        source,
+    })?;
+    Ok(dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf()))
 }
 
 /// 获取配置文件路径
 fn get_config_path() -> Result<PathBuf, ConfigError> {
-    let config_dir = dirs::config_dir().ok_or(ConfigError::DirNotFound)?;
-    let app_dir = config_dir.join("com.vhisper.app");
-    fs::create_dir_all(&app_dir)?;
-    Ok(app_dir.join("config.json"))
+    Ok(data_dir()?.join("config.json"))
 }
 
 /// 加载配置
@@ -40,7 +109,30 @@ pub fn save_config(config: &AppConfig) -> Result<(), ConfigError> {
     let path = get_config_path()?;
     tracing::info!("Saving config to: {:?}", path);
     let content = serde_json::to_string_pretty(config)?;
-    fs::write(&path, &content)?;
+    write_atomic(&path, content.as_bytes())?;
     tracing::info!("Config saved successfully");
     Ok(())
 }
+
+/// 原子写入：先写入临时文件并落盘，再重命名覆盖目标文件
+///
+/// 写入失败（目录只读、磁盘已满等）时原文件保持不变
+pub(crate) fn write_atomic(path: &Path, content: &[u8]) -> Result<(), ConfigError> {
+    let tmp_path = path.with_extension("json.tmp");
+
+    let result = (|| {
+        let mut file = fs::File::create(&tmp_path)?;
+        file.write_all(content)?;
+        file.sync_all()?;
+        fs::rename(&tmp_path, path)
+    })();
+
+    result.map_err(|source| {
+        fs::remove_file(&tmp_path).ok();
+        tracing::error!("Failed to write {:?}: {}", path, source);
+        ConfigError::Write {
+            path: path.to_path_buf(),
+            source,
+        }
+    })
+}
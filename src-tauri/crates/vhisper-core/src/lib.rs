@@ -4,10 +4,31 @@ pub mod config;
 pub mod ffi;
 pub mod llm;
 pub mod pipeline;
+pub mod postprocess;
 
-pub use asr::{create_asr_service, AsrError, AsrResult, AsrService};
-pub use asr::{test_qwen_api, test_dashscope_api, test_openai_api, test_funasr_api};
-pub use audio::{encode_to_pcm, encode_to_wav, AudioError, AudioRecorder};
-pub use config::{load_config, save_config, AppConfig, HotkeyBinding, KeyCode};
-pub use llm::{create_llm_service, LlmError, LlmService, test_ollama_api};
-pub use pipeline::{PipelineError, VoicePipeline};
+pub use asr::{
+    create_asr_service, select_sample_rate, AsrError, AsrResult, AsrService, SpeakerSegment,
+    WordInfo,
+};
+pub use asr::{
+    test_assemblyai_api, test_azure_api, test_qwen_api, test_dashscope_api, test_deepgram_api,
+    test_openai_api, test_funasr_api,
+};
+pub use audio::{
+    analyze_samples, decode_audio, default_input_device_name, downmix_to_mono, encode_to_pcm,
+    encode_to_wav, read_audio_file, read_wav_file, resample, test_microphone, AudioAnalysis,
+    AudioError, AudioRecorder, DeviceMonitor, MicTestResult, StreamFormat, SupportedConfig,
+};
+pub use config::{
+    data_dir, load_config, save_config, set_data_dir, AppConfig, AppProfile, HotkeyAction,
+    HotkeyBinding, HotkeyConfig, HotkeyMode, KeyCode, OutputConfig, OutputMode, PastePosition,
+    PasteShortcut, TextRules,
+};
+pub use llm::{
+    create_llm_service, LlmError, LlmService, test_claude_api, test_gemini_api, test_ollama_api,
+};
+pub use pipeline::{
+    benchmark_providers, transcribe_audio_data, transcribe_file, AsrRetryEvent, BenchmarkResult,
+    HistoryEntry, PartialTranscriptListener, PipelineError, PipelineErrorKind, PipelineEvent,
+    PipelineMetrics, PipelineStage, PipelineState, ProcessingError, ProviderOverride, TargetApp, VoicePipeline,
+};
@@ -0,0 +1,143 @@
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use super::traits::{LlmError, LlmService};
+
+const MESSAGES_URL: &str = "https://api.anthropic.com/v1/messages";
+const MODELS_URL: &str = "https://api.anthropic.com/v1/models";
+const API_VERSION: &str = "2023-06-01";
+
+/// Anthropic Claude LLM 服务
+pub struct ClaudeLlm {
+    api_key: String,
+    model: String,
+    /// 系统提示词
+    system_prompt: String,
+    max_tokens: u32,
+    client: Client,
+}
+
+impl ClaudeLlm {
+    pub fn new(api_key: String, model: String, system_prompt: String, max_tokens: u32) -> Self {
+        Self {
+            api_key,
+            model,
+            system_prompt,
+            max_tokens,
+            client: Client::new(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ClaudeRequest {
+    model: String,
+    max_tokens: u32,
+    system: String,
+    messages: Vec<Message>,
+}
+
+#[derive(Serialize)]
+struct Message {
+    role: String,
+    content: String,
+}
+
+#[derive(Deserialize)]
+struct ClaudeResponse {
+    content: Option<Vec<ContentBlock>>,
+    error: Option<ClaudeError>,
+}
+
+/// 响应内容块，只取文本块
+#[derive(Deserialize)]
+struct ContentBlock {
+    #[serde(rename = "type")]
+    block_type: String,
+    #[serde(default)]
+    text: String,
+}
+
+#[derive(Deserialize)]
+struct ClaudeError {
+    message: String,
+}
+
+#[async_trait]
+impl LlmService for ClaudeLlm {
+    async fn refine_text(&self, text: &str) -> Result<String, LlmError> {
+        let request = ClaudeRequest {
+            model: self.model.clone(),
+            max_tokens: self.max_tokens,
+            system: self.system_prompt.clone(),
+            messages: vec![Message {
+                role: "user".to_string(),
+                content: text.to_string(),
+            }],
+        };
+
+        let response = self
+            .client
+            .post(MESSAGES_URL)
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", API_VERSION)
+            .header("Content-Type", "application/json")
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| LlmError::Network(e.to_string()))?;
+
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .map_err(|e| LlmError::Network(e.to_string()))?;
+
+        if !status.is_success() {
+            return Err(LlmError::Api(format!("HTTP {}: {}", status, body)));
+        }
+
+        let result: ClaudeResponse =
+            serde_json::from_str(&body).map_err(|e| LlmError::Api(e.to_string()))?;
+
+        if let Some(error) = result.error {
+            return Err(LlmError::Api(error.message));
+        }
+
+        let output_text: String = result
+            .content
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|block| block.block_type == "text")
+            .map(|block| block.text)
+            .collect();
+
+        if output_text.trim().is_empty() {
+            return Ok(text.to_string());
+        }
+        Ok(output_text.trim().to_string())
+    }
+}
+
+/// 测试 Claude API Key
+pub async fn test_api(api_key: &str) -> Result<String, LlmError> {
+    let client = Client::new();
+
+    let response = client
+        .get(MODELS_URL)
+        .header("x-api-key", api_key)
+        .header("anthropic-version", API_VERSION)
+        .send()
+        .await
+        .map_err(|e| LlmError::Network(e.to_string()))?;
+
+    if response.status().is_success() {
+        Ok("API Key 验证成功".to_string())
+    } else {
+        Err(LlmError::Api(format!(
+            "API Key 无效: HTTP {}",
+            response.status()
+        )))
+    }
+}
@@ -2,20 +2,23 @@ use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
-use super::traits::{LlmError, LlmService, REFINE_PROMPT};
+use super::traits::{LlmError, LlmService};
 
 /// DashScope LLM 服务 (通义千问)
 pub struct DashScopeLlm {
     api_key: String,
     model: String,
+    /// 系统提示词
+    system_prompt: String,
     client: Client,
 }
 
 impl DashScopeLlm {
-    pub fn new(api_key: String, model: String) -> Self {
+    pub fn new(api_key: String, model: String, system_prompt: String) -> Self {
         Self {
             api_key,
             model,
+            system_prompt,
             client: Client::new(),
         }
     }
@@ -69,7 +72,7 @@ impl LlmService for DashScopeLlm {
                 messages: vec![
                     Message {
                         role: "system".to_string(),
-                        content: REFINE_PROMPT.to_string(),
+                        content: self.system_prompt.clone(),
                     },
                     Message {
                         role: "user".to_string(),
@@ -0,0 +1,155 @@
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use super::traits::{LlmError, LlmService};
+
+const API_BASE_URL: &str = "https://generativelanguage.googleapis.com/v1beta/models";
+
+/// Google Gemini LLM 服务
+pub struct GeminiLlm {
+    api_key: String,
+    model: String,
+    /// 系统提示词
+    system_prompt: String,
+    client: Client,
+}
+
+impl GeminiLlm {
+    pub fn new(api_key: String, model: String, system_prompt: String) -> Self {
+        Self {
+            api_key,
+            model,
+            system_prompt,
+            client: Client::new(),
+        }
+    }
+
+    fn generate_url(&self) -> String {
+        format!("{}/{}:generateContent", API_BASE_URL, self.model)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiRequest {
+    system_instruction: Content,
+    contents: Vec<Content>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct Content {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    role: Option<String>,
+    #[serde(default)]
+    parts: Vec<Part>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Part {
+    #[serde(default)]
+    text: String,
+}
+
+impl Content {
+    fn text(role: Option<&str>, text: &str) -> Self {
+        Self {
+            role: role.map(str::to_string),
+            parts: vec![Part {
+                text: text.to_string(),
+            }],
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct GeminiResponse {
+    candidates: Option<Vec<Candidate>>,
+    error: Option<GeminiError>,
+}
+
+#[derive(Deserialize)]
+struct Candidate {
+    /// 被安全策略拦截等情况下没有内容
+    #[serde(default)]
+    content: Content,
+}
+
+#[derive(Deserialize)]
+struct GeminiError {
+    message: String,
+}
+
+#[async_trait]
+impl LlmService for GeminiLlm {
+    async fn refine_text(&self, text: &str) -> Result<String, LlmError> {
+        let request = GeminiRequest {
+            system_instruction: Content::text(None, &self.system_prompt),
+            contents: vec![Content::text(Some("user"), text)],
+        };
+
+        let response = self
+            .client
+            .post(self.generate_url())
+            .header("x-goog-api-key", &self.api_key)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| LlmError::Network(e.to_string()))?;
+
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .map_err(|e| LlmError::Network(e.to_string()))?;
+
+        if !status.is_success() {
+            if let Ok(GeminiResponse { error: Some(error), .. }) = serde_json::from_str(&body) {
+                return Err(LlmError::Api(error.message));
+            }
+            return Err(LlmError::Api(format!("HTTP {}: {}", status, body)));
+        }
+
+        let result: GeminiResponse =
+            serde_json::from_str(&body).map_err(|e| LlmError::Api(e.to_string()))?;
+
+        if let Some(error) = result.error {
+            return Err(LlmError::Api(error.message));
+        }
+
+        // 取第一个候选结果的所有文本片段
+        let output_text: String = result
+            .candidates
+            .unwrap_or_default()
+            .into_iter()
+            .next()
+            .map(|candidate| candidate.content.parts.into_iter().map(|p| p.text).collect())
+            .unwrap_or_default();
+
+        if output_text.trim().is_empty() {
+            return Ok(text.to_string());
+        }
+        Ok(output_text.trim().to_string())
+    }
+}
+
+/// 测试 Gemini API Key
+pub async fn test_api(api_key: &str) -> Result<String, LlmError> {
+    let client = Client::new();
+
+    let response = client
+        .get(API_BASE_URL)
+        .header("x-goog-api-key", api_key)
+        .send()
+        .await
+        .map_err(|e| LlmError::Network(e.to_string()))?;
+
+    if response.status().is_success() {
+        Ok("API Key 验证成功".to_string())
+    } else {
+        Err(LlmError::Api(format!(
+            "API Key 无效: HTTP {}",
+            response.status()
+        )))
+    }
+}
@@ -1,22 +1,46 @@
+mod claude;
 mod dashscope;
+mod gemini;
 mod ollama;
 mod openai;
 mod traits;
 
+pub use claude::ClaudeLlm;
 pub use dashscope::DashScopeLlm;
-pub use ollama::OllamaLlm;
+pub use gemini::GeminiLlm;
+pub use ollama::{OllamaLlm, OllamaOptions};
 pub use openai::OpenAiLlm;
-pub use traits::{LlmError, LlmService};
+pub use traits::{refine_prompt, split_sentence_chunks, LlmError, LlmService};
 
-use crate::config::settings::LlmConfig;
+use crate::config::provider::{llm_provider_names, resolve_llm_provider};
+use crate::config::settings::{LlmConfig, OPENAI_BASE_URL};
 
 /// 根据配置创建 LLM 服务
-pub fn create_llm_service(config: &LlmConfig) -> Result<Option<Box<dyn LlmService>>, LlmError> {
+///
+/// `model_override` 不为空时替代服务商配置中的模型
+pub fn create_llm_service(
+    config: &LlmConfig,
+    model_override: Option<&str>,
+) -> Result<Option<Box<dyn LlmService>>, LlmError> {
     if !config.enabled {
         return Ok(None);
     }
 
-    match config.provider.as_str() {
+    // 自定义提示词优先，空白视为未设置
+    let system_prompt = match config.custom_prompt.as_deref().map(str::trim) {
+        Some(prompt) if !prompt.is_empty() => prompt.to_string(),
+        _ => refine_prompt(config.language.as_deref(), &config.glossary),
+    };
+
+    let provider = resolve_llm_provider(&config.provider).ok_or_else(|| {
+        LlmError::Config(format!(
+            "未知的 LLM 服务商: {}（可用: {}）",
+            config.provider,
+            llm_provider_names()
+        ))
+    })?;
+
+    match provider {
         "DashScope" => {
             let dashscope_config = config
                 .dashscope
@@ -24,7 +48,8 @@ pub fn create_llm_service(config: &LlmConfig) -> Result<Option<Box<dyn LlmServic
                 .ok_or_else(|| LlmError::Config("DashScope LLM 配置缺失".to_string()))?;
             Ok(Some(Box::new(DashScopeLlm::new(
                 dashscope_config.api_key.clone(),
-                dashscope_config.model.clone(),
+                model_override.map_or_else(|| dashscope_config.model.clone(), str::to_string),
+                system_prompt,
             ))))
         }
         "OpenAI" => {
@@ -33,8 +58,13 @@ pub fn create_llm_service(config: &LlmConfig) -> Result<Option<Box<dyn LlmServic
                 .as_ref()
                 .ok_or_else(|| LlmError::Config("OpenAI LLM 配置缺失".to_string()))?;
             Ok(Some(Box::new(OpenAiLlm::new(
+                openai_config
+                    .base_url
+                    .clone()
+                    .unwrap_or_else(|| OPENAI_BASE_URL.to_string()),
                 openai_config.api_key.clone(),
-                openai_config.model.clone(),
+                model_override.map_or_else(|| openai_config.model.clone(), str::to_string),
+                system_prompt,
                 openai_config.temperature,
                 openai_config.max_tokens,
             ))))
@@ -46,13 +76,39 @@ pub fn create_llm_service(config: &LlmConfig) -> Result<Option<Box<dyn LlmServic
                 .ok_or_else(|| LlmError::Config("Ollama 配置缺失".to_string()))?;
             Ok(Some(Box::new(OllamaLlm::new(
                 ollama_config.endpoint.clone(),
-                ollama_config.model.clone(),
+                model_override.map_or_else(|| ollama_config.model.clone(), str::to_string),
+                system_prompt,
+                OllamaOptions {
+                    temperature: ollama_config.temperature,
+                    num_ctx: ollama_config.num_ctx,
+                },
+                ollama_config.keep_alive.clone(),
+            ))))
+        }
+        "Claude" => {
+            let claude_config = config
+                .claude
+                .as_ref()
+                .ok_or_else(|| LlmError::Config("Claude 配置缺失".to_string()))?;
+            Ok(Some(Box::new(ClaudeLlm::new(
+                claude_config.api_key.clone(),
+                model_override.map_or_else(|| claude_config.model.clone(), str::to_string),
+                system_prompt,
+                claude_config.max_tokens,
+            ))))
+        }
+        "Gemini" => {
+            let gemini_config = config
+                .gemini
+                .as_ref()
+                .ok_or_else(|| LlmError::Config("Gemini 配置缺失".to_string()))?;
+            Ok(Some(Box::new(GeminiLlm::new(
+                gemini_config.api_key.clone(),
+                model_override.map_or_else(|| gemini_config.model.clone(), str::to_string),
+                system_prompt,
             ))))
         }
-        _ => Err(LlmError::Config(format!(
-            "未知的 LLM 服务商: {}",
-            config.provider
-        ))),
+        provider => Err(LlmError::Config(format!("未知的 LLM 服务商: {}", provider))),
     }
 }
 
@@ -60,3 +116,13 @@ pub fn create_llm_service(config: &LlmConfig) -> Result<Option<Box<dyn LlmServic
 pub async fn test_ollama_api(endpoint: &str, model: &str) -> Result<String, LlmError> {
     ollama::test_api(endpoint, model).await
 }
+
+/// 测试 Claude API
+pub async fn test_claude_api(api_key: &str) -> Result<String, LlmError> {
+    claude::test_api(api_key).await
+}
+
+/// 测试 Gemini API
+pub async fn test_gemini_api(api_key: &str) -> Result<String, LlmError> {
+    gemini::test_api(api_key).await
+}
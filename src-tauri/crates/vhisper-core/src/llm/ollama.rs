@@ -1,24 +1,72 @@
 use async_trait::async_trait;
+use futures_util::StreamExt;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
 
-use super::traits::{LlmError, LlmService, REFINE_PROMPT};
+use super::traits::{api_error, refine_in_chunks, LineBuffer, LlmError, LlmService};
 
 /// Ollama 本地 LLM 服务
 pub struct OllamaLlm {
     endpoint: String,
     model: String,
+    /// 系统提示词
+    system_prompt: String,
+    options: OllamaOptions,
+    /// 模型在内存中的保留时间，None 时使用 Ollama 默认值
+    keep_alive: Option<String>,
     client: Client,
 }
 
+/// 生成参数（对应请求中的 `options`）
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct OllamaOptions {
+    pub temperature: f32,
+    /// 上下文窗口大小，None 时使用模型默认值
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_ctx: Option<u32>,
+}
+
 impl OllamaLlm {
-    pub fn new(endpoint: String, model: String) -> Self {
+    pub fn new(
+        endpoint: String,
+        model: String,
+        system_prompt: String,
+        options: OllamaOptions,
+        keep_alive: Option<String>,
+    ) -> Self {
         Self {
             endpoint,
             model,
+            system_prompt,
+            options,
+            keep_alive,
             client: Client::new(),
         }
     }
+
+    fn chat_url(&self) -> String {
+        format!("{}/api/chat", self.endpoint.trim_end_matches('/'))
+    }
+
+    fn chat_request(&self, text: &str, stream: bool) -> OllamaChatRequest {
+        OllamaChatRequest {
+            model: self.model.clone(),
+            messages: vec![
+                Message {
+                    role: "system".to_string(),
+                    content: self.system_prompt.clone(),
+                },
+                Message {
+                    role: "user".to_string(),
+                    content: text.to_string(),
+                },
+            ],
+            stream,
+            options: self.options,
+            keep_alive: self.keep_alive.clone(),
+        }
+    }
 }
 
 #[derive(Serialize)]
@@ -26,6 +74,9 @@ struct OllamaChatRequest {
     model: String,
     messages: Vec<Message>,
     stream: bool,
+    options: OllamaOptions,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    keep_alive: Option<String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -40,30 +91,13 @@ struct OllamaChatResponse {
     error: Option<String>,
 }
 
-#[async_trait]
-impl LlmService for OllamaLlm {
-    async fn refine_text(&self, text: &str) -> Result<String, LlmError> {
-        let url = format!("{}/api/chat", self.endpoint.trim_end_matches('/'));
-
-        let request = OllamaChatRequest {
-            model: self.model.clone(),
-            messages: vec![
-                Message {
-                    role: "system".to_string(),
-                    content: REFINE_PROMPT.to_string(),
-                },
-                Message {
-                    role: "user".to_string(),
-                    content: text.to_string(),
-                },
-            ],
-            stream: false,
-        };
-
+impl OllamaLlm {
+    /// 单次请求优化文本
+    async fn complete(&self, text: &str) -> Result<String, LlmError> {
         let response = self
             .client
-            .post(&url)
-            .json(&request)
+            .post(self.chat_url())
+            .json(&self.chat_request(text, false))
             .send()
             .await
             .map_err(|e| LlmError::Network(e.to_string()))?;
@@ -75,14 +109,14 @@ impl LlmService for OllamaLlm {
             .map_err(|e| LlmError::Network(e.to_string()))?;
 
         if !status.is_success() {
-            return Err(LlmError::Api(format!("HTTP {}: {}", status, body)));
+            return Err(api_error(format!("HTTP {}: {}", status, body)));
         }
 
         let result: OllamaChatResponse =
             serde_json::from_str(&body).map_err(|e| LlmError::Api(e.to_string()))?;
 
         if let Some(error) = result.error {
-            return Err(LlmError::Api(error));
+            return Err(api_error(error));
         }
 
         let output_text = result
@@ -92,6 +126,90 @@ impl LlmService for OllamaLlm {
 
         Ok(output_text.trim().to_string())
     }
+
+    /// 单次流式请求优化文本
+    async fn complete_streaming(
+        &self,
+        text: &str,
+        token_tx: &mpsc::Sender<String>,
+    ) -> Result<String, LlmError> {
+        let response = self
+            .client
+            .post(self.chat_url())
+            .json(&self.chat_request(text, true))
+            .send()
+            .await
+            .map_err(|e| LlmError::Network(e.to_string()))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(api_error(format!("HTTP {}: {}", status, body)));
+        }
+
+        // 流式响应每行一个 JSON 对象，message.content 为增量文本
+        let mut stream = response.bytes_stream();
+        let mut lines = LineBuffer::default();
+        let mut output_text = String::new();
+        let mut finished = false;
+        while !finished {
+            let batch = match stream.next().await {
+                Some(chunk) => lines.push(&chunk.map_err(|e| LlmError::Network(e.to_string()))?),
+                None => {
+                    finished = true;
+                    lines.finish()
+                }
+            };
+
+            for line in batch {
+                let chunk: OllamaChatResponse =
+                    serde_json::from_str(&line).map_err(|e| LlmError::Api(e.to_string()))?;
+                if let Some(error) = chunk.error {
+                    return Err(api_error(error));
+                }
+                if let Some(message) = chunk.message.filter(|m| !m.content.is_empty()) {
+                    output_text.push_str(&message.content);
+                    let _ = token_tx.send(message.content).await;
+                }
+            }
+        }
+
+        if output_text.trim().is_empty() {
+            return Ok(text.to_string());
+        }
+        Ok(output_text.trim().to_string())
+    }
+}
+
+#[async_trait]
+impl LlmService for OllamaLlm {
+    async fn refine_text(&self, text: &str) -> Result<String, LlmError> {
+        match self.complete(text).await {
+            // 超出上下文窗口时按句子分块重试一次
+            Err(LlmError::ContextLength(_)) => {
+                refine_in_chunks(text, |chunk| async move { self.complete(&chunk).await }).await
+            }
+            result => result,
+        }
+    }
+
+    async fn refine_text_streaming(
+        &self,
+        text: &str,
+        token_tx: mpsc::Sender<String>,
+    ) -> Result<String, LlmError> {
+        match self.complete_streaming(text, &token_tx).await {
+            // 上下文超限在输出前返回，分块重试后一次性推送完整结果
+            Err(LlmError::ContextLength(_)) => {
+                let refined =
+                    refine_in_chunks(text, |chunk| async move { self.complete(&chunk).await })
+                        .await?;
+                let _ = token_tx.send(refined.clone()).await;
+                Ok(refined)
+            }
+            result => result,
+        }
+    }
 }
 
 /// 测试 Ollama 服务连接
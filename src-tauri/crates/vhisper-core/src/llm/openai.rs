@@ -1,28 +1,74 @@
 use async_trait::async_trait;
+use futures_util::StreamExt;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
 
-use super::traits::{LlmError, LlmService, REFINE_PROMPT};
+use super::traits::{api_error, refine_in_chunks, LineBuffer, LlmError, LlmService};
 
 /// OpenAI LLM 服务
 pub struct OpenAiLlm {
+    /// API 地址（如 https://api.openai.com/v1）
+    base_url: String,
     api_key: String,
     model: String,
+    /// 系统提示词
+    system_prompt: String,
     temperature: f32,
     max_tokens: u32,
     client: Client,
 }
 
 impl OpenAiLlm {
-    pub fn new(api_key: String, model: String, temperature: f32, max_tokens: u32) -> Self {
+    pub fn new(
+        base_url: String,
+        api_key: String,
+        model: String,
+        system_prompt: String,
+        temperature: f32,
+        max_tokens: u32,
+    ) -> Self {
         Self {
+            base_url,
             api_key,
             model,
+            system_prompt,
             temperature,
             max_tokens,
             client: Client::new(),
         }
     }
+
+    fn chat_request(&self, text: &str, stream: bool) -> OpenAiRequest {
+        OpenAiRequest {
+            model: self.model.clone(),
+            messages: vec![
+                Message {
+                    role: "system".to_string(),
+                    content: self.system_prompt.clone(),
+                },
+                Message {
+                    role: "user".to_string(),
+                    content: text.to_string(),
+                },
+            ],
+            temperature: self.temperature,
+            max_tokens: self.max_tokens,
+            stream,
+        }
+    }
+
+    /// 发送 Chat Completions 请求
+    async fn send(&self, request: &OpenAiRequest) -> Result<reqwest::Response, LlmError> {
+        self.client
+            .post(format!("{}/chat/completions", self.base_url.trim_end_matches('/')))
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .header("Content-Type", "application/json")
+            .json(request)
+            .send()
+            .await
+            .map_err(|e| LlmError::Network(e.to_string()))
+    }
 }
 
 #[derive(Serialize)]
@@ -31,6 +77,9 @@ struct OpenAiRequest {
     messages: Vec<Message>,
     temperature: f32,
     max_tokens: u32,
+    /// 使用 SSE 流式返回
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    stream: bool,
 }
 
 #[derive(Serialize)]
@@ -60,34 +109,28 @@ struct OpenAiError {
     message: String,
 }
 
-#[async_trait]
-impl LlmService for OpenAiLlm {
-    async fn refine_text(&self, text: &str) -> Result<String, LlmError> {
-        let request = OpenAiRequest {
-            model: self.model.clone(),
-            messages: vec![
-                Message {
-                    role: "system".to_string(),
-                    content: REFINE_PROMPT.to_string(),
-                },
-                Message {
-                    role: "user".to_string(),
-                    content: text.to_string(),
-                },
-            ],
-            temperature: self.temperature,
-            max_tokens: self.max_tokens,
-        };
+/// 流式响应中的一块数据
+#[derive(Deserialize)]
+struct OpenAiStreamChunk {
+    #[serde(default)]
+    choices: Vec<StreamChoice>,
+    error: Option<OpenAiError>,
+}
 
-        let response = self
-            .client
-            .post("https://api.openai.com/v1/chat/completions")
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| LlmError::Network(e.to_string()))?;
+#[derive(Deserialize)]
+struct StreamChoice {
+    delta: StreamDelta,
+}
+
+#[derive(Deserialize)]
+struct StreamDelta {
+    content: Option<String>,
+}
+
+impl OpenAiLlm {
+    /// 单次请求优化文本
+    async fn complete(&self, text: &str) -> Result<String, LlmError> {
+        let response = self.send(&self.chat_request(text, false)).await?;
 
         let status = response.status();
         let body = response
@@ -96,14 +139,14 @@ impl LlmService for OpenAiLlm {
             .map_err(|e| LlmError::Network(e.to_string()))?;
 
         if !status.is_success() {
-            return Err(LlmError::Api(format!("HTTP {}: {}", status, body)));
+            return Err(api_error(format!("HTTP {}: {}", status, body)));
         }
 
         let result: OpenAiResponse =
             serde_json::from_str(&body).map_err(|e| LlmError::Api(e.to_string()))?;
 
         if let Some(error) = result.error {
-            return Err(LlmError::Api(error.message));
+            return Err(api_error(error.message));
         }
 
         let output_text = result
@@ -113,4 +156,90 @@ impl LlmService for OpenAiLlm {
 
         Ok(output_text.trim().to_string())
     }
+
+    /// 单次流式请求优化文本
+    async fn complete_streaming(
+        &self,
+        text: &str,
+        token_tx: &mpsc::Sender<String>,
+    ) -> Result<String, LlmError> {
+        let response = self.send(&self.chat_request(text, true)).await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(api_error(format!("HTTP {}: {}", status, body)));
+        }
+
+        // SSE：每个事件为 `data: {json}`，以 `data: [DONE]` 结束
+        let mut stream = response.bytes_stream();
+        let mut lines = LineBuffer::default();
+        let mut output_text = String::new();
+        let mut finished = false;
+        while !finished {
+            let batch = match stream.next().await {
+                Some(chunk) => lines.push(&chunk.map_err(|e| LlmError::Network(e.to_string()))?),
+                None => {
+                    finished = true;
+                    lines.finish()
+                }
+            };
+
+            for line in batch {
+                let Some(data) = line.strip_prefix("data:").map(str::trim) else {
+                    continue;
+                };
+                if data == "[DONE]" {
+                    finished = true;
+                    break;
+                }
+                let chunk: OpenAiStreamChunk =
+                    serde_json::from_str(data).map_err(|e| LlmError::Api(e.to_string()))?;
+                if let Some(error) = chunk.error {
+                    return Err(api_error(error.message));
+                }
+                let tokens = chunk.choices.into_iter().filter_map(|c| c.delta.content);
+                for token in tokens.filter(|t| !t.is_empty()) {
+                    output_text.push_str(&token);
+                    let _ = token_tx.send(token).await;
+                }
+            }
+        }
+
+        if output_text.trim().is_empty() {
+            return Ok(text.to_string());
+        }
+        Ok(output_text.trim().to_string())
+    }
+}
+
+#[async_trait]
+impl LlmService for OpenAiLlm {
+    async fn refine_text(&self, text: &str) -> Result<String, LlmError> {
+        match self.complete(text).await {
+            // 超出上下文窗口时按句子分块重试一次
+            Err(LlmError::ContextLength(_)) => {
+                refine_in_chunks(text, |chunk| async move { self.complete(&chunk).await }).await
+            }
+            result => result,
+        }
+    }
+
+    async fn refine_text_streaming(
+        &self,
+        text: &str,
+        token_tx: mpsc::Sender<String>,
+    ) -> Result<String, LlmError> {
+        match self.complete_streaming(text, &token_tx).await {
+            // 上下文超限在输出前返回，分块重试后一次性推送完整结果
+            Err(LlmError::ContextLength(_)) => {
+                let refined =
+                    refine_in_chunks(text, |chunk| async move { self.complete(&chunk).await })
+                        .await?;
+                let _ = token_tx.send(refined.clone()).await;
+                Ok(refined)
+            }
+            result => result,
+        }
+    }
 }
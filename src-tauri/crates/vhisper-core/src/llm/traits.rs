@@ -1,4 +1,6 @@
 use async_trait::async_trait;
+use std::future::Future;
+use tokio::sync::mpsc;
 
 #[derive(Debug, thiserror::Error)]
 pub enum LlmError {
@@ -8,6 +10,140 @@ pub enum LlmError {
     Network(String),
     #[error("Configuration error: {0}")]
     Config(String),
+    #[error("Context length exceeded: {0}")]
+    ContextLength(String),
+}
+
+/// 上下文超限时分块优化，每块的最大字符数
+const CHUNK_MAX_CHARS: usize = 500;
+
+/// 服务商返回的上下文超限错误中常见的关键词（小写）
+const CONTEXT_LENGTH_PATTERNS: &[&str] = &[
+    "context_length_exceeded",
+    "context length",
+    "context window",
+    "maximum context",
+    "too many tokens",
+    "prompt is too long",
+];
+
+/// 将服务商返回的错误信息转换为 LLM 错误，上下文超限单独区分
+pub(crate) fn api_error(message: String) -> LlmError {
+    let lower = message.to_lowercase();
+    if CONTEXT_LENGTH_PATTERNS.iter().any(|p| lower.contains(p)) {
+        LlmError::ContextLength(message)
+    } else {
+        LlmError::Api(message)
+    }
+}
+
+/// 按句子切分文本，相邻句子合并为不超过 `max_chars` 个字符的块
+///
+/// 单个句子超过 `max_chars` 时先在逗号、分号处切分，仍过长的部分按字符数截断
+pub fn split_sentence_chunks(text: &str, max_chars: usize) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut current = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        current.push(c);
+        // 英文标点后需有空白才视为句末，避免 "3.5"、"e.g." 中间的点
+        let end = match c {
+            '。' | '！' | '？' | '\n' => true,
+            '.' | '!' | '?' => chars.peek().is_none_or(|next| next.is_whitespace()),
+            _ => false,
+        };
+        if end {
+            sentences.push(std::mem::take(&mut current));
+        }
+    }
+    if !current.trim().is_empty() {
+        sentences.push(current);
+    }
+
+    let pieces = sentences
+        .into_iter()
+        .flat_map(|sentence| split_long_sentence(sentence, max_chars));
+    merge_pieces(pieces, max_chars)
+}
+
+/// 将超过 `max_chars` 的句子切分为不超过 `max_chars` 个字符的片段
+fn split_long_sentence(sentence: String, max_chars: usize) -> Vec<String> {
+    if sentence.chars().count() <= max_chars {
+        return vec![sentence];
+    }
+
+    let mut clauses = Vec::new();
+    let mut current = String::new();
+    for c in sentence.chars() {
+        current.push(c);
+        if matches!(c, '，' | '；' | '、' | ',' | ';') {
+            clauses.push(std::mem::take(&mut current));
+        }
+    }
+    if !current.is_empty() {
+        clauses.push(current);
+    }
+
+    // 没有逗号的长句按字符数截断
+    let pieces = clauses.into_iter().flat_map(|clause| {
+        let chars: Vec<char> = clause.chars().collect();
+        chars
+            .chunks(max_chars.max(1))
+            .map(|part| part.iter().collect::<String>())
+            .collect::<Vec<_>>()
+    });
+    merge_pieces(pieces, max_chars)
+}
+
+/// 合并相邻片段，每块不超过 `max_chars` 个字符
+fn merge_pieces(pieces: impl IntoIterator<Item = String>, max_chars: usize) -> Vec<String> {
+    let mut chunks: Vec<String> = Vec::new();
+    let mut chunk = String::new();
+    for piece in pieces {
+        if !chunk.is_empty() && chunk.chars().count() + piece.chars().count() > max_chars {
+            chunks.push(std::mem::take(&mut chunk));
+        }
+        chunk.push_str(&piece);
+    }
+    if !chunk.trim().is_empty() {
+        chunks.push(chunk);
+    }
+    chunks
+}
+
+/// 拼接分块优化的结果，英文等以空格分词的文本块之间补一个空格
+fn join_chunks(chunks: &[String]) -> String {
+    let mut joined = String::new();
+    for chunk in chunks.iter().map(|c| c.trim()).filter(|c| !c.is_empty()) {
+        let needs_space = joined
+            .chars()
+            .last()
+            .is_some_and(|c| c.is_ascii_punctuation() || c.is_ascii_alphanumeric())
+            && chunk.starts_with(|c: char| c.is_ascii_alphanumeric());
+        if needs_space {
+            joined.push(' ');
+        }
+        joined.push_str(chunk);
+    }
+    joined
+}
+
+/// 文本超出上下文窗口时的重试：按句子分块，逐块调用 `refine` 后拼接
+pub(crate) async fn refine_in_chunks<F, Fut>(text: &str, refine: F) -> Result<String, LlmError>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<String, LlmError>>,
+{
+    let chunks = split_sentence_chunks(text, CHUNK_MAX_CHARS);
+    tracing::warn!(
+        "LLM context length exceeded, retrying in {} chunks",
+        chunks.len()
+    );
+    let mut refined = Vec::with_capacity(chunks.len());
+    for chunk in chunks {
+        refined.push(refine(chunk).await?);
+    }
+    Ok(join_chunks(&refined))
 }
 
 /// LLM 服务 trait
@@ -15,6 +151,53 @@ pub enum LlmError {
 pub trait LlmService: Send + Sync {
     /// 优化文本
     async fn refine_text(&self, text: &str) -> Result<String, LlmError>;
+
+    /// 优化文本，生成过程中的增量文本通过 `token_tx` 推送，返回完整结果
+    ///
+    /// 默认不支持流式输出，调用 `refine_text` 后一次性推送完整结果
+    async fn refine_text_streaming(
+        &self,
+        text: &str,
+        token_tx: mpsc::Sender<String>,
+    ) -> Result<String, LlmError> {
+        let refined = self.refine_text(text).await?;
+        let _ = token_tx.send(refined.clone()).await;
+        Ok(refined)
+    }
+}
+
+/// 按行切分流式响应（NDJSON / SSE），不完整的行留到下一块数据
+#[derive(Default)]
+pub(crate) struct LineBuffer {
+    pending: Vec<u8>,
+}
+
+impl LineBuffer {
+    /// 追加一块数据，返回其中已完整的行
+    pub(crate) fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.pending.extend_from_slice(chunk);
+        let Some(last_newline) = self.pending.iter().rposition(|&b| b == b'\n') else {
+            return Vec::new();
+        };
+        let rest = self.pending.split_off(last_newline + 1);
+        let complete = std::mem::replace(&mut self.pending, rest);
+        complete
+            .split(|&b| b == b'\n')
+            .map(|line| String::from_utf8_lossy(line).trim().to_string())
+            .filter(|line| !line.is_empty())
+            .collect()
+    }
+
+    /// 数据结束，返回最后一行（没有换行结尾时）
+    pub(crate) fn finish(&mut self) -> Vec<String> {
+        let rest = std::mem::take(&mut self.pending);
+        let line = String::from_utf8_lossy(&rest).trim().to_string();
+        if line.is_empty() {
+            Vec::new()
+        } else {
+            vec![line]
+        }
+    }
 }
 
 /// 用于文本修正的系统提示词
@@ -34,3 +217,101 @@ pub const REFINE_PROMPT: &str = r#"你是一个语音识别文本校对助手。
 只输出修正后的文本，不要添加任何解释。如果输入文本没有错误，原样输出。
 
 输入文本："#;
+
+/// 常见语言代码对应的中文名称
+fn language_name(code: &str) -> &str {
+    match code {
+        "zh" => "中文",
+        "en" => "英文",
+        "ja" => "日文",
+        "ko" => "韩文",
+        "yue" => "粤语",
+        other => other,
+    }
+}
+
+/// 生成文本修正的系统提示词
+///
+/// - 指定语言时要求保持该语言输出
+/// - 术语表不为空时要求发音相近的词按术语表写法修正
+pub fn refine_prompt(language: Option<&str>, glossary: &[String]) -> String {
+    if language.is_none() && glossary.is_empty() {
+        return REFINE_PROMPT.to_string();
+    }
+
+    let mut prompt = REFINE_PROMPT.trim_end_matches("输入文本：").trim_end().to_string();
+    if let Some(language) = language {
+        prompt.push_str(&format!(
+            "\n\n注意：文本语言为{}，保持原语言输出，不要翻译。",
+            language_name(language)
+        ));
+    }
+    if !glossary.is_empty() {
+        prompt.push_str(&format!(
+            "\n\n术语表（文本中发音相近的词按以下写法修正）：{}",
+            glossary.join("、")
+        ));
+    }
+    prompt.push_str("\n\n输入文本：");
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sentences_are_merged_up_to_the_limit() {
+        let chunks = split_sentence_chunks("第一句。第二句！第三句？", 8);
+        assert_eq!(chunks, ["第一句。第二句！", "第三句？"]);
+    }
+
+    #[test]
+    fn english_decimal_point_does_not_end_a_sentence() {
+        let chunks = split_sentence_chunks("Version 3.5 is out. It is fast.", 20);
+        assert_eq!(chunks, ["Version 3.5 is out.", " It is fast."]);
+    }
+
+    #[test]
+    fn long_sentence_is_split_at_commas() {
+        let text = "今天我们讨论预算，然后确认排期，最后分配任务。";
+        let chunks = split_sentence_chunks(text, 10);
+        assert_eq!(
+            chunks,
+            ["今天我们讨论预算，", "然后确认排期，", "最后分配任务。"]
+        );
+        assert_eq!(chunks.concat(), text);
+    }
+
+    #[test]
+    fn long_run_without_punctuation_is_split_by_length() {
+        let text = "字".repeat(25);
+        let chunks = split_sentence_chunks(&text, 10);
+        assert_eq!(
+            chunks.iter().map(|c| c.chars().count()).collect::<Vec<_>>(),
+            [10, 10, 5]
+        );
+        assert_eq!(chunks.concat(), text);
+    }
+
+    #[test]
+    fn context_length_errors_are_recognized() {
+        let cases = [
+            "This model's maximum context length is 8192 tokens",
+            "{\"code\":\"context_length_exceeded\"}",
+            "prompt is too long: 250000 tokens > 200000 maximum",
+            "Input exceeds the context window",
+        ];
+        for message in cases {
+            assert!(
+                matches!(api_error(message.to_string()), LlmError::ContextLength(_)),
+                "{}",
+                message
+            );
+        }
+        assert!(matches!(
+            api_error("HTTP 429: rate limited".to_string()),
+            LlmError::Api(_)
+        ));
+    }
+}
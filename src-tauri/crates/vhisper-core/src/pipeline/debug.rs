@@ -0,0 +1,109 @@
+//! 调试用录音另存
+//!
+//! 将送往 ASR 的原始录音写成 WAV，并附带一份 JSON 记录幅度统计，便于排查识别问题
+
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::voice::PipelineError;
+use crate::audio::{encode_to_wav, peak_amplitude};
+use crate::config::resolve_data_path;
+
+/// 录音旁路文件内容
+#[derive(Debug, Serialize)]
+struct RecordingStats {
+    sample_rate: u32,
+    channels: u16,
+    /// 录音时长（毫秒）
+    duration_ms: u64,
+    /// 峰值幅度（0.0 - 1.0）
+    max_amplitude: f32,
+    /// 平均幅度（绝对值均值）
+    avg_amplitude: f32,
+}
+
+/// 保存录音到 `dir/<时间戳>.wav`，同名 `.json` 记录幅度统计，返回 WAV 路径
+pub(crate) fn save_recording(
+    dir: &Path,
+    samples: &[f32],
+    sample_rate: u32,
+    channels: u16,
+) -> Result<PathBuf, PipelineError> {
+    let dir = resolve_data_path(dir);
+    fs::create_dir_all(&dir).map_err(|e| {
+        PipelineError::Other(format!("Failed to create {}: {}", dir.display(), e))
+    })?;
+
+    // 同一秒内多次录音时追加序号，避免覆盖
+    let stem = utc_timestamp();
+    let mut wav_path = dir.join(format!("{}.wav", stem));
+    let mut index = 1;
+    while wav_path.exists() {
+        wav_path = dir.join(format!("{}-{}.wav", stem, index));
+        index += 1;
+    }
+
+    let wav = encode_to_wav(samples, sample_rate, channels)?;
+    fs::write(&wav_path, wav).map_err(|e| {
+        PipelineError::Other(format!("Failed to write {}: {}", wav_path.display(), e))
+    })?;
+
+    let frames = samples.len() / channels.max(1) as usize;
+    let stats = RecordingStats {
+        sample_rate,
+        channels,
+        duration_ms: if sample_rate == 0 {
+            0
+        } else {
+            frames as u64 * 1000 / sample_rate as u64
+        },
+        max_amplitude: peak_amplitude(samples),
+        avg_amplitude: if samples.is_empty() {
+            0.0
+        } else {
+            (samples.iter().map(|s| s.abs() as f64).sum::<f64>() / samples.len() as f64) as f32
+        },
+    };
+    let json_path = wav_path.with_extension("json");
+    let json = serde_json::to_string_pretty(&stats)
+        .map_err(|e| PipelineError::Other(e.to_string()))?;
+    fs::write(&json_path, json).map_err(|e| {
+        PipelineError::Other(format!("Failed to write {}: {}", json_path.display(), e))
+    })?;
+
+    Ok(wav_path)
+}
+
+/// 当前 UTC 时间，格式如 `2024-01-02T15-04-05`（文件名中不能用冒号）
+fn utc_timestamp() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+    let secs_of_day = secs % 86_400;
+    format!(
+        "{:04}-{:02}-{:02}T{:02}-{:02}-{:02}",
+        year,
+        month,
+        day,
+        secs_of_day / 3600,
+        secs_of_day % 3600 / 60,
+        secs_of_day % 60
+    )
+}
+
+/// 自 1970-01-01 起的天数转换为公历年月日
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    (yoe + era * 400 + i64::from(month <= 2), month, day)
+}
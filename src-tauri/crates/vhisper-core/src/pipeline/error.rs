@@ -0,0 +1,184 @@
+//! 错误分类
+//!
+//! 将 `PipelineError` 归类为稳定的错误码，界面据此显示对应的帮助（如打开麦克风权限设置）
+
+use serde::Serialize;
+
+use super::voice::PipelineError;
+use crate::asr::AsrError;
+
+/// 错误码
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+pub enum PipelineErrorKind {
+    /// 录音全静音，通常是未授予麦克风权限
+    MicPermission,
+    /// 录音音量过低
+    AudioTooQuiet,
+    /// 录音设备打开或采集失败
+    AudioDevice,
+    /// 没有录到有效音频（录音过短）
+    NoAudio,
+    /// ASR 认证失败（API Key 无效、无权限等）
+    AsrAuth,
+    /// ASR 网络错误（连接失败、超时等）
+    AsrNetwork,
+    /// ASR 配置缺失或有误
+    AsrConfig,
+    /// 其他 ASR 错误
+    AsrFailed,
+    /// 识别置信度过低
+    LowConfidence,
+    /// LLM 优化失败
+    LlmFailed,
+    /// 文本输出到目标应用失败
+    OutputFailed,
+    /// 用户取消了录音或识别
+    Cancelled,
+    /// 其他错误
+    Other,
+}
+
+/// `processing-error` 事件内容
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessingError {
+    pub code: PipelineErrorKind,
+    pub message: String,
+}
+
+impl ProcessingError {
+    pub fn new(code: PipelineErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<&PipelineError> for ProcessingError {
+    fn from(error: &PipelineError) -> Self {
+        Self::new(error.kind(), error.to_string())
+    }
+}
+
+impl PipelineError {
+    /// 错误分类
+    pub fn kind(&self) -> PipelineErrorKind {
+        match self {
+            PipelineError::SilentAudio => PipelineErrorKind::MicPermission,
+            PipelineError::AudioTooQuiet => PipelineErrorKind::AudioTooQuiet,
+            PipelineError::Audio(_) => PipelineErrorKind::AudioDevice,
+            PipelineError::Asr(error) => match error {
+                AsrError::NoAudio => PipelineErrorKind::NoAudio,
+                AsrError::Network(_) => PipelineErrorKind::AsrNetwork,
+                AsrError::Config(_) => PipelineErrorKind::AsrConfig,
+                AsrError::Cancelled => PipelineErrorKind::Cancelled,
+                AsrError::Api(message) | AsrError::Session(message) if is_auth_error(message) => {
+                    PipelineErrorKind::AsrAuth
+                }
+                _ => PipelineErrorKind::AsrFailed,
+            },
+            PipelineError::LowConfidence(_) => PipelineErrorKind::LowConfidence,
+            PipelineError::Cancelled => PipelineErrorKind::Cancelled,
+            PipelineError::Llm(_) => PipelineErrorKind::LlmFailed,
+            PipelineError::Other(_) => PipelineErrorKind::Other,
+        }
+    }
+}
+
+/// 根据服务端错误信息判断是否为认证失败（各服务商没有统一的错误码）
+///
+/// HTTP 状态码需作为独立的数字出现，避免误匹配请求 ID 等数字串
+fn is_auth_error(message: &str) -> bool {
+    const STATUS_CODES: &[&str] = &["401", "403"];
+    const PATTERNS: &[&str] = &[
+        "unauthorized",
+        "forbidden",
+        "authentication",
+        "api key",
+        "apikey",
+        "access denied",
+    ];
+    let message = message.to_ascii_lowercase();
+    message
+        .split(|c: char| !c.is_ascii_digit())
+        .any(|number| STATUS_CODES.contains(&number))
+        || PATTERNS.iter().any(|pattern| message.contains(pattern))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::AudioError;
+    use crate::llm::LlmError;
+
+    #[test]
+    fn kind_of_each_error() {
+        let cases = [
+            (PipelineError::SilentAudio, PipelineErrorKind::MicPermission),
+            (
+                PipelineError::AudioTooQuiet,
+                PipelineErrorKind::AudioTooQuiet,
+            ),
+            (
+                PipelineError::Audio(AudioError::NoInputDevice),
+                PipelineErrorKind::AudioDevice,
+            ),
+            (
+                PipelineError::Asr(AsrError::NoAudio),
+                PipelineErrorKind::NoAudio,
+            ),
+            (
+                PipelineError::Asr(AsrError::Network("timeout".into())),
+                PipelineErrorKind::AsrNetwork,
+            ),
+            (
+                PipelineError::Asr(AsrError::Config("缺少 API Key".into())),
+                PipelineErrorKind::AsrConfig,
+            ),
+            (
+                PipelineError::Asr(AsrError::Cancelled),
+                PipelineErrorKind::Cancelled,
+            ),
+            (
+                PipelineError::Asr(AsrError::Api("HTTP 401 Unauthorized".into())),
+                PipelineErrorKind::AsrAuth,
+            ),
+            (
+                PipelineError::Asr(AsrError::Session("Invalid API key".into())),
+                PipelineErrorKind::AsrAuth,
+            ),
+            (
+                PipelineError::Asr(AsrError::Api("rate limited".into())),
+                PipelineErrorKind::AsrFailed,
+            ),
+            (
+                PipelineError::Asr(AsrError::Encoding("bad wav".into())),
+                PipelineErrorKind::AsrFailed,
+            ),
+            (
+                PipelineError::LowConfidence(0.3),
+                PipelineErrorKind::LowConfidence,
+            ),
+            (
+                PipelineError::Llm(LlmError::Network("timeout".into())),
+                PipelineErrorKind::LlmFailed,
+            ),
+            (PipelineError::Cancelled, PipelineErrorKind::Cancelled),
+            (
+                PipelineError::Other("boom".into()),
+                PipelineErrorKind::Other,
+            ),
+        ];
+        for (error, kind) in cases {
+            assert_eq!(error.kind(), kind, "{}", error);
+        }
+    }
+
+    #[test]
+    fn auth_status_code_must_stand_alone() {
+        assert!(is_auth_error("HTTP 403"));
+        assert!(is_auth_error("status=401;"));
+        assert!(!is_auth_error("request id 14013 failed"));
+        assert!(!is_auth_error("HTTP 500"));
+    }
+}
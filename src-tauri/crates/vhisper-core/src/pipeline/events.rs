@@ -0,0 +1,111 @@
+//! 处理流程阶段事件
+//!
+//! 在 ASR / LLM / 输出等阶段边界发出带时间戳的事件，便于前端展示进度和定位卡住的阶段
+
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::asr::WordInfo;
+
+/// 处理阶段
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum PipelineStage {
+    AsrStarted,
+    AsrFinished,
+    LlmStarted,
+    LlmFinished,
+    OutputStarted,
+}
+
+impl PipelineStage {
+    /// 对应的前端事件名
+    pub fn event_name(&self) -> &'static str {
+        match self {
+            PipelineStage::AsrStarted => "asr-started",
+            PipelineStage::AsrFinished => "asr-finished",
+            PipelineStage::LlmStarted => "llm-started",
+            PipelineStage::LlmFinished => "llm-finished",
+            PipelineStage::OutputStarted => "output-started",
+        }
+    }
+}
+
+/// 阶段事件
+#[derive(Debug, Clone, Serialize)]
+pub struct PipelineEvent {
+    pub stage: PipelineStage,
+    /// Unix 时间戳（毫秒）
+    pub timestamp_ms: u64,
+}
+
+impl PipelineEvent {
+    /// 以当前时间创建事件
+    pub fn now(stage: PipelineStage) -> Self {
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        Self {
+            stage,
+            timestamp_ms,
+        }
+    }
+}
+
+/// 一次处理各阶段耗时（毫秒）
+///
+/// 双声道分别识别时为两个声道耗时之和；未输出文本时 `output_ms` 为 0
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PipelineMetrics {
+    /// 音频编码（混音、重采样、编码为服务商格式）
+    pub encode_ms: u64,
+    /// ASR 识别（含重试和备用服务商）
+    pub asr_ms: u64,
+    /// LLM 优化
+    pub llm_ms: u64,
+    /// 文本输出到前台应用
+    pub output_ms: u64,
+    /// 从停止录音到输出完成的总耗时
+    pub total_ms: u64,
+}
+
+/// 阶段事件监听器
+pub type PipelineEventListener = Arc<dyn Fn(PipelineEvent) + Send + Sync>;
+
+/// 识别中间结果监听器（参数为当前的识别假设文本）
+pub type PartialTranscriptListener = Arc<dyn Fn(String) + Send + Sync>;
+
+/// ASR 网络错误重试事件
+#[derive(Debug, Clone, Serialize)]
+pub struct AsrRetryEvent {
+    /// 第几次重试（从 1 开始）
+    pub attempt: u32,
+    pub max_retries: u32,
+    /// 本次重试前的等待时间（毫秒）
+    pub delay_ms: u64,
+    /// 触发重试的错误信息
+    pub error: String,
+}
+
+/// ASR 重试监听器
+pub type AsrRetryListener = Arc<dyn Fn(AsrRetryEvent) + Send + Sync>;
+
+/// 录音达到最长时长监听器（录音已自动停止采集）
+pub type MaxDurationListener = Arc<dyn Fn() + Send + Sync>;
+
+/// 自动结束模式下说话后持续静音监听器（录音已自动停止采集）
+pub type SilenceListener = Arc<dyn Fn() + Send + Sync>;
+
+/// 实时音量监听器（参数为 0.0 - 1.0 的 RMS 幅度，约 20Hz）
+pub type AudioLevelListener = Arc<dyn Fn(f32) + Send + Sync>;
+
+/// 实际使用的 ASR 服务商监听器（参数为服务商名称，备用服务商生效时与配置不同）
+pub type AsrProviderListener = Arc<dyn Fn(String) + Send + Sync>;
+
+/// LLM 优化中间结果监听器（参数为目前已生成的文本）
+pub type LlmPartialListener = Arc<dyn Fn(String) + Send + Sync>;
+
+/// 低置信度词监听器（参数为置信度低于阈值的词）
+pub type LowConfidenceWordsListener = Arc<dyn Fn(Vec<WordInfo>) + Send + Sync>;
@@ -0,0 +1,158 @@
+//! 基于音频文件的识别
+//!
+//! 不经过录音器，直接读取音频文件（WAV、MP3 等）送入 ASR，用于基准测试、调试和单文件转写
+
+use futures_util::future::join_all;
+use serde::Serialize;
+use std::path::Path;
+use std::time::Instant;
+
+use super::PipelineError;
+use crate::asr::{create_asr_service, select_sample_rate, AsrResult, AsrService};
+use crate::audio::{
+    decode_audio, encode_to_pcm, encode_to_wav, read_audio_file, resample, AudioError,
+};
+use crate::config::provider::resolve_asr_provider;
+use crate::config::settings::AsrConfig;
+
+/// 单个服务商的基准测试结果
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchmarkResult {
+    pub provider: String,
+    pub text: String,
+    /// 识别耗时（毫秒）
+    pub latency_ms: u64,
+    pub error: Option<String>,
+}
+
+/// 按服务商要求重采样并编码音频数据，返回编码后的数据和实际采样率
+pub(crate) fn prepare_audio(
+    service: &dyn AsrService,
+    provider: &str,
+    samples: &[f32],
+    sample_rate: u32,
+) -> Result<(Vec<u8>, u32), AudioError> {
+    let target_rate = select_sample_rate(service.supported_sample_rates(), sample_rate);
+    let resampled;
+    let samples = if target_rate != sample_rate {
+        tracing::info!("Resampling for {}: {}Hz -> {}Hz", provider, sample_rate, target_rate);
+        resampled = resample(samples, sample_rate, target_rate);
+        &resampled[..]
+    } else {
+        samples
+    };
+
+    let audio_data = if resolve_asr_provider(provider) == Some("OpenAIWhisper") {
+        // OpenAI Whisper 需要 WAV 格式
+        encode_to_wav(samples, target_rate, 1)?
+    } else {
+        // 其他服务使用 PCM
+        encode_to_pcm(samples)
+    };
+    Ok((audio_data, target_rate))
+}
+
+/// 已配置的 ASR 服务商列表
+pub fn configured_asr_providers(config: &AsrConfig) -> Vec<&'static str> {
+    let mut providers = Vec::new();
+    if config.qwen.is_some() {
+        providers.push("Qwen");
+    }
+    if config.dashscope.is_some() {
+        providers.push("DashScope");
+    }
+    if config.openai.is_some() {
+        providers.push("OpenAIWhisper");
+    }
+    if config.deepgram.is_some() {
+        providers.push("Deepgram");
+    }
+    if config.azure.is_some() {
+        providers.push("Azure");
+    }
+    if config.assemblyai.is_some() {
+        providers.push("AssemblyAI");
+    }
+    if config.funasr.is_some() {
+        providers.push("FunAsr");
+    }
+    if config.sherpa.is_some() {
+        providers.push("Sherpa");
+    }
+    providers
+}
+
+/// 使用当前 ASR 服务商识别音频文件
+pub async fn transcribe_file(
+    config: &AsrConfig,
+    path: impl AsRef<Path>,
+) -> Result<AsrResult, PipelineError> {
+    let (samples, sample_rate) = read_audio_file(path)?;
+    recognize_samples(config, &samples, sample_rate).await
+}
+
+/// 使用当前 ASR 服务商识别内存中的音频数据（如界面拖入的文件内容）
+pub async fn transcribe_audio_data(
+    config: &AsrConfig,
+    data: &[u8],
+    extension: Option<&str>,
+) -> Result<AsrResult, PipelineError> {
+    let (samples, sample_rate) = decode_audio(data, extension)?;
+    recognize_samples(config, &samples, sample_rate).await
+}
+
+async fn recognize_samples(
+    config: &AsrConfig,
+    samples: &[f32],
+    sample_rate: u32,
+) -> Result<AsrResult, PipelineError> {
+    let asr_service = create_asr_service(config)?;
+    let (audio_data, sample_rate) =
+        prepare_audio(asr_service.as_ref(), &config.provider, samples, sample_rate)?;
+    Ok(asr_service.recognize(&audio_data, sample_rate).await?)
+}
+
+/// 用同一段音频并发测试所有已配置的 ASR 服务商
+pub async fn benchmark_providers(
+    config: &AsrConfig,
+    audio_path: impl AsRef<Path>,
+) -> Result<Vec<BenchmarkResult>, PipelineError> {
+    let (samples, sample_rate) = read_audio_file(audio_path)?;
+    let samples = &samples;
+
+    let tasks = configured_asr_providers(config).into_iter().map(|provider| {
+        let mut provider_config = config.clone();
+        provider_config.provider = provider.to_string();
+
+        async move {
+            let start = Instant::now();
+            let result: Result<AsrResult, PipelineError> = async {
+                let asr_service = create_asr_service(&provider_config)?;
+                let (audio_data, rate) =
+                    prepare_audio(asr_service.as_ref(), provider, samples, sample_rate)?;
+                Ok(asr_service.recognize(&audio_data, rate).await?)
+            }
+            .await;
+            let latency_ms = start.elapsed().as_millis() as u64;
+
+            tracing::info!("Benchmark {}: {}ms, {:?}", provider, latency_ms, result);
+
+            match result {
+                Ok(asr_result) => BenchmarkResult {
+                    provider: provider.to_string(),
+                    text: asr_result.text,
+                    latency_ms,
+                    error: None,
+                },
+                Err(e) => BenchmarkResult {
+                    provider: provider.to_string(),
+                    text: String::new(),
+                    latency_ms,
+                    error: Some(e.to_string()),
+                },
+            }
+        }
+    });
+
+    Ok(join_all(tasks).await)
+}
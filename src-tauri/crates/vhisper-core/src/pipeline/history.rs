@@ -0,0 +1,306 @@
+//! 识别历史记录
+//!
+//! 保存最近的识别结果，超出容量时丢弃最早的记录，持久化到数据目录下的 history.json；
+//! 开启加密后改为保存到 history.enc（AES-256-GCM，密钥由用户口令经 PBKDF2 派生，不落盘）
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs;
+use std::num::NonZeroU32;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::storage::{data_dir, write_atomic, ConfigError};
+
+/// 历史记录文件名（与 config.json 同目录）
+const HISTORY_FILE: &str = "history.json";
+
+/// 加密的历史记录文件名
+const ENCRYPTED_HISTORY_FILE: &str = "history.enc";
+
+/// PBKDF2-HMAC-SHA256 迭代次数（测试中降低，避免过慢）
+const PBKDF2_ITERATIONS: u32 = if cfg!(test) { 1_000 } else { 600_000 };
+
+const SALT_LEN: usize = 16;
+
+/// 单条识别记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// Unix 时间戳（毫秒）
+    pub timestamp: u64,
+    /// ASR 原始识别文本
+    pub raw_text: String,
+    /// 最终输出文本（LLM 优化和后处理之后）
+    pub refined_text: String,
+    /// 录音时长（毫秒）
+    pub duration_ms: u64,
+    /// ASR 服务商
+    pub provider: String,
+}
+
+impl HistoryEntry {
+    /// 以当前时间创建记录
+    pub fn now(raw_text: String, refined_text: String, duration_ms: u64, provider: String) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        Self {
+            timestamp,
+            raw_text,
+            refined_text,
+            duration_ms,
+            provider,
+        }
+    }
+}
+
+/// 识别历史（按时间顺序，最早的在前）
+#[derive(Default)]
+pub(crate) struct History {
+    entries: VecDeque<HistoryEntry>,
+    /// 加密保存时的密钥（已解锁）
+    key: Option<HistoryKey>,
+    /// 已开启加密但尚未解锁：新记录只保存在内存中，解锁后一并写入
+    locked: bool,
+}
+
+impl History {
+    /// 从数据目录加载明文记录，文件不存在或无法解析时返回空记录
+    ///
+    /// `encrypted` 为 true 时处于锁定状态，加密的记录需调用 `unlock` 读取；
+    /// 刚开启加密时遗留的明文记录在解锁后合并进加密文件
+    pub(crate) fn load(encrypted: bool) -> Self {
+        let entries = history_path()
+            .ok()
+            .filter(|path| path.exists())
+            .and_then(|path| match fs::read_to_string(&path) {
+                Ok(content) => serde_json::from_str(&content)
+                    .map_err(|e| tracing::warn!("Invalid history file {:?}: {}", path, e))
+                    .ok(),
+                Err(e) => {
+                    tracing::warn!("Failed to read history file {:?}: {}", path, e);
+                    None
+                }
+            })
+            .unwrap_or_default();
+        Self {
+            entries,
+            key: None,
+            locked: encrypted,
+        }
+    }
+
+    /// 是否已开启加密但尚未解锁
+    pub(crate) fn is_locked(&self) -> bool {
+        self.locked
+    }
+
+    /// 用口令解锁加密的记录，与锁定期间的记录合并后重新写入，并删除明文文件
+    ///
+    /// 尚无加密文件时以该口令新建；口令错误时返回 `ConfigError::WrongPassphrase`
+    pub(crate) fn unlock(&mut self, passphrase: &str, capacity: usize) -> Result<(), ConfigError> {
+        let path = encrypted_history_path()?;
+        let (key, stored) = if path.exists() {
+            let file: EncryptedHistory = serde_json::from_str(&fs::read_to_string(&path)?)?;
+            let salt = decode_base64(&file.salt)?
+                .try_into()
+                .map_err(|_| ConfigError::Encryption("invalid salt".to_string()))?;
+            let key = HistoryKey::derive(passphrase, salt);
+            let stored: Vec<HistoryEntry> = serde_json::from_slice(&key.decrypt(&file)?)?;
+            (key, stored)
+        } else {
+            let mut salt = [0u8; SALT_LEN];
+            SystemRandom::new()
+                .fill(&mut salt)
+                .map_err(|_| ConfigError::Encryption("failed to generate salt".to_string()))?;
+            (HistoryKey::derive(passphrase, salt), Vec::new())
+        };
+
+        // 合并文件与内存中的记录（重复解锁时两者有重叠），按时间排序后去重
+        let mut entries: Vec<HistoryEntry> =
+            stored.into_iter().chain(self.entries.drain(..)).collect();
+        entries.sort_by_key(|entry| entry.timestamp);
+        entries.dedup_by(|a, b| a.timestamp == b.timestamp && a.raw_text == b.raw_text);
+        let excess = entries.len().saturating_sub(capacity);
+        self.entries = entries.into_iter().skip(excess).collect();
+        self.key = Some(key);
+        self.locked = false;
+        self.save()?;
+
+        remove_if_exists(&history_path()?)
+    }
+
+    /// 按配置切换是否加密
+    ///
+    /// 开启后在解锁前不再写入磁盘；关闭后改为明文保存，已解锁时删除加密文件
+    /// （未解锁时保留，以免丢失无法读取的记录）
+    pub(crate) fn set_encrypted(&mut self, encrypted: bool) -> Result<(), ConfigError> {
+        let is_encrypted = self.locked || self.key.is_some();
+        if encrypted == is_encrypted {
+            return Ok(());
+        }
+        if encrypted {
+            self.locked = true;
+            return Ok(());
+        }
+
+        let unlocked = self.key.take().is_some();
+        self.locked = false;
+        self.save()?;
+        if unlocked {
+            remove_if_exists(&encrypted_history_path()?)?;
+        }
+        Ok(())
+    }
+
+    /// 所有记录，最新的在前
+    pub(crate) fn entries(&self) -> Vec<HistoryEntry> {
+        self.entries.iter().rev().cloned().collect()
+    }
+
+    /// 追加一条记录并写入磁盘，超出 `capacity` 时丢弃最早的记录
+    pub(crate) fn push(&mut self, entry: HistoryEntry, capacity: usize) -> Result<(), ConfigError> {
+        self.entries.push_back(entry);
+        while self.entries.len() > capacity {
+            self.entries.pop_front();
+        }
+        self.save()
+    }
+
+    /// 清空记录并写入磁盘（锁定时直接删除加密文件）
+    pub(crate) fn clear(&mut self) -> Result<(), ConfigError> {
+        self.entries.clear();
+        if self.locked {
+            remove_if_exists(&encrypted_history_path()?)?;
+            return remove_if_exists(&history_path()?);
+        }
+        self.save()
+    }
+
+    fn save(&self) -> Result<(), ConfigError> {
+        if self.locked {
+            tracing::debug!("History is locked, keeping entries in memory");
+            return Ok(());
+        }
+        let content = serde_json::to_vec(&self.entries)?;
+        match &self.key {
+            Some(key) => {
+                let file = serde_json::to_string(&key.encrypt(&content)?)?;
+                write_atomic(&encrypted_history_path()?, file.as_bytes())
+            }
+            None => write_atomic(&history_path()?, &content),
+        }
+    }
+}
+
+/// 由口令派生的历史记录密钥
+struct HistoryKey {
+    key: LessSafeKey,
+    salt: [u8; SALT_LEN],
+}
+
+impl HistoryKey {
+    fn derive(passphrase: &str, salt: [u8; SALT_LEN]) -> Self {
+        let mut key = [0u8; 32];
+        pbkdf2::derive(
+            pbkdf2::PBKDF2_HMAC_SHA256,
+            NonZeroU32::new(PBKDF2_ITERATIONS).unwrap(),
+            &salt,
+            passphrase.as_bytes(),
+            &mut key,
+        );
+        let key = UnboundKey::new(&AES_256_GCM, &key).expect("AES-256 key is 32 bytes");
+        Self {
+            key: LessSafeKey::new(key),
+            salt,
+        }
+    }
+
+    /// 每次加密使用新的随机 nonce
+    fn encrypt(&self, plaintext: &[u8]) -> Result<EncryptedHistory, ConfigError> {
+        let mut nonce = [0u8; NONCE_LEN];
+        SystemRandom::new()
+            .fill(&mut nonce)
+            .map_err(|_| ConfigError::Encryption("failed to generate nonce".to_string()))?;
+        let mut data = plaintext.to_vec();
+        self.key
+            .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut data)
+            .map_err(|_| ConfigError::Encryption("failed to encrypt history".to_string()))?;
+        Ok(EncryptedHistory {
+            salt: BASE64.encode(self.salt),
+            nonce: BASE64.encode(nonce),
+            ciphertext: BASE64.encode(data),
+        })
+    }
+
+    /// 口令错误或文件被篡改时认证失败
+    fn decrypt(&self, file: &EncryptedHistory) -> Result<Vec<u8>, ConfigError> {
+        let nonce = Nonce::try_assume_unique_for_key(&decode_base64(&file.nonce)?)
+            .map_err(|_| ConfigError::Encryption("invalid nonce".to_string()))?;
+        let mut data = decode_base64(&file.ciphertext)?;
+        let plaintext = self
+            .key
+            .open_in_place(nonce, Aad::empty(), &mut data)
+            .map_err(|_| ConfigError::WrongPassphrase)?;
+        Ok(plaintext.to_vec())
+    }
+}
+
+/// history.enc 的内容（各字段为 base64）
+#[derive(Serialize, Deserialize)]
+struct EncryptedHistory {
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+fn decode_base64(value: &str) -> Result<Vec<u8>, ConfigError> {
+    BASE64
+        .decode(value)
+        .map_err(|e| ConfigError::Encryption(e.to_string()))
+}
+
+fn remove_if_exists(path: &PathBuf) -> Result<(), ConfigError> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+fn history_path() -> Result<PathBuf, ConfigError> {
+    Ok(data_dir()?.join(HISTORY_FILE))
+}
+
+fn encrypted_history_path() -> Result<PathBuf, ConfigError> {
+    Ok(data_dir()?.join(ENCRYPTED_HISTORY_FILE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encrypted_history_round_trip() {
+        let key = HistoryKey::derive("correct horse", [7; SALT_LEN]);
+        let file = key.encrypt(b"[]").unwrap();
+        assert_ne!(file.ciphertext, BASE64.encode(b"[]"));
+        assert_eq!(key.decrypt(&file).unwrap(), b"[]");
+    }
+
+    #[test]
+    fn wrong_passphrase_is_rejected() {
+        let file = HistoryKey::derive("correct horse", [7; SALT_LEN])
+            .encrypt(b"[]")
+            .unwrap();
+        let wrong = HistoryKey::derive("battery staple", [7; SALT_LEN]);
+        assert!(matches!(
+            wrong.decrypt(&file),
+            Err(ConfigError::WrongPassphrase)
+        ));
+    }
+}
@@ -1,3 +1,19 @@
+mod debug;
+mod error;
+mod events;
+mod file;
+mod history;
 mod voice;
 
-pub use voice::{PipelineError, PipelineState, VoicePipeline};
+pub use error::{PipelineErrorKind, ProcessingError};
+pub use events::{
+    AsrProviderListener, AsrRetryEvent, AsrRetryListener, AudioLevelListener, LlmPartialListener,
+    LowConfidenceWordsListener, MaxDurationListener, PartialTranscriptListener, PipelineEvent,
+    PipelineEventListener, PipelineMetrics, PipelineStage, SilenceListener,
+};
+pub use file::{
+    benchmark_providers, configured_asr_providers, transcribe_audio_data, transcribe_file,
+    BenchmarkResult,
+};
+pub use history::HistoryEntry;
+pub use voice::{PipelineError, PipelineState, ProviderOverride, TargetApp, VoicePipeline};
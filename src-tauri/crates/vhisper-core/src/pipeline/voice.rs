@@ -1,15 +1,51 @@
+use std::path::Path;
 use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
-use std::sync::{Arc, RwLock};
-use std::time::Duration;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tokio::runtime::Handle;
 use tokio::sync::mpsc;
 use tokio::sync::RwLock as TokioRwLock;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
 
 use crate::asr::{
-    create_asr_service, create_streaming_asr_service, StreamingAsrEvent, StreamingControl,
+    create_asr_service, create_streaming_asr_service, select_sample_rate, AsrError, AsrResult,
+    AsrService, SpeakerSegment, StreamingAsrEvent, StreamingControl, WordInfo,
 };
-use crate::audio::{encode_to_pcm, encode_to_wav, AudioRecorder};
-use crate::config::AppConfig;
-use crate::llm::create_llm_service;
+use super::debug::save_recording;
+use super::events::{
+    AsrProviderListener, AsrRetryEvent, AsrRetryListener, AudioLevelListener, LlmPartialListener,
+    LowConfidenceWordsListener, MaxDurationListener, PartialTranscriptListener, PipelineEvent,
+    PipelineEventListener, PipelineMetrics, PipelineStage, SilenceListener,
+};
+use super::file::prepare_audio;
+use super::history::{History, HistoryEntry};
+use crate::audio::{
+    analyze_samples, downmix_to_mono, encode_to_pcm, peak_amplitude, read_audio_file,
+    AudioAnalysis, AudioRecorder,
+};
+use crate::config::settings::{FinalResultPolicy, HotkeyMode, LlmConfig};
+use crate::config::{AppConfig, AppProfile, OutputConfig};
+use crate::llm::{create_llm_service, LlmService};
+use crate::postprocess;
+
+/// 最短有效录音时长（毫秒），低于此值视为未录到音频
+const MIN_AUDIO_DURATION_MS: u32 = 100;
+
+/// ASR 网络错误重试的初始等待时间，之后每次翻倍
+const ASR_RETRY_BASE_DELAY: Duration = Duration::from_millis(250);
+
+/// AcceptInterim 策略下检查宽限时间的间隔
+const INTERIM_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 停止录音后等待预连接完成的最长时间，超时则由识别自行建立连接
+const PRECONNECT_WAIT: Duration = Duration::from_secs(2);
+
+/// 边录音边识别时每次发送的音频时长（毫秒），录音回调的数据块合并到此大小再发送
+const LIVE_CHUNK_MS: usize = 100;
+
+/// 边录音边识别时待发送音频块的队列长度（网络较慢时录音数据在此之前的无界通道中排队）
+const LIVE_CHUNK_QUEUE: usize = 32;
 
 #[derive(Debug, thiserror::Error)]
 pub enum PipelineError {
@@ -23,6 +59,12 @@ pub enum PipelineError {
     Other(String),
     #[error("Operation cancelled")]
     Cancelled,
+    #[error("Low confidence transcription ({0:.2})")]
+    LowConfidence(f32),
+    #[error("录音无声音，请检查麦克风权限是否已授予当前应用")]
+    SilentAudio,
+    #[error("录音音量太低，请靠近麦克风或大声说话")]
+    AudioTooQuiet,
 }
 
 /// Pipeline 状态
@@ -44,6 +86,75 @@ impl From<u8> for PipelineState {
     }
 }
 
+/// 临时服务商覆盖（不写入配置文件）
+#[derive(Debug, Clone)]
+pub struct ProviderOverride {
+    /// 临时使用的 ASR 服务商
+    pub asr: Option<String>,
+    /// 临时使用的 LLM 服务商（同时启用 LLM 优化）
+    pub llm: Option<String>,
+    /// 剩余生效次数，None 表示直到手动清除
+    pub remaining: Option<u32>,
+}
+
+/// 最近一次录音（交错排列的原始采样）
+struct LastRecording {
+    samples: Vec<f32>,
+    sample_rate: u32,
+    channels: u16,
+}
+
+/// 录音期间在后台建立的 ASR 连接
+struct Preconnect {
+    asr: Arc<dyn AsrService>,
+    task: JoinHandle<()>,
+}
+
+/// 边录音边识别的会话：录音数据实时发送给 ASR，录音停止后等待最终结果
+///
+/// 释放时中止识别任务（取消录音、录音无效或处理被取消时）
+struct LiveRecognition {
+    /// 使用的 ASR 服务商
+    provider: String,
+    /// 热词是否已传给服务商
+    hotwords_applied: bool,
+    task: JoinHandle<Result<AsrResult, AsrError>>,
+}
+
+impl LiveRecognition {
+    /// 等待识别结束（录音停止后音频通道关闭，服务商随即返回最终结果）
+    async fn finish(&mut self) -> Result<AsrResult, AsrError> {
+        (&mut self.task)
+            .await
+            .unwrap_or_else(|e| Err(AsrError::Network(format!("识别任务异常结束: {}", e))))
+    }
+}
+
+impl Drop for LiveRecognition {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// 单次识别结果
+struct Transcription {
+    /// ASR 原始识别文本
+    raw: String,
+    /// 经 LLM 优化和后处理后的输出文本
+    text: String,
+    /// 实际使用的 ASR 服务商
+    provider: String,
+}
+
+/// 录音开始时的目标应用（前台应用）
+#[derive(Debug, Clone, Default)]
+pub struct TargetApp {
+    /// bundle id（macOS）或可执行文件名（Windows）
+    pub id: Option<String>,
+    /// 应用显示名称
+    pub name: Option<String>,
+}
+
 /// 语音处理管道
 pub struct VoicePipeline {
     config: Arc<TokioRwLock<AppConfig>>,
@@ -53,6 +164,8 @@ pub struct VoicePipeline {
     state: Arc<AtomicU8>,
     /// 取消标志（Arc 包装以便后台任务共享）
     cancelled: Arc<AtomicBool>,
+    /// 本次处理的取消令牌，取消时直接丢弃进行中的 ASR / LLM 请求
+    cancel_token: Mutex<CancellationToken>,
     /// 流式模式标志（Arc 包装以便后台任务共享）
     streaming_mode: Arc<AtomicBool>,
     /// 流式 ASR 控制通道（用于发送音频和控制命令）
@@ -62,22 +175,89 @@ pub struct VoicePipeline {
     streaming_task_cancelled: Arc<TokioRwLock<Option<Arc<AtomicBool>>>>,
     /// 是否应该完全停止（热键松开时设为 true，区别于 VAD Final）
     should_stop: Arc<AtomicBool>,
+    /// 临时服务商覆盖
+    provider_override: Mutex<Option<ProviderOverride>>,
+    /// 本次录音的目标应用
+    target_app: Mutex<Option<TargetApp>>,
+    /// 本次录音使用的 LLM 模型（来自触发的快捷键）
+    llm_model: Mutex<Option<String>>,
+    /// 最近一次录音（需开启 audio.keep_last_recording）
+    last_recording: Mutex<Option<LastRecording>>,
+    /// 最近一次成功输出的识别结果（用于重复输出）
+    last_output: Mutex<Option<String>>,
+    /// 本次处理中累计的各阶段耗时，补上输出耗时后移入 `last_metrics`
+    metrics: Mutex<Option<PipelineMetrics>>,
+    /// 最近一次成功处理的各阶段耗时
+    last_metrics: Mutex<Option<PipelineMetrics>>,
+    /// 识别历史
+    history: Mutex<History>,
+    /// 阶段事件监听器
+    event_listener: RwLock<Option<PipelineEventListener>>,
+    /// 识别中间结果监听器
+    partial_listener: RwLock<Option<PartialTranscriptListener>>,
+    /// LLM 优化中间结果监听器
+    llm_partial_listener: RwLock<Option<LlmPartialListener>>,
+    /// ASR 重试监听器
+    retry_listener: RwLock<Option<AsrRetryListener>>,
+    /// 实际使用的 ASR 服务商监听器
+    provider_listener: RwLock<Option<AsrProviderListener>>,
+    /// 低置信度词监听器
+    low_confidence_words_listener: RwLock<Option<LowConfidenceWordsListener>>,
+    /// 录音达到最长时长监听器
+    max_duration_listener: RwLock<Option<MaxDurationListener>>,
+    /// 说话后持续静音监听器（自动结束模式）
+    silence_listener: RwLock<Option<SilenceListener>>,
+    /// 实时音量监听器
+    level_listener: RwLock<Option<AudioLevelListener>>,
+    /// 录音期间预先建立的 ASR 连接
+    preconnect: Mutex<Option<Preconnect>>,
+    /// 边录音边识别的会话（需开启 asr.stream_during_recording）
+    live_recognition: Mutex<Option<LiveRecognition>>,
+    /// 启动后台任务使用的运行时（录音可能在运行时之外的线程中开始）
+    runtime: RwLock<Option<Handle>>,
 }
 
 impl VoicePipeline {
     /// 创建新的语音管道
     pub fn new(config: Arc<TokioRwLock<AppConfig>>) -> Result<Self, PipelineError> {
-        let recorder = AudioRecorder::new()?;
+        let mut recorder = AudioRecorder::new()?;
+        let mut encrypt_history = false;
+        // 启动时即开始预录，第一次录音也能补上开头
+        if let Ok(config) = config.try_read() {
+            recorder.set_preroll_ms(config.audio.preroll_ms);
+            encrypt_history = config.history.encrypt;
+        }
 
         Ok(Self {
             config,
             recorder: Arc::new(RwLock::new(recorder)),
             state: Arc::new(AtomicU8::new(PipelineState::Idle as u8)),
             cancelled: Arc::new(AtomicBool::new(false)),
+            cancel_token: Mutex::new(CancellationToken::new()),
             streaming_mode: Arc::new(AtomicBool::new(false)),
             streaming_control_tx: Arc::new(TokioRwLock::new(None)),
             streaming_task_cancelled: Arc::new(TokioRwLock::new(None)),
             should_stop: Arc::new(AtomicBool::new(false)),
+            provider_override: Mutex::new(None),
+            target_app: Mutex::new(None),
+            llm_model: Mutex::new(None),
+            last_recording: Mutex::new(None),
+            last_output: Mutex::new(None),
+            metrics: Mutex::new(None),
+            last_metrics: Mutex::new(None),
+            history: Mutex::new(History::load(encrypt_history)),
+            event_listener: RwLock::new(None),
+            partial_listener: RwLock::new(None),
+            llm_partial_listener: RwLock::new(None),
+            retry_listener: RwLock::new(None),
+            provider_listener: RwLock::new(None),
+            low_confidence_words_listener: RwLock::new(None),
+            max_duration_listener: RwLock::new(None),
+            silence_listener: RwLock::new(None),
+            level_listener: RwLock::new(None),
+            preconnect: Mutex::new(None),
+            live_recognition: Mutex::new(None),
+            runtime: RwLock::new(Handle::try_current().ok()),
         })
     }
 
@@ -91,10 +271,343 @@ impl VoicePipeline {
         self.get_state() == PipelineState::Recording
     }
 
+    /// 麦克风是否仍在采集（状态已离开 Recording 后，录音线程可能尚未结束）
+    pub fn is_capturing(&self) -> bool {
+        self.recorder
+            .read()
+            .map(|recorder| recorder.is_recording())
+            .unwrap_or(false)
+    }
+
+    /// 临时覆盖接下来 `count` 次识别使用的服务商（`count` 为 None 时直到清除）
+    pub fn set_provider_override(
+        &self,
+        asr: Option<String>,
+        llm: Option<String>,
+        count: Option<u32>,
+    ) {
+        let value = if count == Some(0) || (asr.is_none() && llm.is_none()) {
+            None
+        } else {
+            Some(ProviderOverride {
+                asr,
+                llm,
+                remaining: count,
+            })
+        };
+        tracing::info!("Provider override set: {:?}", value);
+        *self.provider_override.lock().unwrap() = value;
+    }
+
+    /// 清除临时服务商覆盖
+    pub fn clear_provider_override(&self) {
+        tracing::info!("Provider override cleared");
+        *self.provider_override.lock().unwrap() = None;
+    }
+
+    /// 获取当前的临时服务商覆盖
+    pub fn provider_override(&self) -> Option<ProviderOverride> {
+        self.provider_override.lock().unwrap().clone()
+    }
+
+    /// 设置本次录音的目标应用（开始录音前调用），用于按应用切换识别语言和配置方案
+    pub fn set_target_app(&self, app: Option<TargetApp>) {
+        tracing::info!("Target app: {:?}", app);
+        *self.target_app.lock().unwrap() = app;
+    }
+
+    /// 本次录音的输出配置：目标应用的配置方案覆盖全局输出方式
+    pub fn output_config(&self, config: &AppConfig) -> OutputConfig {
+        let mut output = config.output.clone();
+        if let Some(mode) = self.app_profile(config).and_then(|profile| profile.output_mode) {
+            output.mode = mode;
+        }
+        output
+    }
+
+    /// 目标应用匹配的配置方案
+    fn app_profile<'a>(&self, config: &'a AppConfig) -> Option<&'a AppProfile> {
+        let target_app = self.target_app.lock().unwrap();
+        let app = target_app.as_ref()?;
+        config.app_profile(app.id.as_deref(), app.name.as_deref())
+    }
+
+    /// 设置本次录音使用的 LLM 模型（停止录音前调用），为 None 时使用配置中的模型
+    pub fn set_llm_model_override(&self, model: Option<String>) {
+        if model.is_some() {
+            tracing::info!("LLM model override: {:?}", model);
+        }
+        *self.llm_model.lock().unwrap() = model;
+    }
+
+    /// 分析最近一次录音（削波、直流偏移、RMS、波形），未保留录音时返回 None
+    pub fn analyze_last_recording(&self, waveform_points: usize) -> Option<AudioAnalysis> {
+        let guard = self.last_recording.lock().unwrap();
+        let last = guard.as_ref()?;
+        Some(analyze_samples(
+            &last.samples,
+            last.sample_rate,
+            last.channels,
+            waveform_points,
+        ))
+    }
+
+    /// 对音频文件执行完整处理（ASR + LLM + 规则后处理），返回最终文本
+    ///
+    /// 用于复现识别问题：直接使用当前配置（不应用应用配置方案和临时服务商覆盖），不输出、不写历史
+    pub async fn process_file(&self, path: impl AsRef<Path>) -> Result<String, PipelineError> {
+        let (samples, sample_rate) = read_audio_file(path)?;
+        let config = self.config.read().await.clone();
+        self.process_samples(&config, &samples, sample_rate, 1, None)
+            .await
+            .map(|transcription| transcription.text)
+    }
+
+    /// 最近一次识别得到的最终文本（未识别过或结果为空时返回 None）
+    pub fn last_output(&self) -> Option<String> {
+        self.last_output.lock().unwrap().clone()
+    }
+
+    /// 最近一次成功处理的各阶段耗时（调用方输出文本后通过 `record_output_duration` 补上输出耗时）
+    pub fn last_metrics(&self) -> Option<PipelineMetrics> {
+        self.last_metrics.lock().unwrap().clone()
+    }
+
+    /// 记录刚完成的处理的输出耗时并计入总耗时，返回完整的各阶段耗时
+    ///
+    /// 每次处理只记录一次；`stop_and_process` 未成功处理时返回 None
+    pub fn record_output_duration(&self, duration: Duration) -> Option<PipelineMetrics> {
+        let mut metrics = self.metrics.lock().unwrap().take()?;
+        let output_ms = duration.as_millis() as u64;
+        metrics.output_ms = output_ms;
+        metrics.total_ms += output_ms;
+        tracing::info!(
+            "Pipeline metrics: encode={}ms asr={}ms llm={}ms output={}ms total={}ms",
+            metrics.encode_ms,
+            metrics.asr_ms,
+            metrics.llm_ms,
+            metrics.output_ms,
+            metrics.total_ms
+        );
+        *self.last_metrics.lock().unwrap() = Some(metrics.clone());
+        Some(metrics)
+    }
+
+    /// 累加本次处理的阶段耗时
+    fn add_metrics(&self, update: impl FnOnce(&mut PipelineMetrics)) {
+        if let Some(metrics) = self.metrics.lock().unwrap().as_mut() {
+            update(metrics);
+        }
+    }
+
+    /// 识别历史，最新的在前
+    pub fn history(&self) -> Vec<HistoryEntry> {
+        self.history.lock().unwrap().entries()
+    }
+
+    /// 历史记录是否已加密且尚未解锁
+    pub fn is_history_locked(&self) -> bool {
+        self.history.lock().unwrap().is_locked()
+    }
+
+    /// 用口令解锁加密的历史记录（尚无加密记录时以该口令新建）
+    pub fn unlock_history(&self, passphrase: &str) -> Result<(), PipelineError> {
+        let capacity = self
+            .config
+            .try_read()
+            .map(|config| config.history.max_entries)
+            .unwrap_or(usize::MAX);
+        self.history
+            .lock()
+            .unwrap()
+            .unlock(passphrase, capacity)
+            .map_err(|e| PipelineError::Other(e.to_string()))
+    }
+
+    /// 清空识别历史（同时删除磁盘上的记录）
+    pub fn clear_history(&self) -> Result<(), PipelineError> {
+        self.history
+            .lock()
+            .unwrap()
+            .clear()
+            .map_err(|e| PipelineError::Other(e.to_string()))
+    }
+
+    /// 设置阶段事件监听器（ASR / LLM 开始和结束时调用）
+    pub fn set_event_listener<F>(&self, listener: F)
+    where
+        F: Fn(PipelineEvent) + Send + Sync + 'static,
+    {
+        if let Ok(mut guard) = self.event_listener.write() {
+            *guard = Some(Arc::new(listener));
+        }
+    }
+
+    /// 设置识别中间结果监听器（支持的服务商在识别过程中推送中间结果）
+    pub fn set_partial_listener<F>(&self, listener: F)
+    where
+        F: Fn(String) + Send + Sync + 'static,
+    {
+        if let Ok(mut guard) = self.partial_listener.write() {
+            *guard = Some(Arc::new(listener));
+        }
+    }
+
+    /// 设置 LLM 优化中间结果监听器（设置后使用流式输出，最终结果仍在优化完成后返回）
+    pub fn set_llm_partial_listener<F>(&self, listener: F)
+    where
+        F: Fn(String) + Send + Sync + 'static,
+    {
+        if let Ok(mut guard) = self.llm_partial_listener.write() {
+            *guard = Some(Arc::new(listener));
+        }
+    }
+
+    /// 设置 ASR 重试监听器（网络错误后重试前调用）
+    pub fn set_retry_listener<F>(&self, listener: F)
+    where
+        F: Fn(AsrRetryEvent) + Send + Sync + 'static,
+    {
+        if let Ok(mut guard) = self.retry_listener.write() {
+            *guard = Some(Arc::new(listener));
+        }
+    }
+
+    /// 设置 ASR 服务商监听器（每次识别成功后以实际使用的服务商调用）
+    pub fn set_provider_listener<F>(&self, listener: F)
+    where
+        F: Fn(String) + Send + Sync + 'static,
+    {
+        if let Ok(mut guard) = self.provider_listener.write() {
+            *guard = Some(Arc::new(listener));
+        }
+    }
+
+    /// 设置低置信度词监听器（识别结果中有词低于 `min_word_confidence` 时调用）
+    pub fn set_low_confidence_words_listener<F>(&self, listener: F)
+    where
+        F: Fn(Vec<WordInfo>) + Send + Sync + 'static,
+    {
+        if let Ok(mut guard) = self.low_confidence_words_listener.write() {
+            *guard = Some(Arc::new(listener));
+        }
+    }
+
+    /// 设置录音达到最长时长监听器（录音已自动停止采集，调用方应随后调用 `stop_and_process`）
+    pub fn set_max_duration_listener<F>(&self, listener: F)
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        if let Ok(mut guard) = self.max_duration_listener.write() {
+            *guard = Some(Arc::new(listener));
+        }
+    }
+
+    /// 设置静音监听器：快捷键为自动结束模式时，说话后持续静音超过 `audio.silence_timeout_ms`
+    /// 即自动停止采集并调用监听器，调用方应随后调用 `stop_and_process`
+    pub fn set_silence_listener<F>(&self, listener: F)
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        if let Ok(mut guard) = self.silence_listener.write() {
+            *guard = Some(Arc::new(listener));
+        }
+    }
+
+    /// 设置实时音量监听器（录音期间约每 50ms 调用一次，录音停止后不再调用）
+    pub fn set_level_listener<F>(&self, listener: F)
+    where
+        F: Fn(f32) + Send + Sync + 'static,
+    {
+        if let Ok(mut guard) = self.level_listener.write() {
+            *guard = Some(Arc::new(listener));
+        }
+    }
+
+    /// 设置启动后台任务使用的运行时（管道在运行时之外创建时需调用，否则不预先建立连接）
+    pub fn set_runtime_handle(&self, handle: Handle) {
+        if let Ok(mut guard) = self.runtime.write() {
+            *guard = Some(handle);
+        }
+    }
+
+    /// 发出阶段事件
+    fn emit_stage(&self, stage: PipelineStage) {
+        let listener = self.event_listener.read().ok().and_then(|l| l.clone());
+        if let Some(listener) = listener {
+            listener(PipelineEvent::now(stage));
+        }
+    }
+
+    /// 读取本次识别使用的配置：应用目标应用的语言设置、配置方案和临时服务商覆盖
+    async fn session_config(&self) -> AppConfig {
+        let mut config = self.config.read().await.clone();
+        self.apply_session_overrides(&mut config, true);
+        config
+    }
+
+    /// 应用目标应用的语言设置、配置方案和临时服务商覆盖
+    ///
+    /// `consume_override` 为 false 时只读取临时覆盖，不减少剩余次数（录音开始时预先准备识别用）
+    fn apply_session_overrides(&self, config: &mut AppConfig, consume_override: bool) {
+        if let Some(profile) = self.app_profile(config).cloned() {
+            tracing::info!("Using app profile {:?}", profile);
+            config.apply_app_profile(&profile);
+        }
+
+        if let Some(app) = self.target_app.lock().unwrap().as_ref() {
+            let language = config
+                .app_language(app.id.as_deref(), app.name.as_deref())
+                .map(str::to_string);
+            if let Some(language) = language {
+                tracing::info!("Using language {} for app {:?}", language, app);
+                config.apply_language(&language);
+            }
+        }
+
+        let mut guard = self.provider_override.lock().unwrap();
+        let mut exhausted = false;
+        if let Some(ov) = guard.as_mut() {
+            if let Some(asr) = &ov.asr {
+                config.asr.provider = asr.clone();
+            }
+            if let Some(llm) = &ov.llm {
+                config.llm.provider = llm.clone();
+                config.llm.enabled = true;
+            }
+
+            if let Some(remaining) = ov.remaining.as_mut().filter(|_| consume_override) {
+                *remaining = remaining.saturating_sub(1);
+                tracing::info!(
+                    "Using provider override (asr={:?}, llm={:?}), {} remaining",
+                    ov.asr,
+                    ov.llm,
+                    remaining
+                );
+                exhausted = *remaining == 0;
+            }
+        }
+        if exhausted {
+            *guard = None;
+        }
+    }
+
+    /// 默认输入设备变更通知
+    ///
+    /// 录音器会在下次开始录音时切换到新设备
+    pub fn notify_device_changed(&self) {
+        if self.is_recording() {
+            tracing::warn!("Input device changed during recording, will switch on next start");
+        }
+        if let Ok(recorder) = self.recorder.read() {
+            recorder.notify_device_changed();
+        }
+    }
+
     /// 取消当前操作
     ///
     /// - 如果正在录音，停止录音并丢弃数据
-    /// - 如果正在处理，标记取消（处理完成后返回 Cancelled 错误）
+    /// - 如果正在处理，中止进行中的 ASR / LLM 请求并返回 Cancelled 错误
     /// - 如果空闲，无操作
     pub fn cancel(&self) -> Result<(), PipelineError> {
         let current = self.get_state();
@@ -111,14 +624,17 @@ impl VoicePipeline {
                     PipelineError::Other(format!("Failed to acquire recorder lock: {}", e))
                 })?;
                 let _ = recorder.stop(); // 忽略数据
+                self.discard_preconnect();
+                self.discard_live_recognition();
                 self.state.store(PipelineState::Idle as u8, Ordering::SeqCst);
                 self.cancelled.store(false, Ordering::SeqCst);
                 tracing::info!("Recording cancelled");
                 Ok(())
             }
             PipelineState::Processing => {
-                // 标记取消，异步处理会检查此标志
+                // 标记取消，并通过令牌中止正在等待的网络请求
                 self.cancelled.store(true, Ordering::SeqCst);
+                self.cancel_token.lock().unwrap().cancel();
                 tracing::info!("Processing cancellation requested");
                 Ok(())
             }
@@ -140,20 +656,192 @@ impl VoicePipeline {
         let mut recorder = self.recorder.write().map_err(|e| {
             PipelineError::Other(format!("Failed to acquire recorder lock: {}", e))
         })?;
+        if let Ok(config) = self.config.try_read() {
+            recorder.set_keep_stereo(config.audio.diarize_channels);
+            recorder.set_preallocate_secs(config.audio.buffer_preallocate_secs);
+            recorder.set_preroll_ms(config.audio.preroll_ms);
+            let listener = self.max_duration_listener.read().ok().and_then(|l| l.clone());
+            recorder.set_max_duration(config.audio.max_recording_secs, listener);
+            // 自动结束模式：说完后持续静音即停止录音
+            let silence_timeout_ms = match config.hotkey.mode {
+                HotkeyMode::AutoStop => config.audio.silence_timeout_ms,
+                _ => 0,
+            };
+            let listener = self.silence_listener.read().ok().and_then(|l| l.clone());
+            recorder.set_silence_stop(silence_timeout_ms, config.audio.min_speech_ms, listener);
+        }
+        recorder.set_level_callback(self.level_listener.read().ok().and_then(|l| l.clone()));
+        // 录音启动失败时会话随之释放，识别任务被中止
+        let live = self.start_live_recognition(&mut recorder);
         recorder.start()?;
+        // 设备不支持目标采样率时（如换了设备），录音器按设备实际采样率重采样后写入缓冲区
+        match recorder.device_sample_rate() {
+            Some(rate) if rate != recorder.sample_rate() => tracing::info!(
+                "Input device captures at {}Hz, resampling to {}Hz",
+                rate,
+                recorder.sample_rate()
+            ),
+            Some(rate) => tracing::info!("Input device captures at {}Hz", rate),
+            None => tracing::warn!("Input device sample rate not confirmed yet"),
+        }
+        drop(recorder);
 
         self.state.store(PipelineState::Recording as u8, Ordering::SeqCst);
+        // 边录边传时识别任务已自行建立连接，无需预连接
+        let live_started = live.is_some();
+        *self.live_recognition.lock().unwrap() = live;
+        if !live_started {
+            self.start_preconnect();
+        }
         Ok(())
     }
 
+    /// 按配置开始边录音边识别：录音数据块经 `recorder` 实时发送给 ASR
+    ///
+    /// 服务商不支持、按声道识别或采样率不匹配时返回 None，录音结束后照常识别
+    fn start_live_recognition(&self, recorder: &mut AudioRecorder) -> Option<LiveRecognition> {
+        let runtime = self.runtime.read().ok().and_then(|r| r.clone())?;
+        let mut config = match self.config.try_read() {
+            Ok(config) if config.asr.stream_during_recording => config.clone(),
+            _ => return None,
+        };
+        // 与识别时一致（此时不消耗临时覆盖的次数）
+        self.apply_session_overrides(&mut config, false);
+        if config.audio.diarize_channels {
+            return None;
+        }
+
+        let asr: Arc<dyn AsrService> = match create_asr_service(&config.asr) {
+            Ok(service) => service.into(),
+            Err(e) => {
+                tracing::debug!("Skipping live recognition: {}", e);
+                return None;
+            }
+        };
+        if !asr.supports_streaming_input() {
+            tracing::debug!(
+                "ASR provider {} does not support streaming input",
+                config.asr.provider
+            );
+            return None;
+        }
+        let sample_rate = recorder.sample_rate();
+        if select_sample_rate(asr.supported_sample_rates(), sample_rate) != sample_rate {
+            tracing::debug!("ASR provider does not accept {}Hz, skipping", sample_rate);
+            return None;
+        }
+
+        let (samples_tx, samples_rx) = mpsc::unbounded_channel::<Vec<f32>>();
+        recorder.set_chunk_sender(Some(samples_tx));
+
+        let hotwords_applied = asr.supports_hotwords();
+        let partial_listener = self.partial_listener.read().ok().and_then(|l| l.clone());
+        let chunk_samples = sample_rate as usize * LIVE_CHUNK_MS / 1000;
+        let task = runtime.spawn(async move {
+            let (audio_tx, audio_rx) = mpsc::channel::<Vec<u8>>(LIVE_CHUNK_QUEUE);
+            let send_audio = forward_live_audio(samples_rx, audio_tx, chunk_samples);
+            match partial_listener {
+                Some(listener) => {
+                    // 识别、发送音频与转发中间结果并行，识别结束后各通道随之关闭
+                    let (partial_tx, mut partial_rx) = mpsc::channel::<String>(16);
+                    let recognize =
+                        asr.start_streaming_recognition(audio_rx, sample_rate, Some(partial_tx));
+                    let forward = async {
+                        while let Some(partial) = partial_rx.recv().await {
+                            listener(partial);
+                        }
+                    };
+                    tokio::join!(recognize, send_audio, forward).0
+                }
+                None => {
+                    let recognize = asr.start_streaming_recognition(audio_rx, sample_rate, None);
+                    tokio::join!(recognize, send_audio).0
+                }
+            }
+        });
+
+        tracing::info!("Live recognition started ({})", config.asr.provider);
+        Some(LiveRecognition {
+            provider: config.asr.provider,
+            hotwords_applied,
+            task,
+        })
+    }
+
+    /// 按配置在后台建立 ASR 连接，识别时直接复用，省去松开快捷键后的握手等待
+    fn start_preconnect(&self) {
+        self.discard_preconnect();
+
+        let Some(runtime) = self.runtime.read().ok().and_then(|r| r.clone()) else {
+            return;
+        };
+        let mut asr_config = match self.config.try_read() {
+            Ok(config) if config.asr.preconnect => config.asr.clone(),
+            _ => return,
+        };
+        // 与识别时一致：临时切换的服务商优先
+        if let Some(provider) = self
+            .provider_override
+            .lock()
+            .unwrap()
+            .as_ref()
+            .and_then(|ov| ov.asr.clone())
+        {
+            asr_config.provider = provider;
+        }
+
+        let asr: Arc<dyn AsrService> = match create_asr_service(&asr_config) {
+            Ok(service) => service.into(),
+            Err(e) => {
+                tracing::debug!("Skipping ASR preconnect: {}", e);
+                return;
+            }
+        };
+        let service = asr.clone();
+        let task = runtime.spawn(async move {
+            let start = Instant::now();
+            match service.preconnect().await {
+                Ok(()) => tracing::debug!("ASR preconnected in {:?}", start.elapsed()),
+                Err(e) => tracing::warn!("ASR preconnect failed: {}", e),
+            }
+        });
+        *self.preconnect.lock().unwrap() = Some(Preconnect { asr, task });
+    }
+
+    /// 等待预连接完成（最多 `PRECONNECT_WAIT`），避免识别时重复建立连接
+    async fn finish_preconnect(&self) {
+        let Some(preconnect) = self.preconnect.lock().unwrap().take() else {
+            return;
+        };
+        if tokio::time::timeout(PRECONNECT_WAIT, preconnect.task).await.is_err() {
+            tracing::warn!("ASR preconnect still pending, connecting directly");
+        }
+    }
+
+    /// 中止预连接并丢弃已建立的连接
+    fn discard_preconnect(&self) {
+        if let Some(preconnect) = self.preconnect.lock().unwrap().take() {
+            preconnect.task.abort();
+            preconnect.asr.discard_preconnected();
+        }
+    }
+
+    /// 中止边录边传的识别任务（会话释放时中止）
+    fn discard_live_recognition(&self) {
+        drop(self.live_recognition.lock().unwrap().take());
+    }
+
     /// 停止录音并处理，返回识别结果文本
     ///
     /// 此方法是幂等的：
     /// - 如果不在录音状态，直接返回空字符串
     /// - 如果已取消，返回 Cancelled 错误
+    /// - 如果录音为空或过短，返回 NoAudio 错误
     pub async fn stop_and_process(&self) -> Result<String, PipelineError> {
         // 检查是否已取消
         if self.cancelled.load(Ordering::SeqCst) {
+            self.discard_preconnect();
+            self.discard_live_recognition();
             self.state.store(PipelineState::Idle as u8, Ordering::SeqCst);
             self.cancelled.store(false, Ordering::SeqCst);
             return Err(PipelineError::Cancelled);
@@ -166,8 +854,11 @@ impl VoicePipeline {
             return Ok(String::new());
         }
 
-        // 转换到 Processing 状态
+        // 转换到 Processing 状态，每次处理使用新的取消令牌
+        let cancel_token = CancellationToken::new();
+        *self.cancel_token.lock().unwrap() = cancel_token.clone();
         self.state.store(PipelineState::Processing as u8, Ordering::SeqCst);
+        let started_at = Instant::now();
 
         // 停止录音 - 使用同步锁，快速获取并释放
         let samples = {
@@ -180,29 +871,146 @@ impl VoicePipeline {
 
         // 检查是否在停止后被取消
         if self.cancelled.load(Ordering::SeqCst) {
+            self.discard_preconnect();
+            self.discard_live_recognition();
             self.state.store(PipelineState::Idle as u8, Ordering::SeqCst);
             self.cancelled.store(false, Ordering::SeqCst);
             return Err(PipelineError::Cancelled);
         }
 
-        if samples.is_empty() {
-            tracing::warn!("No audio data recorded");
-            self.state.store(PipelineState::Idle as u8, Ordering::SeqCst);
-            return Ok(String::new());
-        }
-
-        let config = self.config.read().await.clone();
-        let sample_rate = {
+        let config = self.session_config().await;
+        let (sample_rate, channels) = {
             let recorder = self.recorder.read().map_err(|e| {
+                self.state.store(PipelineState::Idle as u8, Ordering::SeqCst);
                 PipelineError::Other(format!("Failed to acquire recorder lock: {}", e))
             })?;
-            recorder.sample_rate()
+            (recorder.sample_rate(), recorder.channels())
         };
 
-        tracing::info!("Processing {} samples at {}Hz", samples.len(), sample_rate);
+        // 按配置保留本次录音供质量分析，关闭时清除旧数据
+        *self.last_recording.lock().unwrap() = config.audio.keep_last_recording.then(|| {
+            LastRecording {
+                samples: samples.clone(),
+                sample_rate,
+                channels,
+            }
+        });
+
+        // 调试：另存录音，失败不影响识别
+        if let Some(dir) = &config.debug.save_recordings_dir {
+            match save_recording(dir, &samples, sample_rate, channels) {
+                Ok(path) => tracing::info!("Recording saved to {}", path.display()),
+                Err(e) => tracing::warn!("Failed to save recording: {}", e),
+            }
+        }
+
+        // 录音已停止，边录边传的识别任务正在等待最终结果
+        let live = self.live_recognition.lock().unwrap().take();
+        *self.metrics.lock().unwrap() = Some(PipelineMetrics::default());
+
+        // 取消时丢弃处理 future，进行中的 WebSocket / HTTP 请求随之中止
+        let result = tokio::select! {
+            result = async {
+                self.finish_preconnect().await;
+                self.process_samples(&config, &samples, sample_rate, channels, live).await
+            } => result,
+            _ = cancel_token.cancelled() => {
+                tracing::info!("Processing cancelled, in-flight requests aborted");
+                Err(PipelineError::Cancelled)
+            }
+        };
+
+        // 完成（无论成功与否），恢复 Idle 状态
+        self.state.store(PipelineState::Idle as u8, Ordering::SeqCst);
+        if matches!(result, Err(PipelineError::Cancelled)) {
+            self.cancelled.store(false, Ordering::SeqCst);
+        }
+        if let Ok(transcription) = &result {
+            tracing::info!("stop_and_process completed successfully");
+            if let Some(metrics) = self.metrics.lock().unwrap().as_mut() {
+                metrics.total_ms = started_at.elapsed().as_millis() as u64;
+                tracing::info!(
+                    "Processing metrics: encode={}ms asr={}ms llm={}ms total={}ms",
+                    metrics.encode_ms,
+                    metrics.asr_ms,
+                    metrics.llm_ms,
+                    metrics.total_ms
+                );
+                *self.last_metrics.lock().unwrap() = Some(metrics.clone());
+            }
+            if !transcription.text.is_empty() {
+                *self.last_output.lock().unwrap() = Some(transcription.text.clone());
+                if config.history.enabled {
+                    let frames = samples.len() as u64 / channels.max(1) as u64;
+                    let duration_ms = frames * 1000 / sample_rate.max(1) as u64;
+                    self.record_history(&config, transcription, duration_ms);
+                }
+            }
+        } else {
+            *self.metrics.lock().unwrap() = None;
+        }
+        result.map(|transcription| transcription.text)
+    }
+
+    /// 追加识别历史，写入失败只记录日志
+    fn record_history(&self, config: &AppConfig, transcription: &Transcription, duration_ms: u64) {
+        let entry = HistoryEntry::now(
+            transcription.raw.clone(),
+            transcription.text.clone(),
+            duration_ms,
+            transcription.provider.clone(),
+        );
+        let mut history = self.history.lock().unwrap();
+        let result = history
+            .set_encrypted(config.history.encrypt)
+            .and_then(|()| history.push(entry, config.history.max_entries));
+        if let Err(e) = result {
+            tracing::warn!("Failed to save history: {}", e);
+        }
+    }
+
+    /// 检查取消标志
+    fn check_cancelled(&self) -> Result<(), PipelineError> {
+        if self.cancelled.load(Ordering::SeqCst) {
+            Err(PipelineError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// 处理录音数据：音量检查 → ASR → LLM
+    ///
+    /// `samples` 为交错排列的多声道数据（`channels` 为声道数）；
+    /// `live` 为录音期间已开始的识别会话，有则优先使用其结果
+    async fn process_samples(
+        &self,
+        config: &AppConfig,
+        samples: &[f32],
+        sample_rate: u32,
+        channels: u16,
+        live: Option<LiveRecognition>,
+    ) -> Result<Transcription, PipelineError> {
+        // 空音频或过短音频：不创建 ASR 连接
+        let min_samples =
+            sample_rate as usize * channels as usize * MIN_AUDIO_DURATION_MS as usize / 1000;
+        if samples.len() < min_samples {
+            tracing::warn!(
+                "No usable audio recorded ({} samples, min {})",
+                samples.len(),
+                min_samples
+            );
+            return Err(AsrError::NoAudio.into());
+        }
+
+        tracing::info!(
+            "Processing {} samples at {}Hz ({} channels)",
+            samples.len(),
+            sample_rate,
+            channels
+        );
 
         // 检测是否全静音
-        let max_amplitude = samples.iter().map(|s| s.abs()).fold(0.0f32, f32::max);
+        let max_amplitude = peak_amplitude(samples);
         let avg_amplitude = samples.iter().map(|s| s.abs()).sum::<f32>() / samples.len() as f32;
         let non_zero_count = samples.iter().filter(|&&s| s != 0.0).count();
 
@@ -217,84 +1025,296 @@ impl VoicePipeline {
         // >= 0.05 = 正常语音
         if max_amplitude < 0.001 {
             tracing::warn!(">>> SILENT (amplitude={:.6}) - likely permission issue <<<", max_amplitude);
-            self.state.store(PipelineState::Idle as u8, Ordering::SeqCst);
-            return Err(PipelineError::Other(
-                "录音无声音，请检查麦克风权限是否已授予当前应用".to_string()
-            ));
+            return Err(PipelineError::SilentAudio);
         }
 
         if max_amplitude < 0.05 {
             tracing::warn!(">>> AUDIO TOO QUIET (amplitude={:.6}) - speak louder or closer <<<", max_amplitude);
-            self.state.store(PipelineState::Idle as u8, Ordering::SeqCst);
-            return Err(PipelineError::Other(
-                "录音音量太低，请靠近麦克风或大声说话".to_string()
-            ));
+            return Err(PipelineError::AudioTooQuiet);
         }
 
         tracing::info!("Audio OK, proceeding to ASR...");
 
-        // 编码音频数据
-        let audio_data = if config.asr.provider == "OpenAIWhisper" {
-            // OpenAI Whisper 需要 WAV 格式
-            let channels = {
-                let recorder = self.recorder.read().map_err(|e| {
-                    PipelineError::Other(format!("Failed to acquire recorder lock: {}", e))
-                })?;
-                recorder.channels()
-            };
-            encode_to_wav(&samples, sample_rate, channels)?
-        } else {
-            // 其他服务使用 PCM
-            encode_to_pcm(&samples)
-        };
+        if channels == 2 && config.audio.diarize_channels {
+            return self.transcribe_channels(config, samples, sample_rate).await;
+        }
 
-        // 检查取消标志
-        if self.cancelled.load(Ordering::SeqCst) {
-            self.state.store(PipelineState::Idle as u8, Ordering::SeqCst);
-            self.cancelled.store(false, Ordering::SeqCst);
-            return Err(PipelineError::Cancelled);
+        // 未按声道识别时混合为单声道，编码（含 WAV 头）均按单声道处理
+        let encode_started = Instant::now();
+        let mono = downmix_to_mono(samples, channels);
+        self.add_metrics(|m| m.encode_ms += encode_started.elapsed().as_millis() as u64);
+        self.transcribe(config, &mono, sample_rate, live).await
+    }
+
+    /// 双声道分别识别，并按声道标注说话人
+    async fn transcribe_channels(
+        &self,
+        config: &AppConfig,
+        samples: &[f32],
+        sample_rate: u32,
+    ) -> Result<Transcription, PipelineError> {
+        let encode_started = Instant::now();
+        let (left, right) = split_stereo(samples);
+        self.add_metrics(|m| m.encode_ms += encode_started.elapsed().as_millis() as u64);
+
+        let (left_text, right_text) = tokio::join!(
+            self.transcribe(config, &left, sample_rate, None),
+            self.transcribe(config, &right, sample_rate, None),
+        );
+
+        let [left, right] = [left_text?, right_text?];
+        Ok(Transcription {
+            raw: label_speakers([left.raw.as_str(), right.raw.as_str()]),
+            text: label_speakers([left.text.as_str(), right.text.as_str()]),
+            provider: left.provider,
+        })
+    }
+
+    /// 将置信度低于 `min` 的词通知监听器
+    fn notify_low_confidence_words(&self, words: &[WordInfo], min: f32) {
+        let uncertain: Vec<WordInfo> = words
+            .iter()
+            .filter(|word| word.confidence.is_some_and(|confidence| confidence < min))
+            .cloned()
+            .collect();
+        if uncertain.is_empty() {
+            return;
         }
 
-        // 创建 ASR 服务并识别
-        let asr_service = create_asr_service(&config.asr)?;
-        let asr_result = match asr_service.recognize(&audio_data, sample_rate).await {
-            Ok(r) => r,
-            Err(e) => {
-                self.state.store(PipelineState::Idle as u8, Ordering::SeqCst);
-                return Err(e.into());
+        tracing::warn!("{} words below confidence threshold {:.2}", uncertain.len(), min);
+        let listener = self.low_confidence_words_listener.read().ok().and_then(|l| l.clone());
+        if let Some(listener) = listener {
+            listener(uncertain);
+        }
+    }
+
+    /// 单声道音频识别，并按配置进行 LLM 优化
+    async fn transcribe(
+        &self,
+        config: &AppConfig,
+        samples: &[f32],
+        sample_rate: u32,
+        live: Option<LiveRecognition>,
+    ) -> Result<Transcription, PipelineError> {
+        // 识别（主服务商网络不可用时依次尝试备用服务商）
+        self.emit_stage(PipelineStage::AsrStarted);
+        let asr_result = self
+            .recognize_with_fallback(config, samples, sample_rate, live)
+            .await;
+        self.emit_stage(PipelineStage::AsrFinished);
+        let (asr_result, provider, hotwords_applied) = asr_result?;
+
+        tracing::info!("ASR result: {} (confidence: {:?})", asr_result.text, asr_result.confidence);
+
+        // 置信度过低时不输出，提示用户重说
+        if let (Some(min), Some(confidence)) = (config.asr.min_confidence, asr_result.confidence) {
+            if confidence < min {
+                tracing::warn!("ASR confidence {:.2} below threshold {:.2}", confidence, min);
+                return Err(PipelineError::LowConfidence(confidence));
             }
-        };
+        }
 
-        tracing::info!("ASR result: {}", asr_result.text);
+        // 标记置信度过低的词供用户复核，文本照常输出
+        if let Some(min) = config.asr.min_word_confidence {
+            self.notify_low_confidence_words(&asr_result.words, min);
+        }
 
         // 再次检查取消标志
-        if self.cancelled.load(Ordering::SeqCst) {
-            self.state.store(PipelineState::Idle as u8, Ordering::SeqCst);
-            self.cancelled.store(false, Ordering::SeqCst);
-            return Err(PipelineError::Cancelled);
-        }
+        self.check_cancelled()?;
 
-        let mut final_text = asr_result.text.clone();
+        // 服务商开启了说话人分离时按说话人分行标注
+        let mut final_text = if asr_result.segments.is_empty() {
+            asr_result.text.clone()
+        } else {
+            label_speaker_segments(&asr_result.segments)
+        };
+        let mut refined_by_llm = false;
 
         // 如果启用了 LLM，进行文本优化
         if config.llm.enabled && !final_text.is_empty() {
-            if let Ok(Some(llm_service)) = create_llm_service(&config.llm) {
-                match llm_service.refine_text(&final_text).await {
-                    Ok(refined) => {
-                        tracing::info!("LLM refined: {} -> {}", final_text, refined);
-                        final_text = refined;
+            let model = self.llm_model.lock().unwrap().clone();
+            let llm_config = llm_config_with_hotwords(config, hotwords_applied);
+            if let Ok(Some(llm_service)) = create_llm_service(&llm_config, model.as_deref()) {
+                self.emit_stage(PipelineStage::LlmStarted);
+                let partial_listener =
+                    self.llm_partial_listener.read().ok().and_then(|l| l.clone());
+                let llm_started = Instant::now();
+                let refined =
+                    refine_with_llm(llm_service.as_ref(), config, &final_text, partial_listener)
+                        .await;
+                self.add_metrics(|m| m.llm_ms += llm_started.elapsed().as_millis() as u64);
+                self.emit_stage(PipelineStage::LlmFinished);
+                if let Some(refined) = refined {
+                    final_text = refined;
+                    refined_by_llm = true;
+                }
+            }
+        }
+
+        // 未经 LLM 优化时做基础规整（大小写、句末标点、语气词）
+        if !refined_by_llm {
+            final_text = postprocess::post_process(&final_text, &config.output.text_rules);
+        }
+
+        // 规则后处理（数字格式等）
+        Ok(Transcription {
+            text: postprocess::apply(&config.postprocess, &final_text),
+            raw: asr_result.text,
+            provider,
+        })
+    }
+
+    /// 依次使用主服务商和 `asr.fallback_providers` 识别
+    ///
+    /// 返回结果、实际使用的服务商，以及热词是否已传给该服务商
+    ///
+    /// 只有重试后仍为网络错误时才切换到下一个服务商，服务端返回的错误直接返回；
+    /// 每个服务商独立创建连接并使用自己的超时计时
+    ///
+    /// 有边录边传的识别会话时先等待其结果，网络错误时再用完整录音从主服务商开始识别
+    async fn recognize_with_fallback(
+        &self,
+        config: &AppConfig,
+        samples: &[f32],
+        sample_rate: u32,
+        live: Option<LiveRecognition>,
+    ) -> Result<(AsrResult, String, bool), PipelineError> {
+        if let Some(mut live) = live.filter(|live| live.provider == config.asr.provider) {
+            let asr_started = Instant::now();
+            let result = live.finish().await;
+            self.add_metrics(|m| m.asr_ms += asr_started.elapsed().as_millis() as u64);
+            match result {
+                Ok(result) => {
+                    tracing::info!("Live recognition succeeded with provider {}", live.provider);
+                    let listener = self.provider_listener.read().ok().and_then(|l| l.clone());
+                    if let Some(listener) = listener {
+                        listener(live.provider.clone());
                     }
-                    Err(e) => {
-                        tracing::warn!("LLM refinement failed, using original: {}", e);
+                    return Ok((result, live.provider.clone(), live.hotwords_applied));
+                }
+                Err(AsrError::Network(error)) => {
+                    tracing::warn!("Live recognition failed ({}), using full recording", error);
+                }
+                Err(AsrError::NoAudio) => {
+                    tracing::warn!("Live recognition got no audio, using full recording");
+                }
+                Err(e) => return Err(e.into()),
+            }
+            self.check_cancelled()?;
+        }
+
+        let providers = std::iter::once(&config.asr.provider).chain(&config.asr.fallback_providers);
+        let mut network_error: Option<String> = None;
+
+        for provider in providers {
+            if let Some(error) = &network_error {
+                tracing::warn!("ASR network error ({}), falling back to {}", error, provider);
+            }
+
+            let mut asr_config = config.asr.clone();
+            asr_config.provider = provider.clone();
+
+            // 创建 ASR 服务，按其支持的采样率编码音频数据；备用服务商配置有误时跳过
+            let asr_service = match create_asr_service(&asr_config) {
+                Ok(service) => service,
+                Err(e) if network_error.is_some() => {
+                    tracing::warn!("Skipping fallback ASR provider {}: {}", provider, e);
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            };
+            let encode_started = Instant::now();
+            let (audio_data, rate) =
+                prepare_audio(asr_service.as_ref(), provider, samples, sample_rate)?;
+            self.add_metrics(|m| m.encode_ms += encode_started.elapsed().as_millis() as u64);
+
+            // 检查取消标志
+            self.check_cancelled()?;
+
+            let asr_started = Instant::now();
+            let result = self
+                .recognize_with_retry(config, asr_service.as_ref(), &audio_data, rate)
+                .await;
+            self.add_metrics(|m| m.asr_ms += asr_started.elapsed().as_millis() as u64);
+            match result {
+                Err(PipelineError::Asr(AsrError::Network(error))) => network_error = Some(error),
+                Err(e) => return Err(e),
+                Ok(result) => {
+                    tracing::info!("ASR succeeded with provider {}", provider);
+                    let listener = self.provider_listener.read().ok().and_then(|l| l.clone());
+                    if let Some(listener) = listener {
+                        listener(provider.clone());
                     }
+                    return Ok((result, provider.clone(), asr_service.supports_hotwords()));
                 }
             }
         }
 
-        // 完成，恢复 Idle 状态
-        self.state.store(PipelineState::Idle as u8, Ordering::SeqCst);
-        tracing::info!("stop_and_process completed successfully");
-        Ok(final_text)
+        Err(AsrError::Network(network_error.unwrap_or_default()).into())
+    }
+
+    /// 调用 ASR 识别，网络错误时按指数退避重试（最多 `asr.max_retries` 次）
+    async fn recognize_with_retry(
+        &self,
+        config: &AppConfig,
+        asr_service: &dyn AsrService,
+        audio_data: &[u8],
+        sample_rate: u32,
+    ) -> Result<AsrResult, PipelineError> {
+        let max_retries = config.asr.max_retries;
+        let mut attempt = 0;
+        loop {
+            match self.recognize_once(asr_service, audio_data, sample_rate).await {
+                Err(AsrError::Network(error)) if attempt < max_retries => {
+                    attempt += 1;
+                    let delay = ASR_RETRY_BASE_DELAY * 2u32.saturating_pow(attempt - 1);
+                    tracing::warn!(
+                        "ASR network error: {}, retrying in {:?} ({}/{})",
+                        error,
+                        delay,
+                        attempt,
+                        max_retries
+                    );
+                    let listener = self.retry_listener.read().ok().and_then(|l| l.clone());
+                    if let Some(listener) = listener {
+                        listener(AsrRetryEvent {
+                            attempt,
+                            max_retries,
+                            delay_ms: delay.as_millis() as u64,
+                            error,
+                        });
+                    }
+                    tokio::time::sleep(delay).await;
+                    self.check_cancelled()?;
+                }
+                result => return Ok(result?),
+            }
+        }
+    }
+
+    /// 单次识别，设置了中间结果监听器时同时转发中间结果
+    async fn recognize_once(
+        &self,
+        asr_service: &dyn AsrService,
+        audio_data: &[u8],
+        sample_rate: u32,
+    ) -> Result<AsrResult, AsrError> {
+        let partial_listener = self.partial_listener.read().ok().and_then(|l| l.clone());
+        match partial_listener {
+            Some(listener) => {
+                // 识别与转发并行，识别结束后发送端释放，转发随之结束
+                let (partial_tx, mut partial_rx) = mpsc::channel::<String>(16);
+                let recognize =
+                    asr_service.recognize_streaming(audio_data, sample_rate, partial_tx);
+                let forward = async {
+                    while let Some(partial) = partial_rx.recv().await {
+                        listener(partial);
+                    }
+                };
+                tokio::join!(recognize, forward).0
+            }
+            None => asr_service.recognize(audio_data, sample_rate).await,
+        }
     }
 
     // ========================================================================
@@ -365,7 +1385,7 @@ impl VoicePipeline {
         self.streaming_mode.store(true, Ordering::SeqCst);
 
         // 获取配置和采样率
-        let config = self.config.read().await.clone();
+        let config = self.session_config().await;
         let sample_rate = {
             let recorder = self.recorder.read().map_err(|e| {
                 PipelineError::Other(format!("Failed to acquire recorder lock: {}", e))
@@ -388,6 +1408,14 @@ impl VoicePipeline {
             let mut recorder = self.recorder.write().map_err(|e| {
                 PipelineError::Other(format!("Failed to acquire recorder lock: {}", e))
             })?;
+            // 流式识别只支持单声道
+            recorder.set_keep_stereo(false);
+            recorder.set_preallocate_secs(config.audio.buffer_preallocate_secs);
+            recorder.set_preroll_ms(config.audio.preroll_ms);
+            // 连续输入会话由调用方结束，不限制时长
+            recorder.set_max_duration(0, None);
+            recorder.set_silence_stop(0, 0, None);
+            recorder.set_level_callback(self.level_listener.read().ok().and_then(|l| l.clone()));
             recorder.start()?;
         }
 
@@ -441,14 +1469,120 @@ impl VoicePipeline {
         let state = self.state.clone();
         let streaming_mode = self.streaming_mode.clone();
         let config_for_asr = config.clone();
+        let recorder_for_asr = self.recorder.clone();
+        let commit_phrase = config.streaming.active_commit_phrase().map(str::to_string);
+        let postprocess_config = config.postprocess.clone();
+        // 逐句 LLM 优化：每个 Final 片段优化后再转发，长段听写可逐句输出
+        let segment_llm = if config.streaming.refine_segments {
+            let model = self.llm_model.lock().unwrap().clone();
+            // 流式识别服务不支持热词，统一交给 LLM 修正
+            let llm_config = llm_config_with_hotwords(&config, false);
+            create_llm_service(&llm_config, model.as_deref()).unwrap_or_else(|e| {
+                tracing::warn!("Segment refinement disabled: {}", e);
+                None
+            })
+        } else {
+            None
+        };
+        // AcceptInterim：停止后超过宽限时间仍无 Final，则用最近的中间结果作为最终结果
+        let interim_grace = (config.streaming.final_result_policy
+            == FinalResultPolicy::AcceptInterim)
+            .then(|| Duration::from_millis(config.streaming.interim_grace_ms));
 
         tokio::spawn(async move {
             let mut current_event_rx = event_rx;
+            let mut commit_phrase_detected = false;
+            let mut latest_interim: Option<String> = None;
+            let mut stop_seen_at: Option<Instant> = None;
 
             loop {
                 // 处理当前 ASR 连接的事件
                 // 注意：不在这里检查 should_stop，必须等到 Final/Error 才能退出
-                while let Some(event) = current_event_rx.recv().await {
+                while let Some((event, from_interim)) = recv_streaming_event(
+                    &mut current_event_rx,
+                    interim_grace,
+                    &should_stop_for_asr,
+                    &mut stop_seen_at,
+                    latest_interim.as_deref(),
+                )
+                .await
+                {
+                    if from_interim {
+                        // 不再等待服务端结果，关闭当前会话
+                        if let Some(tx) = control_tx_holder_for_asr.read().await.as_ref() {
+                            let _ = tx.send(StreamingControl::Cancel).await;
+                        }
+                    }
+
+                    match &event {
+                        StreamingAsrEvent::Partial { text, stash } => {
+                            latest_interim = Some(format!("{}{}", text, stash));
+                        }
+                        StreamingAsrEvent::Final { .. } => latest_interim = None,
+                        StreamingAsrEvent::Error(_) => {}
+                    }
+
+                    // 结束口令：检测到后停止录音并提交，最终结果中去掉口令
+                    let event = match (event, commit_phrase.as_deref()) {
+                        (StreamingAsrEvent::Partial { text, stash }, Some(phrase)) => {
+                            let partial = format!("{}{}", text, stash);
+                            if !commit_phrase_detected
+                                && find_trailing_commit_phrase(&partial, phrase).is_some()
+                            {
+                                tracing::info!("Commit phrase detected, finishing session");
+                                commit_phrase_detected = true;
+                                should_stop_for_asr.store(true, Ordering::SeqCst);
+                                if let Ok(mut recorder) = recorder_for_asr.write() {
+                                    let _ = recorder.stop();
+                                }
+                                if let Some(tx) = control_tx_holder_for_asr.read().await.as_ref() {
+                                    let _ = tx.send(StreamingControl::Commit).await;
+                                }
+                                state.store(PipelineState::Processing as u8, Ordering::SeqCst);
+                            }
+                            if commit_phrase_detected {
+                                // 口令可能跨越稳定部分和暂定部分，合并后去掉
+                                StreamingAsrEvent::Partial {
+                                    text: strip_commit_phrase(&partial, phrase),
+                                    stash: String::new(),
+                                }
+                            } else {
+                                StreamingAsrEvent::Partial { text, stash }
+                            }
+                        }
+                        (StreamingAsrEvent::Final { text }, Some(phrase))
+                            if commit_phrase_detected =>
+                        {
+                            StreamingAsrEvent::Final {
+                                text: strip_commit_phrase(&text, phrase),
+                            }
+                        }
+                        (event, _) => event,
+                    };
+
+                    // 最终结果进行 LLM 优化（如启用）和规则后处理
+                    let event = match event {
+                        StreamingAsrEvent::Final { text } => {
+                            let text = match &segment_llm {
+                                Some(llm_service) if !text.trim().is_empty() => {
+                                    refine_with_llm(
+                                        llm_service.as_ref(),
+                                        &config_for_asr,
+                                        &text,
+                                        None,
+                                    )
+                                    .await
+                                    .unwrap_or(text)
+                                }
+                                _ => text,
+                            };
+                            StreamingAsrEvent::Final {
+                                text: postprocess::apply(&postprocess_config, &text),
+                            }
+                        }
+                        event => event,
+                    };
+
                     let is_final = matches!(event, StreamingAsrEvent::Final { .. });
                     let is_error = matches!(event, StreamingAsrEvent::Error(_));
 
@@ -612,3 +1746,229 @@ impl VoicePipeline {
         self.streaming_mode.load(Ordering::SeqCst)
     }
 }
+
+/// LLM 配置：热词未传给 ASR 服务商时加入术语表，由 LLM 按术语表修正
+fn llm_config_with_hotwords(config: &AppConfig, hotwords_applied: bool) -> LlmConfig {
+    let mut llm_config = config.llm.clone();
+    if !hotwords_applied {
+        llm_config.glossary.extend(config.asr.hotwords.iter().cloned());
+    }
+    llm_config
+}
+
+/// 将录音回调的数据块编码为 16-bit PCM，合并到约 `chunk_samples` 个样本后发送给 ASR
+///
+/// 录音停止（发送端释放）后发送剩余数据并关闭 `audio_tx`；识别已结束时直接返回
+async fn forward_live_audio(
+    mut samples_rx: mpsc::UnboundedReceiver<Vec<f32>>,
+    audio_tx: mpsc::Sender<Vec<u8>>,
+    chunk_samples: usize,
+) {
+    let mut pending = Vec::with_capacity(chunk_samples);
+    while let Some(samples) = samples_rx.recv().await {
+        pending.extend_from_slice(&samples);
+        if pending.len() >= chunk_samples {
+            if audio_tx.send(encode_to_pcm(&pending)).await.is_err() {
+                return;
+            }
+            pending.clear();
+        }
+    }
+    if !pending.is_empty() {
+        let _ = audio_tx.send(encode_to_pcm(&pending)).await;
+    }
+}
+
+/// 使用 LLM 优化识别文本，失败时返回 None（调用方使用原文）
+async fn refine_with_llm(
+    llm_service: &dyn LlmService,
+    config: &AppConfig,
+    text: &str,
+    partial_listener: Option<LlmPartialListener>,
+) -> Option<String> {
+    let result = match partial_listener {
+        Some(listener) => {
+            // 优化与转发并行，优化结束后发送端释放，转发随之结束
+            let (token_tx, mut token_rx) = mpsc::channel::<String>(64);
+            let refine = llm_service.refine_text_streaming(text, token_tx);
+            let forward = async {
+                let mut partial = String::new();
+                while let Some(token) = token_rx.recv().await {
+                    partial.push_str(&token);
+                    listener(partial.clone());
+                }
+            };
+            tokio::join!(refine, forward).0
+        }
+        None => llm_service.refine_text(text).await,
+    };
+
+    match result {
+        Ok(refined) => {
+            tracing::info!("LLM refined: {} -> {}", text, refined);
+            Some(if config.postprocess.strip_markdown {
+                postprocess::strip_markdown(&refined)
+            } else {
+                refined
+            })
+        }
+        Err(e) => {
+            tracing::warn!("LLM refinement failed, using original: {}", e);
+            None
+        }
+    }
+}
+
+/// 接收下一个流式识别事件
+///
+/// 设置了宽限时间（AcceptInterim 策略）时，停止录音后超过宽限时间仍未收到 Final，
+/// 则以最近一次中间结果作为 Final 返回，第二个返回值为 true
+async fn recv_streaming_event(
+    rx: &mut mpsc::Receiver<StreamingAsrEvent>,
+    interim_grace: Option<Duration>,
+    should_stop: &AtomicBool,
+    stop_seen_at: &mut Option<Instant>,
+    latest_interim: Option<&str>,
+) -> Option<(StreamingAsrEvent, bool)> {
+    let Some(grace) = interim_grace else {
+        return rx.recv().await.map(|event| (event, false));
+    };
+
+    loop {
+        if let Ok(event) = tokio::time::timeout(INTERIM_POLL_INTERVAL, rx.recv()).await {
+            return event.map(|event| (event, false));
+        }
+        if !should_stop.load(Ordering::SeqCst) {
+            continue;
+        }
+
+        let stopped_at = *stop_seen_at.get_or_insert_with(Instant::now);
+        if let Some(text) = latest_interim.filter(|text| !text.trim().is_empty()) {
+            if stopped_at.elapsed() >= grace {
+                tracing::info!("Final not received within {:?}, using latest interim", grace);
+                let event = StreamingAsrEvent::Final {
+                    text: text.to_string(),
+                };
+                return Some((event, true));
+            }
+        }
+    }
+}
+
+/// 口令前后可忽略的空白和标点
+fn is_phrase_separator(c: char) -> bool {
+    c.is_whitespace() || c.is_ascii_punctuation() || "，。！？、".contains(c)
+}
+
+/// 文本以结束口令结尾时（忽略大小写、空白和末尾标点），返回口令开始的字节位置
+///
+/// 只匹配末尾，句中偶然说到口令不会提前结束录音
+fn find_trailing_commit_phrase(text: &str, phrase: &str) -> Option<usize> {
+    let mut expected = phrase
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .rev()
+        .peekable();
+    expected.peek()?;
+
+    let body = text.trim_end_matches(is_phrase_separator);
+    for (index, c) in body.char_indices().rev() {
+        if c.is_whitespace() {
+            continue;
+        }
+        for lower in c.to_lowercase().rev() {
+            if expected.next() != Some(lower) {
+                return None;
+            }
+        }
+        if expected.peek().is_none() {
+            return Some(index);
+        }
+    }
+    None
+}
+
+/// 去掉文本末尾的结束口令及其前面的标点，末尾不是口令时原样返回
+fn strip_commit_phrase(text: &str, phrase: &str) -> String {
+    match find_trailing_commit_phrase(text, phrase) {
+        Some(index) => text[..index].trim_end_matches(is_phrase_separator).to_string(),
+        None => text.to_string(),
+    }
+}
+
+/// 按声道标注说话人，跳过空文本
+fn label_speakers<'a>(texts: impl IntoIterator<Item = &'a str>) -> String {
+    texts
+        .into_iter()
+        .enumerate()
+        .filter(|(_, text)| !text.is_empty())
+        .map(|(i, text)| format!("[说话人{}] {}", i + 1, text))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 按服务商返回的说话人标签逐行标注（"Speaker A: ..."），相邻的同一说话人片段合并为一行
+fn label_speaker_segments(segments: &[SpeakerSegment]) -> String {
+    let mut lines: Vec<(&str, String)> = Vec::new();
+    for segment in segments.iter().filter(|segment| !segment.text.is_empty()) {
+        match lines.last_mut() {
+            Some((speaker, text)) if *speaker == segment.speaker => {
+                text.push(' ');
+                text.push_str(&segment.text);
+            }
+            _ => lines.push((&segment.speaker, segment.text.clone())),
+        }
+    }
+    lines
+        .into_iter()
+        .map(|(speaker, text)| format!("Speaker {}: {}", speaker, text))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 将交错排列的双声道数据拆分为左右声道
+fn split_stereo(samples: &[f32]) -> (Vec<f32>, Vec<f32>) {
+    samples
+        .chunks_exact(2)
+        .map(|frame| (frame[0], frame[1]))
+        .unzip()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commit_phrase_only_matches_at_end() {
+        assert!(find_trailing_commit_phrase("今天就到这里，发送。", "发送").is_some());
+        assert!(find_trailing_commit_phrase("Please SEND IT!", "send it").is_some());
+        assert!(find_trailing_commit_phrase("把邮件发送给他", "发送").is_none());
+        assert!(find_trailing_commit_phrase("发送", "").is_none());
+    }
+
+    #[test]
+    fn strip_commit_phrase_keeps_earlier_mentions() {
+        assert_eq!(strip_commit_phrase("先发送报告，然后发送。", "发送"), "先发送报告，然后");
+        assert_eq!(strip_commit_phrase("that is all. Send it", "send it"), "that is all");
+        assert_eq!(strip_commit_phrase("把邮件发送给他", "发送"), "把邮件发送给他");
+    }
+
+    #[test]
+    fn speaker_segments_use_speaker_prefix() {
+        let segment = |speaker: &str, text: &str| SpeakerSegment {
+            speaker: speaker.to_string(),
+            text: text.to_string(),
+        };
+        let segments = [
+            segment("A", "Hello there."),
+            segment("A", "How are you?"),
+            segment("B", "Fine, thanks."),
+            segment("A", ""),
+        ];
+        assert_eq!(
+            label_speaker_segments(&segments),
+            "Speaker A: Hello there. How are you?\nSpeaker B: Fine, thanks."
+        );
+    }
+}
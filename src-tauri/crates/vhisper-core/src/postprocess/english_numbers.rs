@@ -0,0 +1,362 @@
+//! 英文数字转换
+//!
+//! 将英文数字单词转换为阿拉伯数字（"twenty five percent" → "25%"），支持小数、年份和常用单位
+
+/// 数字后的单位及其缩写，按长度优先匹配
+const UNITS: &[(&str, &str)] = &[
+    ("per cent", "%"),
+    ("percent", "%"),
+    ("degrees celsius", "°C"),
+    ("degrees fahrenheit", "°F"),
+    ("degrees", "°"),
+    ("degree", "°"),
+    ("kilometers", " km"),
+    ("kilometres", " km"),
+    ("kilometer", " km"),
+    ("kilometre", " km"),
+    ("centimeters", " cm"),
+    ("centimetres", " cm"),
+    ("millimeters", " mm"),
+    ("millimetres", " mm"),
+    ("meters", " m"),
+    ("metres", " m"),
+    ("kilograms", " kg"),
+    ("kilogram", " kg"),
+    ("grams", " g"),
+];
+
+/// 货币单位，符号写在数字前面（"five dollars" → "$5"）
+const CURRENCIES: &[(&str, &str)] = &[
+    ("dollars", "$"),
+    ("dollar", "$"),
+    ("euros", "€"),
+    ("euro", "€"),
+];
+
+/// 按两位一组读的年份前半部分（"nineteen eighty four"、"twenty twenty four"）
+///
+/// 其他组合（"eleven thirty"）多半是时间，不合并
+const YEAR_PREFIXES: &[u64] = &[19, 20];
+
+/// 文本中的一个英文单词（字节范围 + 小写形式）
+struct Word {
+    start: usize,
+    end: usize,
+    lower: String,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Kind {
+    Zero,
+    Ones(u64),
+    Teen(u64),
+    Tens(u64),
+    Hundred,
+    Scale(u64),
+}
+
+fn kind(word: &str) -> Option<Kind> {
+    let kind = match word {
+        "zero" => Kind::Zero,
+        "one" => Kind::Ones(1),
+        "two" => Kind::Ones(2),
+        "three" => Kind::Ones(3),
+        "four" => Kind::Ones(4),
+        "five" => Kind::Ones(5),
+        "six" => Kind::Ones(6),
+        "seven" => Kind::Ones(7),
+        "eight" => Kind::Ones(8),
+        "nine" => Kind::Ones(9),
+        "ten" => Kind::Teen(10),
+        "eleven" => Kind::Teen(11),
+        "twelve" => Kind::Teen(12),
+        "thirteen" => Kind::Teen(13),
+        "fourteen" => Kind::Teen(14),
+        "fifteen" => Kind::Teen(15),
+        "sixteen" => Kind::Teen(16),
+        "seventeen" => Kind::Teen(17),
+        "eighteen" => Kind::Teen(18),
+        "nineteen" => Kind::Teen(19),
+        "twenty" => Kind::Tens(20),
+        "thirty" => Kind::Tens(30),
+        "forty" => Kind::Tens(40),
+        "fifty" => Kind::Tens(50),
+        "sixty" => Kind::Tens(60),
+        "seventy" => Kind::Tens(70),
+        "eighty" => Kind::Tens(80),
+        "ninety" => Kind::Tens(90),
+        "hundred" => Kind::Hundred,
+        "thousand" => Kind::Scale(1_000),
+        "million" => Kind::Scale(1_000_000),
+        "billion" => Kind::Scale(1_000_000_000),
+        _ => return None,
+    };
+    Some(kind)
+}
+
+/// 小数点后和年份中按位读的数字，"oh" 读作 0
+fn digit(word: &str) -> Option<u64> {
+    match kind(word) {
+        Some(Kind::Zero) => Some(0),
+        Some(Kind::Ones(d)) => Some(d),
+        _ if word == "oh" => Some(0),
+        _ => None,
+    }
+}
+
+/// 拆分出英文单词；紧挨数字的单词（"5th"、"mp3"）不参与转换
+fn split_words(text: &str) -> Vec<Word> {
+    let bytes = text.as_bytes();
+    let mut words = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if !bytes[i].is_ascii_alphabetic() {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < bytes.len() && bytes[i].is_ascii_alphabetic() {
+            i += 1;
+        }
+        let touches_digit = (start > 0 && bytes[start - 1].is_ascii_digit())
+            || bytes.get(i).is_some_and(|b| b.is_ascii_digit());
+        if !touches_digit {
+            words.push(Word {
+                start,
+                end: i,
+                lower: text[start..i].to_ascii_lowercase(),
+            });
+        }
+    }
+
+    words
+}
+
+/// 第 k 个单词与下一个单词是否相连（只隔空格或一个连字符，如 "twenty-five"）
+fn joined(text: &str, words: &[Word], k: usize) -> bool {
+    let Some(next) = words.get(k + 1) else {
+        return false;
+    };
+    let gap = &text[words[k].end..next.start];
+    gap == "-" || (!gap.is_empty() && gap.bytes().all(|b| b == b' '))
+}
+
+/// 从 start 开始匹配多词短语（如 "per cent"），返回短语之后的单词位置
+fn phrase_at(text: &str, words: &[Word], start: usize, phrase: &str) -> Option<usize> {
+    let mut k = start;
+    for part in phrase.split(' ') {
+        if words.get(k)?.lower != part || (k > start && !joined(text, words, k - 1)) {
+            return None;
+        }
+        k += 1;
+    }
+    Some(k)
+}
+
+/// 匹配 start 处的单位，返回（缩写, 结束位置）
+fn unit_at(
+    text: &str,
+    words: &[Word],
+    start: usize,
+    units: &[(&str, &'static str)],
+) -> Option<(&'static str, usize)> {
+    if start == 0 || !joined(text, words, start - 1) {
+        return None;
+    }
+    units.iter().find_map(|&(phrase, symbol)| {
+        phrase_at(text, words, start, phrase).map(|end| (symbol, end))
+    })
+}
+
+/// 解析英文整数，如 "one hundred and twenty three"、"two thousand twenty four"
+///
+/// 遇到不符合读法的单词即停止（"five six" 只解析 "five"），返回数值和结束位置
+fn parse_integer(text: &str, words: &[Word], start: usize) -> Option<(u64, usize)> {
+    let mut total = 0;
+    let mut current = 0;
+    let mut last: Option<Kind> = None;
+    let mut scale_limit = u64::MAX;
+    let mut i = start;
+
+    while i < words.len() {
+        if i > start && !joined(text, words, i - 1) {
+            break;
+        }
+        let word = words[i].lower.as_str();
+
+        // "one hundred and five"：and 只出现在百、千之后
+        if word == "and" {
+            let before_small = joined(text, words, i)
+                && words.get(i + 1).is_some_and(|next| {
+                    matches!(
+                        kind(&next.lower),
+                        Some(Kind::Ones(_) | Kind::Teen(_) | Kind::Tens(_))
+                    )
+                });
+            if before_small && matches!(last, Some(Kind::Hundred | Kind::Scale(_))) {
+                i += 1;
+                continue;
+            }
+            break;
+        }
+
+        let Some(k) = kind(word) else {
+            break;
+        };
+        let allowed = match (k, last) {
+            (Kind::Zero, None) => true,
+            (Kind::Ones(_), None | Some(Kind::Tens(_) | Kind::Hundred | Kind::Scale(_))) => true,
+            (Kind::Teen(_) | Kind::Tens(_), None | Some(Kind::Hundred | Kind::Scale(_))) => true,
+            (Kind::Hundred, Some(Kind::Ones(_) | Kind::Teen(_) | Kind::Tens(_))) => current < 100,
+            (
+                Kind::Scale(s),
+                Some(Kind::Ones(_) | Kind::Teen(_) | Kind::Tens(_) | Kind::Hundred),
+            ) => s < scale_limit,
+            _ => false,
+        };
+        if !allowed {
+            break;
+        }
+
+        match k {
+            Kind::Zero => {}
+            Kind::Ones(v) | Kind::Teen(v) | Kind::Tens(v) => current += v,
+            Kind::Hundred => current *= 100,
+            Kind::Scale(s) => {
+                total += current * s;
+                current = 0;
+                scale_limit = s;
+            }
+        }
+        last = Some(k);
+        i += 1;
+    }
+
+    last.map(|_| (total + current, i))
+}
+
+/// 年份后半部分："eighty four"、"oh five"
+fn year_tail(text: &str, words: &[Word], start: usize) -> Option<(String, usize)> {
+    if start == 0 || !joined(text, words, start - 1) {
+        return None;
+    }
+    if words.get(start)?.lower == "oh" {
+        let d = joined(text, words, start)
+            .then(|| words.get(start + 1).and_then(|w| kind(&w.lower)))
+            .flatten();
+        return match d {
+            Some(Kind::Ones(d)) => Some((format!("0{}", d), start + 2)),
+            _ => None,
+        };
+    }
+    let (value, end) = parse_integer(text, words, start)?;
+    (10..100).contains(&value).then(|| (value.to_string(), end))
+}
+
+/// 小数部分：point 后按位读的数字，如 "point one four"
+fn fraction(text: &str, words: &[Word], start: usize) -> Option<(String, usize)> {
+    if start == 0 || !joined(text, words, start - 1) || words.get(start)?.lower != "point" {
+        return None;
+    }
+    let mut digits = String::new();
+    let mut k = start + 1;
+    while k < words.len() && joined(text, words, k - 1) {
+        let Some(d) = digit(&words[k].lower) else {
+            break;
+        };
+        digits.push_str(&d.to_string());
+        k += 1;
+    }
+    (!digits.is_empty()).then_some((digits, k))
+}
+
+/// 从 start 开始解析一个英文数字（含年份、小数），返回转换结果、结束位置和是否为单个个位数
+///
+/// 紧跟着另一个数字的（"eleven thirty"、"five six"）读法不明确，整段返回 None
+fn parse_number(text: &str, words: &[Word], start: usize) -> Option<(String, usize, bool)> {
+    let (value, end) = parse_integer(text, words, start)?;
+    let single_digit = end == start + 1 && value < 10;
+
+    if YEAR_PREFIXES.contains(&value) && end == start + 1 {
+        if let Some((tail, year_end)) = year_tail(text, words, end) {
+            return Some((format!("{}{}", value, tail), year_end, false));
+        }
+    }
+
+    let mut number = value.to_string();
+    if let Some((digits, frac_end)) = fraction(text, words, end) {
+        number.push('.');
+        number.push_str(&digits);
+        return Some((number, frac_end, false));
+    }
+
+    let followed_by_number =
+        joined(text, words, end - 1) && words.get(end).is_some_and(|w| kind(&w.lower).is_some());
+    if followed_by_number {
+        return None;
+    }
+
+    Some((number, end, single_digit))
+}
+
+/// 从 start 开始连续的数字单词结束位置
+fn number_run_end(text: &str, words: &[Word], start: usize) -> usize {
+    let mut end = start + 1;
+    while end < words.len() && joined(text, words, end - 1) && kind(&words[end].lower).is_some() {
+        end += 1;
+    }
+    end
+}
+
+/// 英文数字转换为阿拉伯数字
+///
+/// - "twenty five" → "25"，"three point five" → "3.5"
+/// - "twenty five percent" → "25%"，"five dollars" → "$5"
+/// - "nineteen eighty four" → "1984"
+/// - 单独的个位数（"one of them"）不转换，后跟单位时才转换（"five km"）
+pub fn english_to_digits(text: &str) -> String {
+    let words = split_words(text);
+    let mut out = String::with_capacity(text.len());
+    let mut cursor = 0;
+    let mut i = 0;
+
+    while i < words.len() {
+        if kind(&words[i].lower).is_none() {
+            i += 1;
+            continue;
+        }
+        let Some((number, mut end, single_digit)) = parse_number(text, &words, i) else {
+            i = number_run_end(text, &words, i);
+            continue;
+        };
+
+        let currency = unit_at(text, &words, end, CURRENCIES);
+        let unit = match currency {
+            Some(_) => None,
+            None => unit_at(text, &words, end, UNITS),
+        };
+        if single_digit && currency.is_none() && unit.is_none() {
+            i = end;
+            continue;
+        }
+
+        out.push_str(&text[cursor..words[i].start]);
+        if let Some((symbol, currency_end)) = currency {
+            out.push_str(symbol);
+            out.push_str(&number);
+            end = currency_end;
+        } else if let Some((symbol, unit_end)) = unit {
+            out.push_str(&number);
+            out.push_str(symbol);
+            end = unit_end;
+        } else {
+            out.push_str(&number);
+        }
+        cursor = words[end - 1].end;
+        i = end;
+    }
+
+    out.push_str(&text[cursor..]);
+    out
+}
@@ -0,0 +1,96 @@
+//! Markdown 转纯文本
+//!
+//! LLM 优化结果可能带有加粗、列表等 Markdown 标记，粘贴到纯文本输入框时去掉这些符号
+
+/// 成对出现时才去掉的行内标记（长标记优先）
+const INLINE_MARKERS: &[&str] = &["**", "__", "~~", "*", "`"];
+
+/// 去掉 Markdown 格式，保留文字内容
+pub fn strip_markdown(text: &str) -> String {
+    text.lines()
+        .filter(|line| !is_fence_or_rule(line.trim()))
+        .map(strip_line)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 代码块围栏（```）和分割线（---、***）整行去掉
+fn is_fence_or_rule(line: &str) -> bool {
+    if line.starts_with("```") {
+        return true;
+    }
+    line.len() >= 3
+        && ['-', '*', '_']
+            .iter()
+            .any(|&c| line.chars().all(|ch| ch == c || ch == ' '))
+}
+
+fn strip_line(line: &str) -> String {
+    let indent_len = line.len() - line.trim_start().len();
+    let (indent, mut content) = line.split_at(indent_len);
+
+    // 标题与引用
+    let hashes = content.chars().take_while(|&c| c == '#').count();
+    if (1..=6).contains(&hashes) && content[hashes..].starts_with(' ') {
+        content = &content[hashes + 1..];
+    }
+    while let Some(rest) = content.strip_prefix("> ") {
+        content = rest;
+    }
+
+    // 无序列表标记（有序列表的 "1. " 在纯文本中也可读，保留）
+    for marker in ["- ", "* ", "+ "] {
+        if let Some(rest) = content.strip_prefix(marker) {
+            content = rest;
+            break;
+        }
+    }
+
+    let mut result = strip_links(content);
+    for marker in INLINE_MARKERS {
+        result = remove_paired(&result, marker);
+    }
+    format!("{}{}", indent, result)
+}
+
+/// `[文字](链接)` 和 `![说明](图片)` 只保留文字
+fn strip_links(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(open) = rest.find('[') {
+        let Some(close) = rest[open..].find("](").map(|i| open + i) else {
+            break;
+        };
+        let Some(end) = rest[close..].find(')').map(|i| close + i) else {
+            break;
+        };
+
+        let before = &rest[..open];
+        result.push_str(before.strip_suffix('!').unwrap_or(before));
+        result.push_str(&rest[open + 1..close]);
+        rest = &rest[end + 1..];
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// 去掉成对出现的标记，未配对的最后一个保留（如 "3 * 4"）
+fn remove_paired(text: &str, marker: &str) -> String {
+    let parts: Vec<&str> = text.split(marker).collect();
+    let markers = parts.len() - 1;
+    if markers < 2 {
+        return text.to_string();
+    }
+
+    let paired = markers - markers % 2;
+    let mut result = String::with_capacity(text.len());
+    for (i, part) in parts.iter().enumerate() {
+        if i > 0 && i > paired {
+            result.push_str(marker);
+        }
+        result.push_str(part);
+    }
+    result
+}
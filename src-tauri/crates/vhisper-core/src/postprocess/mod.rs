@@ -0,0 +1,81 @@
+//! 识别结果后处理
+//!
+//! 基于规则的文本转换，不依赖 LLM
+
+mod english_numbers;
+mod markdown;
+mod numbers;
+mod rules;
+
+pub use english_numbers::english_to_digits;
+pub use markdown::strip_markdown;
+pub use numbers::{to_chinese, to_digits};
+pub use rules::post_process;
+
+use crate::config::settings::{NumberFormat, PostProcessConfig};
+
+/// 按配置对识别结果进行后处理
+pub fn apply(config: &PostProcessConfig, text: &str) -> String {
+    match config.number_format {
+        NumberFormat::Off => text.to_string(),
+        NumberFormat::Digits => english_to_digits(&to_digits(text)),
+        NumberFormat::Chinese => to_chinese(text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits(text: &str) -> String {
+        let config = PostProcessConfig {
+            number_format: NumberFormat::Digits,
+            ..Default::default()
+        };
+        apply(&config, text)
+    }
+
+    #[test]
+    fn english_number_words_to_digits() {
+        let cases = [
+            ("twenty five percent", "25%"),
+            ("three point five", "3.5"),
+            ("one hundred and twenty three", "123"),
+            ("five dollars", "$5"),
+            ("ten kilometers", "10 km"),
+            ("nineteen eighty four", "1984"),
+            // 多半是时间，不合并为年份
+            ("eleven thirty", "eleven thirty"),
+            ("one of them", "one of them"),
+            ("growth was twenty five percent this year", "growth was 25% this year"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(digits(input), expected, "input: {}", input);
+        }
+    }
+
+    #[test]
+    fn chinese_number_words_to_digits() {
+        let cases = [
+            ("百分之五十", "50%"),
+            ("三点五", "3.5"),
+            ("一百二十三", "123"),
+            ("两万五", "25000"),
+            ("三块五", "3.5元"),
+            ("二零二四年三月五日", "2024年3月5日"),
+            ("一心一意", "一心一意"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(digits(input), expected, "input: {}", input);
+        }
+    }
+
+    #[test]
+    fn off_keeps_text() {
+        let config = PostProcessConfig {
+            number_format: NumberFormat::Off,
+            ..Default::default()
+        };
+        assert_eq!(apply(&config, "twenty five 百分之五十"), "twenty five 百分之五十");
+    }
+}
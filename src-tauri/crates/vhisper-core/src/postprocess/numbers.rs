@@ -0,0 +1,397 @@
+//! 数字格式转换
+//!
+//! 在中文数字（"三点五"）与阿拉伯数字（"3.5"）之间转换，支持金额、百分比和日期写法
+
+/// 不应转换的常用词（以数字开头）
+const IDIOMS: &[&str] = &[
+    "一起", "一样", "一下", "一直", "一定", "一些", "一般", "一切", "一边", "一旦", "一会",
+    "一向", "一致", "一律", "一再", "一番", "一口气", "一块儿", "一点儿", "一一", "十分",
+    "三心二意", "三三两两", "一五一十", "七七八八",
+];
+
+/// 单个数字后跟这些字符时才转换，如"五个"、"三月"
+const UNIT_SUFFIXES: &[char] = &[
+    '个', '元', '块', '岁', '年', '月', '日', '号', '点', '分', '秒', '米', '克', '斤', '度', '次',
+    '天', '周', '倍', '层', '楼', '%',
+];
+
+/// "一"的歧义最多，只在明确的日期/金额语境下转换
+const ONE_SUFFIXES: &[char] = &['元', '块', '岁', '月', '日', '号', '%'];
+
+const CHINESE_DIGITS: [char; 10] = ['零', '一', '二', '三', '四', '五', '六', '七', '八', '九'];
+
+fn digit_value(c: char) -> Option<u32> {
+    match c {
+        '零' | '〇' => Some(0),
+        '一' => Some(1),
+        '二' | '两' => Some(2),
+        '三' => Some(3),
+        '四' => Some(4),
+        '五' => Some(5),
+        '六' => Some(6),
+        '七' => Some(7),
+        '八' => Some(8),
+        '九' => Some(9),
+        _ => None,
+    }
+}
+
+fn unit_value(c: char) -> Option<u64> {
+    match c {
+        '十' => Some(10),
+        '百' => Some(100),
+        '千' => Some(1000),
+        '万' => Some(10_000),
+        '亿' => Some(100_000_000),
+        _ => None,
+    }
+}
+
+fn is_numeral(c: char) -> bool {
+    digit_value(c).is_some() || unit_value(c).is_some()
+}
+
+/// 将中文数字字符转换为阿拉伯数字字符
+fn to_ascii_digit(c: char) -> char {
+    digit_value(c)
+        .and_then(|d| char::from_digit(d, 10))
+        .unwrap_or(c)
+}
+
+fn starts_with(chars: &[char], start: usize, pattern: &str) -> bool {
+    pattern
+        .chars()
+        .enumerate()
+        .all(|(offset, p)| chars.get(start + offset) == Some(&p))
+}
+
+/// 匹配从 start 开始的常用词，返回其长度
+fn idiom_at(chars: &[char], start: usize) -> Option<usize> {
+    IDIOMS
+        .iter()
+        .find(|idiom| starts_with(chars, start, idiom))
+        .map(|idiom| idiom.chars().count())
+}
+
+/// 解析中文整数，如"一百二十三"、"两万五"；纯数字串按位读，如"二零二四"
+fn parse_integer(run: &[char]) -> Option<String> {
+    if run.iter().all(|&c| digit_value(c).is_some()) {
+        return Some(run.iter().map(|&c| to_ascii_digit(c)).collect());
+    }
+
+    let mut total = 0u64; // 万、亿以上的部分
+    let mut section = 0u64; // 万以内的部分
+    let mut number = 0u64; // 当前数字
+    let mut prev_unit = 0u64; // 紧邻前一个字符的单位
+    let mut trailing_unit = 0u64; // 末尾数字前的单位（用于"三百五"省略写法）
+
+    for &c in run {
+        if let Some(d) = digit_value(c) {
+            number = d as u64;
+            trailing_unit = if d == 0 { 0 } else { prev_unit };
+            prev_unit = 0;
+            continue;
+        }
+
+        let unit = unit_value(c)?;
+        trailing_unit = 0;
+        if unit >= 10_000 {
+            section += number;
+            if unit == 100_000_000 {
+                total = (total + section) * unit;
+            } else {
+                total += section * unit;
+            }
+            section = 0;
+        } else {
+            // "十五"、"一千零十"：十前省略"一"
+            let n = if number == 0 && unit == 10 { 1 } else { number };
+            section += n * unit;
+        }
+        number = 0;
+        prev_unit = unit;
+    }
+
+    // 省略写法："三百五" = 350，"两万五" = 25000
+    if trailing_unit >= 100 {
+        number *= trailing_unit / 10;
+    }
+
+    Some((total + section + number).to_string())
+}
+
+/// 从 start 开始解析一个中文数字（含小数），返回转换结果和结束位置
+fn parse_number(chars: &[char], start: usize) -> Option<(String, usize)> {
+    let first = *chars.get(start)?;
+    if digit_value(first).is_none() && first != '十' {
+        return None;
+    }
+
+    let mut end = start;
+    while end < chars.len() && is_numeral(chars[end]) {
+        end += 1;
+    }
+    let mut number = parse_integer(&chars[start..end])?;
+
+    // 小数："三点五"；"三点五分"是时间，不转换为小数
+    if chars.get(end) == Some(&'点') {
+        let frac_start = end + 1;
+        let mut frac_end = frac_start;
+        while frac_end < chars.len() && digit_value(chars[frac_end]).is_some() {
+            frac_end += 1;
+        }
+        if frac_end > frac_start && chars.get(frac_end) != Some(&'分') {
+            number.push('.');
+            number.extend(chars[frac_start..frac_end].iter().map(|&c| to_ascii_digit(c)));
+            end = frac_end;
+        }
+    }
+
+    Some((number, end))
+}
+
+/// 角（"三块五"中的"五"）：块后紧跟单个数字
+fn jiao_after(chars: &[char], kuai: usize) -> Option<u32> {
+    let d = chars.get(kuai + 1).and_then(|&c| digit_value(c))?;
+    if chars.get(kuai + 2).is_some_and(|&c| is_numeral(c)) {
+        return None;
+    }
+    Some(d)
+}
+
+/// 位于 kuai 处的"块"是否表示金额（"五块钱"、"三块五"）
+fn is_money(chars: &[char], kuai: usize) -> bool {
+    chars.get(kuai) == Some(&'块')
+        && (chars.get(kuai + 1) == Some(&'钱') || jiao_after(chars, kuai).is_some())
+}
+
+/// 判断解析出的数字是否应该转换（避免误伤"三明治"、"五六个"等）
+fn should_convert(chars: &[char], start: usize, end: usize) -> bool {
+    let run = &chars[start..end];
+    let next = chars.get(end).copied();
+
+    if run.len() == 1 {
+        // "一块蛋糕"中的"块"是量词，只有"一块钱"、"一块五"才是金额
+        if run[0] == '一' && next == Some('块') {
+            return is_money(chars, end);
+        }
+        let suffixes = if run[0] == '一' { ONE_SUFFIXES } else { UNIT_SUFFIXES };
+        return next.is_some_and(|c| suffixes.contains(&c));
+    }
+
+    // 两个相邻数字通常是约数（"五六个"），只在年份中按位转换
+    if run.len() == 2 && run.iter().all(|&c| digit_value(c).is_some_and(|d| d != 0)) {
+        return next == Some('年');
+    }
+
+    true
+}
+
+/// 中文数字转换为阿拉伯数字
+///
+/// - "三点五" → "3.5"，"一百二十三" → "123"
+/// - "二零二四年三月五日" → "2024年3月5日"
+/// - "百分之五十" → "50%"，"三块五" → "3.5元"
+pub fn to_digits(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        // 百分比
+        if starts_with(&chars, i, "百分之") {
+            if let Some((number, end)) = parse_number(&chars, i + 3) {
+                out.push_str(&number);
+                out.push('%');
+                i = end;
+                continue;
+            }
+        }
+
+        if let Some(len) = idiom_at(&chars, i) {
+            out.extend(&chars[i..i + len]);
+            i += len;
+            continue;
+        }
+
+        let Some((number, end)) = parse_number(&chars, i) else {
+            out.push(chars[i]);
+            i += 1;
+            continue;
+        };
+
+        if !should_convert(&chars, i, end) {
+            out.extend(&chars[i..end]);
+            i = end;
+            continue;
+        }
+        i = end;
+
+        // 金额："三块五毛钱" → "3.5元"；"三块蛋糕"中的"块"是量词，保持不变
+        if is_money(&chars, i) {
+            let mut amount = number;
+            if let Some(d) = jiao_after(&chars, i) {
+                amount.push('.');
+                amount.push_str(&d.to_string());
+                i += 1;
+                if matches!(chars.get(i + 1), Some(&'毛') | Some(&'角')) {
+                    i += 1;
+                }
+            }
+            i += 1; // 跳过最后一个已处理的字符
+            if chars.get(i) == Some(&'钱') {
+                i += 1;
+            }
+            out.push_str(&amount);
+            out.push('元');
+            continue;
+        }
+
+        out.push_str(&number);
+    }
+
+    out
+}
+
+/// 万以内的数字转换为中文
+fn section_to_chinese(section: u64) -> String {
+    const UNITS: [&str; 4] = ["", "十", "百", "千"];
+
+    let mut out = String::new();
+    let mut zero = false;
+    for pos in (0..4).rev() {
+        let d = (section / 10u64.pow(pos as u32) % 10) as usize;
+        if d == 0 {
+            if !out.is_empty() {
+                zero = true;
+            }
+        } else {
+            if zero {
+                out.push('零');
+                zero = false;
+            }
+            out.push(CHINESE_DIGITS[d]);
+            out.push_str(UNITS[pos]);
+        }
+    }
+    out
+}
+
+/// 整数转换为中文读法，如 10005 → "一万零五"
+fn int_to_chinese(mut n: u64) -> String {
+    const BIG_UNITS: [&str; 3] = ["", "万", "亿"];
+
+    if n == 0 {
+        return "零".to_string();
+    }
+
+    let mut sections = Vec::new();
+    while n > 0 {
+        sections.push(n % 10_000);
+        n /= 10_000;
+    }
+
+    let mut out = String::new();
+    let mut need_zero = false;
+    for (idx, &section) in sections.iter().enumerate().rev() {
+        if section == 0 {
+            if !out.is_empty() {
+                need_zero = true;
+            }
+            continue;
+        }
+        if !out.is_empty() && (need_zero || section < 1000) {
+            out.push('零');
+        }
+        need_zero = false;
+        out.push_str(&section_to_chinese(section));
+        out.push_str(BIG_UNITS[idx]);
+    }
+
+    // "十五" 而不是 "一十五"
+    if out.starts_with("一十") {
+        out.remove(0);
+    }
+    out
+}
+
+/// 逐位读数字，如 "2024" → "二零二四"
+fn digits_to_chinese(digits: &str) -> String {
+    digits
+        .chars()
+        .filter_map(|c| c.to_digit(10))
+        .map(|d| CHINESE_DIGITS[d as usize])
+        .collect()
+}
+
+/// 阿拉伯数字转换为中文数字
+///
+/// - "3.5" → "三点五"，"123" → "一百二十三"
+/// - "2024年" → "二零二四年"
+/// - "50%" → "百分之五十"，"¥5" → "五元"
+///
+/// 与字母相连的数字（如 "mp3"、"5G"）保持不变
+pub fn to_chinese(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len() * 2);
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let after_letter = i > 0 && chars[i - 1].is_ascii_alphabetic();
+        if !c.is_ascii_digit() || after_letter {
+            out.push(c);
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        while i < chars.len() && chars[i].is_ascii_digit() {
+            i += 1;
+        }
+        let int_part: String = chars[start..i].iter().collect();
+
+        let mut frac_part = None;
+        if chars.get(i) == Some(&'.') && chars.get(i + 1).is_some_and(|c| c.is_ascii_digit()) {
+            let frac_start = i + 1;
+            i = frac_start;
+            while i < chars.len() && chars[i].is_ascii_digit() {
+                i += 1;
+            }
+            frac_part = Some(chars[frac_start..i].iter().collect::<String>());
+        }
+
+        if chars.get(i).is_some_and(|c| c.is_ascii_alphabetic()) {
+            out.extend(&chars[start..i]);
+            continue;
+        }
+
+        let currency = out.ends_with('¥') || out.ends_with('￥');
+        if currency {
+            out.pop();
+        }
+        if chars.get(i) == Some(&'%') {
+            out.push_str("百分之");
+            i += 1;
+        }
+
+        let is_year = chars.get(i) == Some(&'年') && int_part.len() == 4 && frac_part.is_none();
+        let has_leading_zero = int_part.len() > 1 && int_part.starts_with('0');
+        match int_part.parse::<u64>() {
+            Ok(n) if !is_year && !has_leading_zero && int_part.len() <= 12 => {
+                out.push_str(&int_to_chinese(n));
+            }
+            _ => out.push_str(&digits_to_chinese(&int_part)),
+        }
+
+        if let Some(frac) = frac_part {
+            out.push('点');
+            out.push_str(&digits_to_chinese(&frac));
+        }
+        if currency {
+            out.push('元');
+        }
+    }
+
+    out
+}
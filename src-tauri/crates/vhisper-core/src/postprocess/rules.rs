@@ -0,0 +1,126 @@
+//! 不使用 LLM 时的基础文本规整
+//!
+//! 去掉语气词、英文句首大写、补全句末标点；中文句子补 "。"，英文句子补 "."
+
+use crate::config::settings::TextRules;
+
+/// 句末标点，已有时不再补全
+const SENTENCE_END: &[char] = &[
+    '.', '?', '!', '…', '。', '？', '！', ';', '；', '"', '”', '\'', ')', '）',
+];
+
+/// 语气词后紧跟的这些标点一并去掉
+const FILLER_TRAILING: &[char] = &[',', '，', '、'];
+
+/// 按规则规整识别文本
+pub fn post_process(text: &str, rules: &TextRules) -> String {
+    let mut text = text.trim().to_string();
+    if rules.trim_filler_words {
+        for filler in rules.filler_words.iter().map(|w| w.trim()).filter(|w| !w.is_empty()) {
+            text = remove_filler(&text, filler);
+        }
+        text = text.trim().to_string();
+    }
+    if rules.auto_capitalize {
+        text = capitalize_sentences(&text);
+    }
+    if rules.auto_period {
+        append_period(&mut text);
+    }
+    text
+}
+
+/// 去掉语气词及其后紧跟的逗号
+///
+/// 英文语气词按整词匹配（不区分大小写），避免误删单词中的片段；中文语气词直接匹配
+fn remove_filler(text: &str, filler: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let filler: Vec<char> = filler.chars().collect();
+    let whole_word = filler.iter().all(char::is_ascii_alphanumeric);
+
+    let mut result = String::with_capacity(text.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let end = i + filler.len();
+        let matched = end <= chars.len()
+            && chars[i..end]
+                .iter()
+                .zip(&filler)
+                .all(|(a, b)| a.eq_ignore_ascii_case(b))
+            && (!whole_word || is_word_boundary(&chars, i, end));
+        if !matched {
+            result.push(chars[i]);
+            i += 1;
+            continue;
+        }
+
+        i = end;
+        if i < chars.len() && FILLER_TRAILING.contains(&chars[i]) {
+            i += 1;
+        }
+        // 去掉语气词留下的多余空格
+        if result.is_empty() || result.ends_with(char::is_whitespace) {
+            while i < chars.len() && chars[i] == ' ' {
+                i += 1;
+            }
+        }
+    }
+    result
+}
+
+/// `chars[start..end]` 前后是否都不是字母或数字
+fn is_word_boundary(chars: &[char], start: usize, end: usize) -> bool {
+    let before = start == 0 || !chars[start - 1].is_alphanumeric();
+    let after = end == chars.len() || !chars[end].is_alphanumeric();
+    before && after
+}
+
+/// 英文句首字母大写，单独的 "i" 改为 "I"
+fn capitalize_sentences(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut result = String::with_capacity(text.len());
+    let mut sentence_start = true;
+
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_whitespace() {
+            result.push(c);
+            continue;
+        }
+
+        let lone_i = c == 'i' && is_word_boundary(&chars, i, i + 1);
+        if (sentence_start || lone_i) && c.is_ascii_lowercase() {
+            result.push(c.to_ascii_uppercase());
+        } else {
+            result.push(c);
+        }
+
+        // 英文句号后需有空白才视为句末，避免 "e.g."、"3.5" 中间的点
+        sentence_start = match c {
+            '.' | '?' | '!' => chars.get(i + 1).is_none_or(|next| next.is_whitespace()),
+            '。' | '？' | '！' => true,
+            _ => false,
+        };
+    }
+    result
+}
+
+/// 句末没有标点时补全：包含中日韩文字时用 "。"，否则用 "."
+fn append_period(text: &mut String) {
+    let Some(last) = text.chars().last() else {
+        return;
+    };
+    if SENTENCE_END.contains(&last) || !last.is_alphanumeric() {
+        return;
+    }
+    text.push(if text.chars().any(is_cjk) { '。' } else { '.' });
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(
+        c,
+        '\u{3040}'..='\u{30FF}'
+            | '\u{3400}'..='\u{4DBF}'
+            | '\u{4E00}'..='\u{9FFF}'
+            | '\u{AC00}'..='\u{D7AF}'
+    )
+}
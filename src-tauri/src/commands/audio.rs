@@ -1,7 +1,9 @@
+use std::time::Duration;
 use tauri::{AppHandle, Emitter, State};
+use vhisper_core::{PipelineMetrics, PipelineState};
 
 use crate::output;
-use crate::{get_pipeline, AppState};
+use crate::{get_pipeline, process_stopped_recording, AppState};
 
 /// 开始录音
 #[tauri::command]
@@ -14,7 +16,9 @@ pub async fn start_recording(
         return Ok(());
     }
 
+    output::expire_last_output();
     if let Some(pipeline) = get_pipeline() {
+        pipeline.set_target_app(output::get_frontmost_app());
         pipeline.start_recording().map_err(|e| e.to_string())?;
         *is_recording = true;
         let _ = app.emit("recording-started", ());
@@ -39,30 +43,106 @@ pub async fn stop_recording(
     let _ = app.emit("recording-stopped", ());
 
     if let Some(pipeline) = get_pipeline() {
-        let config = state.config.read().await;
-        match pipeline.stop_and_process().await {
-            Ok(text) => {
-                // 输出文本到当前应用
-                if !text.is_empty() {
-                    if let Err(e) = output::output_text(
-                        &text,
-                        config.output.restore_clipboard,
-                        config.output.paste_delay_ms,
-                        None,
-                    ) {
-                        tracing::error!("Text output failed: {}", e);
-                    }
-                }
-                let _ = app.emit("processing-complete", ());
-                tracing::info!("Recording processed via command");
-            }
-            Err(e) => {
-                let error_msg = e.to_string();
-                let _ = app.emit("processing-error", &error_msg);
-                return Err(error_msg);
-            }
-        }
+        pipeline.set_llm_model_override(None);
     }
+    process_stopped_recording(&app, None, None)
+        .await
+        .map_err(|e| e.to_string())?;
+    tracing::info!("Recording processed via command");
 
     Ok(())
 }
+
+/// 分析最近一次录音的质量（需在设置中开启保留最近录音）
+#[tauri::command]
+pub fn analyze_last_recording(
+    waveform_points: Option<usize>,
+) -> Result<vhisper_core::AudioAnalysis, String> {
+    let pipeline = get_pipeline().ok_or("语音管道未初始化")?;
+    pipeline
+        .analyze_last_recording(waveform_points.unwrap_or(200))
+        .ok_or_else(|| "没有可分析的录音，请先开启保留最近录音并录一段音频".to_string())
+}
+
+/// 最近一次处理各阶段的耗时（尚未成功处理过时返回 None）
+#[tauri::command]
+pub fn get_last_metrics() -> Option<PipelineMetrics> {
+    get_pipeline().and_then(|pipeline| pipeline.last_metrics())
+}
+
+/// 测试麦克风：录制 `duration_ms` 毫秒并返回幅度统计
+///
+/// 使用独立的录音器，录音或识别进行中时拒绝执行
+#[tauri::command]
+pub async fn test_microphone(
+    state: State<'_, AppState>,
+    duration_ms: u64,
+) -> Result<vhisper_core::MicTestResult, String> {
+    let busy = *state.is_recording.read().await
+        || get_pipeline().is_some_and(|p| p.get_state() != PipelineState::Idle || p.is_streaming());
+    if busy {
+        return Err("正在录音或识别，请稍后再试".to_string());
+    }
+
+    let duration = Duration::from_millis(duration_ms.clamp(200, 10_000));
+    tauri::async_runtime::spawn_blocking(move || vhisper_core::test_microphone(duration))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("麦克风测试失败: {}", e))
+}
+
+/// 获取默认输入设备支持的录音格式
+#[tauri::command]
+pub async fn get_input_configs() -> Result<Vec<vhisper_core::SupportedConfig>, String> {
+    tauri::async_runtime::spawn_blocking(vhisper_core::AudioRecorder::supported_configs)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 转写单个音频（WAV、MP3 等），只做语音识别，返回识别文本
+///
+/// `path` 为文件路径；`data` 为界面拖入的文件内容，`file_name` 用于推断格式
+#[tauri::command]
+pub async fn transcribe_audio(
+    state: State<'_, AppState>,
+    path: Option<String>,
+    data: Option<Vec<u8>>,
+    file_name: Option<String>,
+) -> Result<String, String> {
+    let asr_config = state.config.read().await.asr.clone();
+
+    let result = match (path, data) {
+        (Some(path), _) => vhisper_core::transcribe_file(&asr_config, &path).await,
+        (None, Some(data)) => {
+            let extension = file_name.as_deref().and_then(|name| {
+                std::path::Path::new(name)
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+            });
+            vhisper_core::transcribe_audio_data(&asr_config, &data, extension).await
+        }
+        (None, None) => return Err("请提供音频文件路径或音频数据".to_string()),
+    };
+
+    result.map(|r| r.text).map_err(|e| e.to_string())
+}
+
+/// 调试：对音频文件（WAV、MP3 等）执行完整的识别流程（ASR + LLM），返回最终文本，不粘贴
+#[tauri::command]
+pub async fn transcribe_file(path: String) -> Result<String, String> {
+    let pipeline = get_pipeline().ok_or("语音管道未初始化")?;
+    pipeline.process_file(&path).await.map_err(|e| e.to_string())
+}
+
+/// 重新输出最近一次识别结果到当前前台应用
+#[tauri::command]
+pub async fn repeat_last_output(app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    let output_config = state.config.read().await.output.clone();
+    crate::repeat_last_output(&app, &output_config)
+}
+
+/// 取消当前录音或正在进行的识别（误触快捷键时使用）
+#[tauri::command]
+pub fn cancel_processing(app: AppHandle) -> Result<(), String> {
+    crate::cancel_processing(&app)
+}
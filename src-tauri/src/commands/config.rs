@@ -1,7 +1,7 @@
-use tauri::State;
+use tauri::{AppHandle, Emitter, State};
 
 use crate::hotkey;
-use crate::{AppConfig, AppState};
+use crate::{get_pipeline, AppConfig, AppState};
 
 /// 获取当前配置
 #[tauri::command]
@@ -12,20 +12,90 @@ pub async fn get_config(state: State<'_, AppState>) -> Result<AppConfig, String>
 
 /// 保存配置
 #[tauri::command]
-pub async fn save_config(state: State<'_, AppState>, config: AppConfig) -> Result<(), String> {
-    // 保存到文件
-    vhisper_core::save_config(&config).map_err(|e| e.to_string())?;
+pub async fn save_config(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    mut config: AppConfig,
+) -> Result<(), String> {
+    // 引导完成标记由 complete_setup 写入，设置页保存时不应被重置
+    config.setup_done |= state.config.read().await.setup_done;
 
-    // 获取新的快捷键绑定
-    let new_binding = config.hotkey.binding.clone();
+    // 快捷键不可用时拒绝保存，避免绑定到 Tab 等按键后无法正常使用
+    config.hotkey.validate()?;
+
+    // 先保存到文件，失败时不更新内存配置、不重载快捷键，避免界面与磁盘不一致
+    vhisper_core::save_config(&config).map_err(|e| format!("配置保存失败: {}", e))?;
+
+    let new_hotkey_config = config.hotkey.clone();
+
+    // 切换 LLM 服务商后立即探测，不可用时提示用户
+    super::test::spawn_llm_health_check(app, config.llm.clone());
 
     // 更新内存中的配置
     let mut current_config = state.config.write().await;
     *current_config = config;
 
     // 触发快捷键热重载
-    hotkey::reload_hotkey(new_binding);
+    hotkey::reload_hotkey(new_hotkey_config);
 
     tracing::info!("Config saved and hotkey reloaded");
     Ok(())
 }
+
+/// 从磁盘重新加载配置（手动编辑或其他工具修改配置文件后无需重启）
+#[tauri::command]
+pub async fn reload_config_from_disk(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<AppConfig, String> {
+    let mut config = vhisper_core::load_config().map_err(|e| format!("配置加载失败: {}", e))?;
+    config.hotkey.migrate();
+
+    let new_hotkey_config = config.hotkey.clone();
+
+    // 更新内存中的配置
+    *state.config.write().await = config.clone();
+
+    // 触发快捷键热重载
+    hotkey::reload_hotkey(new_hotkey_config);
+
+    let _ = app.emit("config-reloaded", &config);
+
+    tracing::info!("Config reloaded from disk");
+    Ok(config)
+}
+
+/// 临时覆盖接下来 `count` 次识别使用的服务商（不写入配置，`count` 为空时直到清除）
+#[tauri::command]
+pub async fn set_temporary_provider(
+    asr: Option<String>,
+    llm: Option<String>,
+    count: Option<u32>,
+) -> Result<(), String> {
+    let pipeline = get_pipeline().ok_or("语音管道未初始化")?;
+    pipeline.set_provider_override(asr, llm, count);
+    Ok(())
+}
+
+/// 清除临时服务商覆盖，恢复使用配置中的服务商
+#[tauri::command]
+pub async fn clear_temporary_provider() -> Result<(), String> {
+    if let Some(pipeline) = get_pipeline() {
+        pipeline.clear_provider_override();
+    }
+    Ok(())
+}
+
+/// 快捷键监听是否正常运行（用于在设置页提示监听器异常退出）
+#[tauri::command]
+pub fn get_hotkey_listener_status() -> bool {
+    hotkey::is_listener_running()
+}
+
+/// 获取当前使用的数据目录（可通过 --data-dir 启动参数或 VHISPER_DATA_DIR 环境变量指定）
+#[tauri::command]
+pub fn get_data_dir() -> Result<String, String> {
+    vhisper_core::data_dir()
+        .map(|dir| dir.to_string_lossy().into_owned())
+        .map_err(|e| e.to_string())
+}
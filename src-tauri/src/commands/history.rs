@@ -0,0 +1,31 @@
+use vhisper_core::HistoryEntry;
+
+use crate::get_pipeline;
+
+/// 获取识别历史，最新的在前
+#[tauri::command]
+pub fn get_history() -> Result<Vec<HistoryEntry>, String> {
+    let pipeline = get_pipeline().ok_or("语音管道未初始化")?;
+    Ok(pipeline.history())
+}
+
+/// 清空识别历史
+#[tauri::command]
+pub fn clear_history() -> Result<(), String> {
+    let pipeline = get_pipeline().ok_or("语音管道未初始化")?;
+    pipeline.clear_history().map_err(|e| e.to_string())
+}
+
+/// 历史记录是否已加密且尚未解锁
+#[tauri::command]
+pub fn is_history_locked() -> Result<bool, String> {
+    let pipeline = get_pipeline().ok_or("语音管道未初始化")?;
+    Ok(pipeline.is_history_locked())
+}
+
+/// 用口令解锁加密的历史记录
+#[tauri::command]
+pub fn unlock_history(passphrase: String) -> Result<(), String> {
+    let pipeline = get_pipeline().ok_or("语音管道未初始化")?;
+    pipeline.unlock_history(&passphrase).map_err(|e| e.to_string())
+}
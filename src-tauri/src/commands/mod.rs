@@ -1,4 +1,6 @@
 pub mod audio;
 pub mod config;
+pub mod history;
 pub mod permissions;
+pub mod setup;
 pub mod test;
@@ -0,0 +1,146 @@
+use serde::Serialize;
+use tauri::State;
+
+use super::test::collect_providers_status;
+use crate::permissions::{self, PermissionState};
+use crate::{AppConfig, AppState};
+
+/// 引导步骤状态
+#[derive(Debug, Clone, Serialize)]
+pub struct SetupStep {
+    /// 步骤标识：microphone / accessibility / api_key / provider
+    pub id: &'static str,
+    pub done: bool,
+    pub message: String,
+}
+
+/// 首次启动引导状态（按顺序排列的步骤）
+#[derive(Debug, Clone, Serialize)]
+pub struct SetupStatus {
+    pub steps: Vec<SetupStep>,
+    /// 是否已完成引导
+    pub setup_done: bool,
+}
+
+/// 检查麦克风权限
+#[tauri::command]
+pub fn check_microphone_permission() -> PermissionState {
+    permissions::check_permissions().microphone
+}
+
+/// 检查辅助功能权限（macOS 全局快捷键和模拟粘贴需要）
+#[tauri::command]
+pub fn check_accessibility_permission() -> bool {
+    permissions::check_permissions().accessibility
+}
+
+/// 当前 ASR 服务商是否已填写必需的配置（API Key、服务地址或模型目录）
+fn asr_credentials(config: &AppConfig) -> Result<(), String> {
+    let asr = &config.asr;
+    let provider = vhisper_core::config::resolve_asr_provider(&asr.provider)
+        .ok_or_else(|| format!("未知的 ASR 服务商: {}", asr.provider))?;
+
+    let configured = match provider {
+        "Qwen" => asr.qwen.as_ref().is_some_and(|c| !c.api_key.is_empty()),
+        "DashScope" => asr.dashscope.as_ref().is_some_and(|c| !c.api_key.is_empty()),
+        "OpenAIWhisper" => asr.openai.as_ref().is_some_and(|c| !c.api_key.is_empty()),
+        "OpenAIRealtime" => asr
+            .openai_realtime
+            .as_ref()
+            .is_some_and(|c| !c.api_key.is_empty()),
+        "FunAsr" => asr.funasr.as_ref().is_some_and(|c| !c.endpoint.is_empty()),
+        "Sherpa" => asr.sherpa.as_ref().is_some_and(|c| !c.model_dir.is_empty()),
+        _ => false,
+    };
+
+    if configured {
+        Ok(())
+    } else {
+        Err(format!("请填写 {} 的 API Key 或服务地址", provider))
+    }
+}
+
+/// 获取引导各步骤的状态：麦克风权限 → 辅助功能权限 → API Key → 服务商连通性
+#[tauri::command]
+pub async fn get_setup_status(state: State<'_, AppState>) -> Result<SetupStatus, String> {
+    let config = state.config.read().await.clone();
+    let permission_status = permissions::check_permissions();
+
+    let microphone_ok = matches!(
+        permission_status.microphone,
+        PermissionState::Granted | PermissionState::NotApplicable
+    );
+    let mut steps = vec![
+        SetupStep {
+            id: "microphone",
+            done: microphone_ok,
+            message: if microphone_ok {
+                "已授予麦克风权限".to_string()
+            } else {
+                "需要授予麦克风权限".to_string()
+            },
+        },
+        SetupStep {
+            id: "accessibility",
+            done: permission_status.accessibility,
+            message: if permission_status.accessibility {
+                "已授予辅助功能权限".to_string()
+            } else {
+                "需要授予辅助功能权限以使用全局快捷键".to_string()
+            },
+        },
+    ];
+
+    let credentials = asr_credentials(&config);
+    steps.push(SetupStep {
+        id: "api_key",
+        done: credentials.is_ok(),
+        message: credentials.clone().err().unwrap_or_else(|| "已配置".to_string()),
+    });
+
+    // 只有配置完整时才探测连通性
+    let provider_step = if credentials.is_ok() {
+        let provider = vhisper_core::config::resolve_asr_provider(&config.asr.provider);
+        collect_providers_status(&config)
+            .await
+            .into_iter()
+            .find(|s| s.kind == "asr" && Some(s.provider.as_str()) == provider)
+            .map(|s| SetupStep {
+                id: "provider",
+                done: s.reachable,
+                message: s.message,
+            })
+            .unwrap_or_else(|| SetupStep {
+                id: "provider",
+                // 不支持探测的服务商（如本地模型）视为可用
+                done: true,
+                message: "该服务商不支持连通性检测".to_string(),
+            })
+    } else {
+        SetupStep {
+            id: "provider",
+            done: false,
+            message: "请先完成服务商配置".to_string(),
+        }
+    };
+    steps.push(provider_step);
+
+    Ok(SetupStatus {
+        steps,
+        setup_done: config.setup_done,
+    })
+}
+
+/// 完成首次启动引导，写入 setup_done 标记
+#[tauri::command]
+pub async fn complete_setup(state: State<'_, AppState>) -> Result<(), String> {
+    let mut config = state.config.write().await;
+
+    let mut new_config = config.clone();
+    new_config.setup_done = true;
+    vhisper_core::save_config(&new_config).map_err(|e| format!("配置保存失败: {}", e))?;
+
+    *config = new_config;
+    tracing::info!("Setup completed");
+    Ok(())
+}
@@ -1,3 +1,15 @@
+use serde::Serialize;
+use std::future::Future;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+use tokio::task::JoinSet;
+use vhisper_core::config::settings::LlmConfig;
+
+use crate::{AppConfig, AppState};
+
+/// 启动时探测服务商的超时时间
+const PROVIDER_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// 测试通义千问 ASR API
 #[tauri::command]
 pub async fn test_qwen_api(api_key: String) -> Result<String, String> {
@@ -14,10 +26,34 @@ pub async fn test_dashscope_api(api_key: String) -> Result<String, String> {
         .map_err(|e| e.to_string())
 }
 
-/// 测试 OpenAI API
+/// 测试 OpenAI API（`base_url` 为 OpenAI 兼容服务地址，未提供时使用官方地址）
+#[tauri::command]
+pub async fn test_openai_api(api_key: String, base_url: Option<String>) -> Result<String, String> {
+    vhisper_core::test_openai_api(&api_key, base_url.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 测试 Deepgram API
+#[tauri::command]
+pub async fn test_deepgram_api(api_key: String) -> Result<String, String> {
+    vhisper_core::test_deepgram_api(&api_key)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 测试 Azure Speech 订阅密钥
 #[tauri::command]
-pub async fn test_openai_api(api_key: String) -> Result<String, String> {
-    vhisper_core::test_openai_api(&api_key)
+pub async fn test_azure_api(subscription_key: String, region: String) -> Result<String, String> {
+    vhisper_core::test_azure_api(&subscription_key, &region)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 测试 AssemblyAI API
+#[tauri::command]
+pub async fn test_assemblyai_api(api_key: String) -> Result<String, String> {
+    vhisper_core::test_assemblyai_api(&api_key)
         .await
         .map_err(|e| e.to_string())
 }
@@ -37,3 +73,245 @@ pub async fn test_ollama_api(endpoint: String, model: String) -> Result<String,
         .await
         .map_err(|e| e.to_string())
 }
+
+/// 测试 Claude API
+#[tauri::command]
+pub async fn test_claude_api(api_key: String) -> Result<String, String> {
+    vhisper_core::test_claude_api(&api_key)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 测试 Gemini API
+#[tauri::command]
+pub async fn test_gemini_api(api_key: String) -> Result<String, String> {
+    vhisper_core::test_gemini_api(&api_key)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 单个服务商的连通状态
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderStatus {
+    /// "asr" 或 "llm"
+    pub kind: &'static str,
+    pub provider: String,
+    pub reachable: bool,
+    pub message: String,
+}
+
+/// 在 JoinSet 中加入一个带超时的探测任务
+fn spawn_check<F, E>(
+    set: &mut JoinSet<ProviderStatus>,
+    kind: &'static str,
+    provider: &str,
+    fut: F,
+) where
+    F: Future<Output = Result<String, E>> + Send + 'static,
+    E: std::fmt::Display,
+{
+    let provider = provider.to_string();
+    set.spawn(async move {
+        let (reachable, message) = match tokio::time::timeout(PROVIDER_CHECK_TIMEOUT, fut).await {
+            Ok(Ok(msg)) => (true, msg),
+            Ok(Err(e)) => (false, e.to_string()),
+            Err(_) => (false, "连接超时".to_string()),
+        };
+        ProviderStatus {
+            kind,
+            provider,
+            reachable,
+            message,
+        }
+    });
+}
+
+/// 并发探测所有已配置的服务商
+pub async fn collect_providers_status(config: &AppConfig) -> Vec<ProviderStatus> {
+    let mut set = JoinSet::new();
+
+    // ASR 服务商
+    if let Some(qwen) = &config.asr.qwen {
+        let key = qwen.api_key.clone();
+        spawn_check(&mut set, "asr", "Qwen", async move {
+            vhisper_core::test_qwen_api(&key).await
+        });
+    }
+    if let Some(dashscope) = &config.asr.dashscope {
+        let key = dashscope.api_key.clone();
+        spawn_check(&mut set, "asr", "DashScope", async move {
+            vhisper_core::test_dashscope_api(&key).await
+        });
+    }
+    if let Some(openai) = &config.asr.openai {
+        let key = openai.api_key.clone();
+        let base_url = openai.base_url.clone();
+        spawn_check(&mut set, "asr", "OpenAIWhisper", async move {
+            vhisper_core::test_openai_api(&key, base_url.as_deref()).await
+        });
+    }
+    if let Some(deepgram) = &config.asr.deepgram {
+        let key = deepgram.api_key.clone();
+        spawn_check(&mut set, "asr", "Deepgram", async move {
+            vhisper_core::test_deepgram_api(&key).await
+        });
+    }
+    if let Some(azure) = &config.asr.azure {
+        let key = azure.subscription_key.clone();
+        let region = azure.region.clone();
+        spawn_check(&mut set, "asr", "Azure", async move {
+            vhisper_core::test_azure_api(&key, &region).await
+        });
+    }
+    if let Some(assemblyai) = &config.asr.assemblyai {
+        let key = assemblyai.api_key.clone();
+        spawn_check(&mut set, "asr", "AssemblyAI", async move {
+            vhisper_core::test_assemblyai_api(&key).await
+        });
+    }
+    if let Some(funasr) = &config.asr.funasr {
+        let endpoint = funasr.endpoint.clone();
+        spawn_check(&mut set, "asr", "FunAsr", async move {
+            vhisper_core::test_funasr_api(&endpoint).await
+        });
+    }
+
+    // LLM 服务商（仅在启用时探测）
+    if config.llm.enabled {
+        if let Some(dashscope) = &config.llm.dashscope {
+            let key = dashscope.api_key.clone();
+            spawn_check(&mut set, "llm", "DashScope", async move {
+                vhisper_core::test_dashscope_api(&key).await
+            });
+        }
+        if let Some(openai) = &config.llm.openai {
+            let key = openai.api_key.clone();
+            let base_url = openai.base_url.clone();
+            spawn_check(&mut set, "llm", "OpenAI", async move {
+                vhisper_core::test_openai_api(&key, base_url.as_deref()).await
+            });
+        }
+        if let Some(ollama) = &config.llm.ollama {
+            let endpoint = ollama.endpoint.clone();
+            let model = ollama.model.clone();
+            spawn_check(&mut set, "llm", "Ollama", async move {
+                vhisper_core::test_ollama_api(&endpoint, &model).await
+            });
+        }
+        if let Some(claude) = &config.llm.claude {
+            let key = claude.api_key.clone();
+            spawn_check(&mut set, "llm", "Claude", async move {
+                vhisper_core::test_claude_api(&key).await
+            });
+        }
+        if let Some(gemini) = &config.llm.gemini {
+            let key = gemini.api_key.clone();
+            spawn_check(&mut set, "llm", "Gemini", async move {
+                vhisper_core::test_gemini_api(&key).await
+            });
+        }
+    }
+
+    let mut statuses = Vec::new();
+    while let Some(result) = set.join_next().await {
+        if let Ok(status) = result {
+            statuses.push(status);
+        }
+    }
+    statuses
+}
+
+/// 当前使用的 LLM 服务商不可用（`llm-unavailable` 事件内容）
+#[derive(Debug, Clone, Serialize)]
+pub struct LlmUnavailable {
+    pub provider: String,
+    pub error: String,
+}
+
+/// 探测当前使用的 LLM 服务商
+async fn check_active_llm(config: &LlmConfig) -> Result<(), String> {
+    let missing = || format!("{} 配置缺失", config.provider);
+    let check = async {
+        match vhisper_core::config::resolve_llm_provider(&config.provider) {
+            Some("DashScope") => {
+                let dashscope = config.dashscope.as_ref().ok_or_else(missing)?;
+                vhisper_core::test_dashscope_api(&dashscope.api_key)
+                    .await
+                    .map_err(|e| e.to_string())
+            }
+            Some("OpenAI") => {
+                let openai = config.openai.as_ref().ok_or_else(missing)?;
+                vhisper_core::test_openai_api(&openai.api_key, openai.base_url.as_deref())
+                    .await
+                    .map_err(|e| e.to_string())
+            }
+            Some("Ollama") => {
+                let ollama = config.ollama.as_ref().ok_or_else(missing)?;
+                vhisper_core::test_ollama_api(&ollama.endpoint, &ollama.model)
+                    .await
+                    .map_err(|e| e.to_string())
+            }
+            Some("Claude") => {
+                let claude = config.claude.as_ref().ok_or_else(missing)?;
+                vhisper_core::test_claude_api(&claude.api_key)
+                    .await
+                    .map_err(|e| e.to_string())
+            }
+            Some("Gemini") => {
+                let gemini = config.gemini.as_ref().ok_or_else(missing)?;
+                vhisper_core::test_gemini_api(&gemini.api_key)
+                    .await
+                    .map_err(|e| e.to_string())
+            }
+            _ => Err(format!("未知的 LLM 服务商: {}", config.provider)),
+        }
+    };
+
+    match tokio::time::timeout(PROVIDER_CHECK_TIMEOUT, check).await {
+        Ok(result) => result.map(|_| ()),
+        Err(_) => Err("连接超时".to_string()),
+    }
+}
+
+/// 后台探测当前使用的 LLM 服务商，不可用时发出 `llm-unavailable` 事件
+///
+/// 不阻塞调用方；LLM 未启用时不探测。识别时 LLM 失败仍会回退到原始识别文本，
+/// 这里只是让用户提前知道服务不可用
+pub fn spawn_llm_health_check(app: AppHandle, config: LlmConfig) {
+    if !config.enabled {
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        if let Err(error) = check_active_llm(&config).await {
+            tracing::warn!("LLM provider {} unavailable: {}", config.provider, error);
+            let _ = app.emit(
+                "llm-unavailable",
+                LlmUnavailable {
+                    provider: config.provider,
+                    error,
+                },
+            );
+        }
+    });
+}
+
+/// 获取所有已配置服务商的连通状态
+#[tauri::command]
+pub async fn get_providers_status(
+    state: State<'_, AppState>,
+) -> Result<Vec<ProviderStatus>, String> {
+    let config = state.config.read().await.clone();
+    Ok(collect_providers_status(&config).await)
+}
+
+/// 用同一段音频测试所有已配置的 ASR 服务商，比较识别结果与耗时
+#[tauri::command]
+pub async fn benchmark_providers(
+    state: State<'_, AppState>,
+    audio_path: String,
+) -> Result<Vec<vhisper_core::BenchmarkResult>, String> {
+    let asr_config = state.config.read().await.asr.clone();
+    vhisper_core::benchmark_providers(&asr_config, &audio_path)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,322 @@
+//! Linux 快捷键监听
+//!
+//! 通过 evdev 读取 /dev/input 下键盘设备的按键状态（不独占设备，X11 / Wayland 均可用），
+//! 需要当前用户对输入设备有读权限（通常加入 `input` 组）
+
+use evdev::{Device, Key};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{Receiver, TryRecvError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+use vhisper_core::{HotkeyAction, HotkeyBinding, HotkeyConfig, HotkeyMode, KeyCode};
+use crate::{emit_processing_error, get_pipeline, process_stopped_recording};
+use crate::output::{self, capture_frontmost_app};
+
+/// 轮询按键状态的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// 重新扫描键盘设备的间隔（支持热插拔）
+const RESCAN_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, thiserror::Error)]
+pub enum HotkeyError {
+    #[error("Failed to start hotkey listener: {0}")]
+    Start(String),
+}
+
+/// 将 KeyCode 转换为 Linux 输入键码（修饰键左右两侧均可触发）
+fn keycode_to_keys(key: &KeyCode) -> &'static [Key] {
+    match key {
+        // 修饰键
+        KeyCode::Alt => &[Key::KEY_LEFTALT, Key::KEY_RIGHTALT],
+        KeyCode::Control => &[Key::KEY_LEFTCTRL, Key::KEY_RIGHTCTRL],
+        KeyCode::Shift => &[Key::KEY_LEFTSHIFT, Key::KEY_RIGHTSHIFT],
+        KeyCode::Meta => &[Key::KEY_LEFTMETA, Key::KEY_RIGHTMETA],
+
+        // 功能键
+        KeyCode::F1 => &[Key::KEY_F1],
+        KeyCode::F2 => &[Key::KEY_F2],
+        KeyCode::F3 => &[Key::KEY_F3],
+        KeyCode::F4 => &[Key::KEY_F4],
+        KeyCode::F5 => &[Key::KEY_F5],
+        KeyCode::F6 => &[Key::KEY_F6],
+        KeyCode::F7 => &[Key::KEY_F7],
+        KeyCode::F8 => &[Key::KEY_F8],
+        KeyCode::F9 => &[Key::KEY_F9],
+        KeyCode::F10 => &[Key::KEY_F10],
+        KeyCode::F11 => &[Key::KEY_F11],
+        KeyCode::F12 => &[Key::KEY_F12],
+
+        // 字母键
+        KeyCode::KeyA => &[Key::KEY_A],
+        KeyCode::KeyB => &[Key::KEY_B],
+        KeyCode::KeyC => &[Key::KEY_C],
+        KeyCode::KeyD => &[Key::KEY_D],
+        KeyCode::KeyE => &[Key::KEY_E],
+        KeyCode::KeyF => &[Key::KEY_F],
+        KeyCode::KeyG => &[Key::KEY_G],
+        KeyCode::KeyH => &[Key::KEY_H],
+        KeyCode::KeyI => &[Key::KEY_I],
+        KeyCode::KeyJ => &[Key::KEY_J],
+        KeyCode::KeyK => &[Key::KEY_K],
+        KeyCode::KeyL => &[Key::KEY_L],
+        KeyCode::KeyM => &[Key::KEY_M],
+        KeyCode::KeyN => &[Key::KEY_N],
+        KeyCode::KeyO => &[Key::KEY_O],
+        KeyCode::KeyP => &[Key::KEY_P],
+        KeyCode::KeyQ => &[Key::KEY_Q],
+        KeyCode::KeyR => &[Key::KEY_R],
+        KeyCode::KeyS => &[Key::KEY_S],
+        KeyCode::KeyT => &[Key::KEY_T],
+        KeyCode::KeyU => &[Key::KEY_U],
+        KeyCode::KeyV => &[Key::KEY_V],
+        KeyCode::KeyW => &[Key::KEY_W],
+        KeyCode::KeyX => &[Key::KEY_X],
+        KeyCode::KeyY => &[Key::KEY_Y],
+        KeyCode::KeyZ => &[Key::KEY_Z],
+
+        // 数字键
+        KeyCode::Digit0 => &[Key::KEY_0],
+        KeyCode::Digit1 => &[Key::KEY_1],
+        KeyCode::Digit2 => &[Key::KEY_2],
+        KeyCode::Digit3 => &[Key::KEY_3],
+        KeyCode::Digit4 => &[Key::KEY_4],
+        KeyCode::Digit5 => &[Key::KEY_5],
+        KeyCode::Digit6 => &[Key::KEY_6],
+        KeyCode::Digit7 => &[Key::KEY_7],
+        KeyCode::Digit8 => &[Key::KEY_8],
+        KeyCode::Digit9 => &[Key::KEY_9],
+
+        // 特殊键
+        KeyCode::Space => &[Key::KEY_SPACE],
+        KeyCode::Tab => &[Key::KEY_TAB],
+        KeyCode::CapsLock => &[Key::KEY_CAPSLOCK],
+        KeyCode::Escape => &[Key::KEY_ESC],
+        KeyCode::Backquote => &[Key::KEY_GRAVE],
+    }
+}
+
+/// 打开所有可读的键盘设备（以是否支持字母键和空格键判断）
+fn open_keyboards() -> Vec<Device> {
+    evdev::enumerate()
+        .map(|(_, device)| device)
+        .filter(|device| {
+            device
+                .supported_keys()
+                .is_some_and(|keys| keys.contains(Key::KEY_A) && keys.contains(Key::KEY_SPACE))
+        })
+        .collect()
+}
+
+/// 所有键盘当前按下的键（读取失败的设备视为已拔出并移除）
+fn pressed_keys(devices: &mut Vec<Device>) -> Vec<Key> {
+    let mut pressed = Vec::new();
+    devices.retain(|device| match device.get_key_state() {
+        Ok(state) => {
+            pressed.extend(state.iter());
+            true
+        }
+        Err(_) => false,
+    });
+    pressed
+}
+
+/// 检查按键是否按下
+fn is_key_down(pressed: &[Key], key: &KeyCode) -> bool {
+    keycode_to_keys(key).iter().any(|k| pressed.contains(k))
+}
+
+/// 检查修饰键是否全部按下
+fn check_modifiers(pressed: &[Key], modifiers: &[KeyCode]) -> bool {
+    modifiers.iter().all(|m| is_key_down(pressed, m))
+}
+
+/// 启动 Linux 快捷键监听
+pub fn start_listener(
+    app_handle: AppHandle,
+    config: HotkeyConfig,
+    stop_rx: Receiver<()>,
+) -> Result<(), HotkeyError> {
+    let bindings = config.all_bindings();
+    let mode = config.mode;
+    let min_press_ms = config.min_press_ms;
+
+    let mut devices = open_keyboards();
+    if devices.is_empty() {
+        return Err(HotkeyError::Start(
+            "no readable keyboard under /dev/input (add the user to the 'input' group)"
+                .to_string(),
+        ));
+    }
+    let mut last_scan = Instant::now();
+
+    // 每个快捷键独立记录按下状态
+    let mut is_key_pressed = vec![false; bindings.len()];
+    let is_recording = Arc::new(AtomicBool::new(false));
+    // 触发当前录音的快捷键索引
+    let mut active_binding = 0usize;
+    let mut original_app_pid: Option<i32> = None;
+    let mut double_tap = super::DoubleTapDetector::new(config.double_tap_window_ms);
+    let mut recording_started_at: Option<Instant> = None;
+
+    for binding in &bindings {
+        tracing::info!(
+            "Starting Linux hotkey listener for: {:?} ({} keyboard devices)",
+            binding,
+            devices.len()
+        );
+    }
+
+    loop {
+        // 检查是否收到停止信号
+        match stop_rx.try_recv() {
+            Ok(_) | Err(TryRecvError::Disconnected) => {
+                tracing::info!("Linux hotkey listener stopped");
+                break;
+            }
+            Err(TryRecvError::Empty) => {}
+        }
+
+        // 快捷键已暂停（如游戏中），保持轮询但不处理按键
+        if !super::hotkeys_enabled() {
+            thread::sleep(POLL_INTERVAL);
+            continue;
+        }
+
+        if last_scan.elapsed() >= RESCAN_INTERVAL {
+            devices = open_keyboards();
+            last_scan = Instant::now();
+        }
+
+        let pressed = pressed_keys(&mut devices);
+
+        for (index, binding) in bindings.iter().enumerate() {
+            // 组合判断：主键按下 + 所有修饰键按下
+            let hotkey_active = is_key_down(&pressed, &binding.key)
+                && check_modifiers(&pressed, &binding.modifiers);
+
+            let was_pressed = is_key_pressed[index];
+
+            if hotkey_active && !was_pressed {
+                // 快捷键激活
+                is_key_pressed[index] = true;
+
+                if binding.action == HotkeyAction::RepeatLastOutput {
+                    super::spawn_repeat_last_output(&app_handle, binding);
+                    continue;
+                }
+
+                if binding.action == HotkeyAction::Cancel {
+                    // 丢弃本次录音，之后按下录音快捷键重新开始
+                    is_recording.store(false, Ordering::SeqCst);
+                    original_app_pid = None;
+                    super::cancel_processing(&app_handle, binding);
+                    continue;
+                }
+
+                // 双击模式：只有窗口内的第二次按下才开始或结束录音
+                if mode == HotkeyMode::DoubleTap && !double_tap.register(index) {
+                    continue;
+                }
+
+                // 自动结束模式：录音已因静音结束时，本次按下开始新的录音
+                if is_recording.load(Ordering::SeqCst)
+                    && super::recording_auto_stopped(mode, recording_started_at)
+                {
+                    is_recording.store(false, Ordering::SeqCst);
+                    original_app_pid = None;
+                }
+
+                // 切换模式：再次按下（双击模式为再次双击）触发录音的快捷键时结束录音
+                if matches!(
+                    mode,
+                    HotkeyMode::Toggle | HotkeyMode::DoubleTap | HotkeyMode::AutoStop
+                ) && is_recording.load(Ordering::SeqCst)
+                    && active_binding == index
+                {
+                    is_recording.store(false, Ordering::SeqCst);
+                    tracing::info!("Hotkey pressed again - stopping recording");
+                    stop_recording(&app_handle, original_app_pid.take(), binding.clone());
+                    continue;
+                }
+
+                if !is_recording.load(Ordering::SeqCst)
+                    && !super::is_frontmost_app_excluded(&app_handle)
+                {
+                    is_recording.store(true, Ordering::SeqCst);
+                    active_binding = index;
+                    recording_started_at = Some(Instant::now());
+
+                    // 记录当前活跃窗口所属进程的 PID
+                    original_app_pid = capture_frontmost_app();
+                    tracing::info!(
+                        "Hotkey {} pressed - starting recording (app pid: {:?})",
+                        binding.display_text(),
+                        original_app_pid
+                    );
+                    start_recording(&app_handle);
+                }
+            } else if !hotkey_active && was_pressed {
+                // 快捷键释放 (主键释放或任一修饰键释放)
+                is_key_pressed[index] = false;
+
+                // 按住说话模式下，只有触发录音的快捷键释放时才停止
+                if mode == HotkeyMode::PushToTalk
+                    && is_recording.load(Ordering::SeqCst)
+                    && active_binding == index
+                {
+                    is_recording.store(false, Ordering::SeqCst);
+                    let started_at = recording_started_at.take();
+                    if started_at.is_some_and(|at| super::is_accidental_press(at, min_press_ms)) {
+                        original_app_pid = None;
+                        super::discard_recording(&app_handle);
+                        continue;
+                    }
+                    tracing::info!("Hotkey released - stopping recording");
+
+                    // 处理在共享 runtime 上异步执行，不会阻塞轮询
+                    stop_recording(&app_handle, original_app_pid.take(), binding.clone());
+                }
+            }
+        }
+
+        // 短暂休眠以减少 CPU 使用
+        thread::sleep(POLL_INTERVAL);
+    }
+
+    Ok(())
+}
+
+fn start_recording(app_handle: &AppHandle) {
+    let _ = app_handle.emit("recording-started", ());
+
+    output::expire_last_output();
+    if let Some(pipeline) = get_pipeline() {
+        pipeline.set_target_app(output::get_frontmost_app());
+        if let Err(e) = pipeline.start_recording() {
+            tracing::error!("Failed to start recording: {}", e);
+            emit_processing_error(app_handle, &e);
+        }
+    }
+}
+
+fn stop_recording(
+    app_handle: &AppHandle,
+    original_app_pid: Option<i32>,
+    binding: HotkeyBinding,
+) {
+    let _ = app_handle.emit("recording-stopped", ());
+
+    if let Some(pipeline) = get_pipeline() {
+        pipeline.set_llm_model_override(binding.model_override.clone());
+
+        // 在 tauri 共享 async runtime 上 spawn，取消通过 pipeline.cancel() 完成
+        let app_handle = app_handle.clone();
+        tauri::async_runtime::spawn(async move {
+            let _ = process_stopped_recording(&app_handle, Some(&binding), original_app_pid).await;
+        });
+    }
+}
@@ -3,15 +3,15 @@ use core_graphics::event::{
     CGEventType,
 };
 use core_foundation::runloop::{kCFRunLoopCommonModes, kCFRunLoopDefaultMode, CFRunLoop};
-use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicI32, AtomicUsize, Ordering};
 use std::sync::mpsc::{Receiver, TryRecvError};
-use std::sync::Arc;
-use tauri::{AppHandle, Emitter, Manager};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tauri::{AppHandle, Emitter};
 
-use vhisper_core::{HotkeyBinding, KeyCode};
-use crate::get_pipeline;
-use crate::output::{self, get_frontmost_app_pid};
-use crate::AppState;
+use vhisper_core::{HotkeyAction, HotkeyBinding, HotkeyConfig, HotkeyMode, KeyCode};
+use crate::{emit_processing_error, get_pipeline, process_stopped_recording};
+use crate::output::{self, capture_frontmost_app};
 
 #[derive(Debug, thiserror::Error)]
 pub enum HotkeyError {
@@ -113,131 +113,184 @@ fn check_modifiers(flags: CGEventFlags, required: &[KeyCode]) -> bool {
     true
 }
 
-/// 启动 macOS 快捷键监听
-pub fn start_listener(
-    app_handle: AppHandle,
+/// 单个快捷键的监听状态
+struct BindingState {
     binding: HotkeyBinding,
-    stop_rx: Receiver<()>,
-) -> Result<(), HotkeyError> {
-    let is_key_pressed = Arc::new(AtomicBool::new(false));
-    let is_recording = Arc::new(AtomicBool::new(false));
-    let original_app_pid = Arc::new(AtomicI32::new(-1));
-
-    // 判断主键类型
-    let is_modifier_key = binding.key.is_modifier();
-    let binding_clone = binding.clone();
-
-    let is_key_pressed_clone = is_key_pressed.clone();
-    let is_recording_clone = is_recording.clone();
-    let original_app_pid_clone = original_app_pid.clone();
-
-    // 获取主键的 flag (如果是修饰键)
-    let main_key_flag = keycode_to_cg_flag(&binding.key);
-    // 获取主键的 keycode (如果是普通键)
-    let main_key_code = keycode_to_cg_keycode(&binding.key);
-
-    tracing::info!(
-        "Starting hotkey listener for: {:?} (modifier: {}, keycode: {:?}, flag: {:?})",
-        binding,
-        is_modifier_key,
-        main_key_code,
-        main_key_flag
-    );
+    /// 主键是否为修饰键
+    is_modifier_key: bool,
+    /// 主键的 flag (如果是修饰键)
+    main_key_flag: Option<CGEventFlags>,
+    /// 主键的 keycode (如果是普通键)
+    main_key_code: Option<u16>,
+    is_pressed: AtomicBool,
+}
 
-    let callback = move |_proxy, event_type, event: &core_graphics::event::CGEvent| {
+impl BindingState {
+    fn new(binding: HotkeyBinding) -> Self {
+        Self {
+            is_modifier_key: binding.key.is_modifier(),
+            main_key_flag: keycode_to_cg_flag(&binding.key),
+            main_key_code: keycode_to_cg_keycode(&binding.key),
+            binding,
+            is_pressed: AtomicBool::new(false),
+        }
+    }
+
+    /// 是否需要订阅 KeyDown/KeyUp（普通键作为主键，CapsLock 除外）
+    fn needs_key_events(&self) -> bool {
+        !self.is_modifier_key && !matches!(self.binding.key, KeyCode::CapsLock)
+    }
+
+    /// 根据事件计算此快捷键的按下状态，None 表示事件与此快捷键无关
+    fn key_state(
+        &self,
+        event_type: CGEventType,
+        event: &core_graphics::event::CGEvent,
+    ) -> Option<bool> {
         let flags = event.get_flags();
 
         match event_type {
             CGEventType::FlagsChanged => {
                 // 根据主键类型检测按键状态
-                let key_pressed = if is_modifier_key {
+                if self.is_modifier_key {
                     // 修饰键作为主键
-                    if let Some(flag) = main_key_flag {
-                        flags.contains(flag) && check_modifiers(flags, &binding_clone.modifiers)
-                    } else {
-                        false
-                    }
-                } else if matches!(binding_clone.key, KeyCode::CapsLock) {
+                    let flag = self.main_key_flag?;
+                    Some(flags.contains(flag) && check_modifiers(flags, &self.binding.modifiers))
+                } else if matches!(self.binding.key, KeyCode::CapsLock) {
                     // CapsLock 特殊处理
-                    flags.contains(CGEventFlags::CGEventFlagAlphaShift)
-                        && check_modifiers(flags, &binding_clone.modifiers)
+                    Some(
+                        flags.contains(CGEventFlags::CGEventFlagAlphaShift)
+                            && check_modifiers(flags, &self.binding.modifiers),
+                    )
                 } else {
                     // 其他键不通过 FlagsChanged 处理
-                    return None;
-                };
-
-                handle_key_state_change(
-                    key_pressed,
-                    &is_key_pressed_clone,
-                    &is_recording_clone,
-                    &original_app_pid_clone,
-                    &app_handle,
-                );
+                    None
+                }
             }
 
             CGEventType::KeyDown => {
-                if is_modifier_key {
+                if self.is_modifier_key {
                     return None;
                 }
                 // 普通键作为主键：检查按下
                 // CGEventField 9 = kCGKeyboardEventKeycode
                 let key_code = event.get_integer_value_field(9) as u16;
-
-                if let Some(expected_keycode) = main_key_code {
-                    if key_code == expected_keycode
-                        && check_modifiers(flags, &binding_clone.modifiers)
-                    {
-                        handle_key_state_change(
-                            true,
-                            &is_key_pressed_clone,
-                            &is_recording_clone,
-                            &original_app_pid_clone,
-                            &app_handle,
-                        );
-                    }
+                let expected_keycode = self.main_key_code?;
+                if key_code == expected_keycode
+                    && check_modifiers(flags, &self.binding.modifiers)
+                {
+                    Some(true)
+                } else {
+                    None
                 }
             }
 
             CGEventType::KeyUp => {
-                if is_modifier_key {
+                if self.is_modifier_key {
                     return None;
                 }
                 // 普通键作为主键：检查释放
                 // CGEventField 9 = kCGKeyboardEventKeycode
                 let key_code = event.get_integer_value_field(9) as u16;
-
-                if let Some(expected_keycode) = main_key_code {
-                    if key_code == expected_keycode {
-                        handle_key_state_change(
-                            false,
-                            &is_key_pressed_clone,
-                            &is_recording_clone,
-                            &original_app_pid_clone,
-                            &app_handle,
-                        );
-                    }
+                let expected_keycode = self.main_key_code?;
+                if key_code == expected_keycode {
+                    Some(false)
+                } else {
+                    None
                 }
             }
 
-            _ => {}
+            _ => None,
+        }
+    }
+}
+
+/// 所有快捷键共享的录音状态
+struct ListenerState {
+    bindings: Vec<BindingState>,
+    mode: HotkeyMode,
+    is_recording: AtomicBool,
+    /// 触发当前录音的快捷键索引
+    active_binding: AtomicUsize,
+    original_app_pid: AtomicI32,
+    /// 双击模式下的按下时间记录
+    double_tap: Mutex<super::DoubleTapDetector>,
+    /// 按住说话的最短按键时长（毫秒）
+    min_press_ms: u64,
+    /// 当前录音的开始时间
+    recording_started_at: Mutex<Option<Instant>>,
+}
+
+/// 开始录音在单独线程中执行；误触丢弃时先等开始完成，避免取消早于开始而留下未结束的录音
+static RECORDING_START: Mutex<()> = Mutex::new(());
+
+/// 启动 macOS 快捷键监听
+pub fn start_listener(
+    app_handle: AppHandle,
+    config: HotkeyConfig,
+    stop_rx: Receiver<()>,
+) -> Result<(), HotkeyError> {
+    let listener_state = Arc::new(ListenerState {
+        bindings: config.all_bindings().into_iter().map(BindingState::new).collect(),
+        mode: config.mode,
+        is_recording: AtomicBool::new(false),
+        active_binding: AtomicUsize::new(0),
+        original_app_pid: AtomicI32::new(-1),
+        double_tap: Mutex::new(super::DoubleTapDetector::new(config.double_tap_window_ms)),
+        min_press_ms: config.min_press_ms,
+        recording_started_at: Mutex::new(None),
+    });
+
+    for state in &listener_state.bindings {
+        tracing::info!(
+            "Starting hotkey listener for: {:?} (modifier: {}, keycode: {:?}, flag: {:?})",
+            state.binding,
+            state.is_modifier_key,
+            state.main_key_code,
+            state.main_key_flag
+        );
+    }
+
+    let listener_state_clone = listener_state.clone();
+
+    // 系统在回调超时或用户输入时会禁用事件监听，回调中无法访问 tap，交给运行循环重新启用
+    let tap_disabled = Arc::new(AtomicBool::new(false));
+    let tap_disabled_clone = tap_disabled.clone();
+
+    let callback = move |_proxy, event_type, event: &core_graphics::event::CGEvent| {
+        if matches!(
+            event_type,
+            CGEventType::TapDisabledByTimeout | CGEventType::TapDisabledByUserInput
+        ) {
+            tracing::warn!("Event tap disabled by system ({:?})", event_type);
+            tap_disabled_clone.store(true, Ordering::SeqCst);
+            return None;
+        }
+
+        // 快捷键已暂停（如游戏中），事件监听保持运行但不处理按键
+        if !super::hotkeys_enabled() {
+            return None;
+        }
+
+        for (index, state) in listener_state_clone.bindings.iter().enumerate() {
+            if let Some(key_pressed) = state.key_state(event_type, event) {
+                handle_key_state_change(index, key_pressed, &listener_state_clone, &app_handle);
+            }
         }
 
         // 返回 None 表示不拦截事件
         None
     };
 
-    // 订阅的事件类型取决于主键类型
-    let event_types = if is_modifier_key {
-        vec![CGEventType::FlagsChanged]
-    } else if matches!(binding.key, KeyCode::CapsLock) {
-        // CapsLock 通过 FlagsChanged 检测
-        vec![CGEventType::FlagsChanged]
-    } else {
+    // 订阅的事件类型取决于主键类型（CapsLock 通过 FlagsChanged 检测）
+    let event_types = if listener_state.bindings.iter().any(BindingState::needs_key_events) {
         vec![
             CGEventType::FlagsChanged,
             CGEventType::KeyDown,
             CGEventType::KeyUp,
         ]
+    } else {
+        vec![CGEventType::FlagsChanged]
     };
 
     tracing::info!("Subscribing to event types: {:?}", event_types);
@@ -280,6 +333,12 @@ pub fn start_listener(
             Err(TryRecvError::Empty) => {}
         }
 
+        // 事件监听被系统禁用后重新启用，否则快捷键会一直失效直到重启
+        if tap_disabled.swap(false, Ordering::SeqCst) {
+            tracing::warn!("Re-enabling event tap");
+            tap.enable();
+        }
+
         // 运行事件循环 100ms，然后检查停止信号
         unsafe {
             core_foundation::runloop::CFRunLoopRunInMode(
@@ -296,25 +355,81 @@ pub fn start_listener(
 }
 
 fn handle_key_state_change(
+    index: usize,
     key_pressed: bool,
-    is_key_pressed: &AtomicBool,
-    is_recording: &AtomicBool,
-    original_app_pid: &AtomicI32,
+    listener_state: &ListenerState,
     app_handle: &AppHandle,
 ) {
-    let was_pressed = is_key_pressed.load(Ordering::SeqCst);
+    let binding_state = &listener_state.bindings[index];
+    let is_recording = &listener_state.is_recording;
+    let was_pressed = binding_state.is_pressed.load(Ordering::SeqCst);
 
     if key_pressed && !was_pressed {
         // 按键按下
-        is_key_pressed.store(true, Ordering::SeqCst);
+        binding_state.is_pressed.store(true, Ordering::SeqCst);
+
+        if binding_state.binding.action == HotkeyAction::RepeatLastOutput {
+            super::spawn_repeat_last_output(app_handle, &binding_state.binding);
+            return;
+        }
 
-        if !is_recording.load(Ordering::SeqCst) {
+        if binding_state.binding.action == HotkeyAction::Cancel {
+            // 丢弃本次录音，之后按下录音快捷键重新开始
+            is_recording.store(false, Ordering::SeqCst);
+            super::cancel_processing(app_handle, &binding_state.binding);
+            return;
+        }
+
+        // 双击模式：单击保留按键原有用途，只有窗口内的第二次按下才开始或结束录音
+        if listener_state.mode == HotkeyMode::DoubleTap {
+            let mut double_tap = listener_state
+                .double_tap
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            if !double_tap.register(index) {
+                return;
+            }
+        }
+
+        // 自动结束模式：录音已因静音结束时，本次按下开始新的录音
+        if is_recording.load(Ordering::SeqCst) {
+            let started_at = *listener_state
+                .recording_started_at
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            if super::recording_auto_stopped(listener_state.mode, started_at) {
+                is_recording.store(false, Ordering::SeqCst);
+            }
+        }
+
+        // 切换模式：再次按下（双击模式为再次双击）触发录音的快捷键时结束录音
+        if matches!(
+            listener_state.mode,
+            HotkeyMode::Toggle | HotkeyMode::DoubleTap | HotkeyMode::AutoStop
+        ) && is_recording.load(Ordering::SeqCst)
+            && listener_state.active_binding.load(Ordering::SeqCst) == index
+        {
+            tracing::info!("Hotkey pressed again - stopping recording");
+            finish_recording(binding_state, listener_state, app_handle);
+            return;
+        }
+
+        if !is_recording.load(Ordering::SeqCst) && !super::is_frontmost_app_excluded(app_handle) {
             is_recording.store(true, Ordering::SeqCst);
+            listener_state.active_binding.store(index, Ordering::SeqCst);
+            *listener_state
+                .recording_started_at
+                .lock()
+                .unwrap_or_else(|e| e.into_inner()) = Some(Instant::now());
 
             // 记录当前活跃应用的 PID
-            let pid = get_frontmost_app_pid().unwrap_or(-1);
-            original_app_pid.store(pid, Ordering::SeqCst);
-            tracing::info!("Hotkey pressed - starting recording (app pid: {})", pid);
+            let pid = capture_frontmost_app().unwrap_or(-1);
+            listener_state.original_app_pid.store(pid, Ordering::SeqCst);
+            tracing::info!(
+                "Hotkey {} pressed - starting recording (app pid: {})",
+                binding_state.binding.display_text(),
+                pid
+            );
 
             let app_handle = app_handle.clone();
             std::thread::spawn(move || {
@@ -323,83 +438,82 @@ fn handle_key_state_change(
         }
     } else if !key_pressed && was_pressed {
         // 按键释放
-        is_key_pressed.store(false, Ordering::SeqCst);
+        binding_state.is_pressed.store(false, Ordering::SeqCst);
+
+        // 按住说话模式下，只有触发录音的快捷键释放时才停止
+        if listener_state.mode == HotkeyMode::PushToTalk
+            && is_recording.load(Ordering::SeqCst)
+            && listener_state.active_binding.load(Ordering::SeqCst) == index
+        {
+            let started_at = listener_state
+                .recording_started_at
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .take();
+            let min_press_ms = listener_state.min_press_ms;
+            if started_at.is_some_and(|at| super::is_accidental_press(at, min_press_ms)) {
+                is_recording.store(false, Ordering::SeqCst);
+                let app_handle = app_handle.clone();
+                std::thread::spawn(move || {
+                    let _start = RECORDING_START.lock().unwrap_or_else(|e| e.into_inner());
+                    super::discard_recording(&app_handle);
+                });
+                return;
+            }
 
-        if is_recording.load(Ordering::SeqCst) {
-            is_recording.store(false, Ordering::SeqCst);
-            let pid = original_app_pid.load(Ordering::SeqCst);
             tracing::info!("Hotkey released - stopping recording");
-
-            let app_handle = app_handle.clone();
-            std::thread::spawn(move || {
-                stop_recording(&app_handle, if pid >= 0 { Some(pid) } else { None });
-            });
+            finish_recording(binding_state, listener_state, app_handle);
         }
     }
 }
 
+/// 结束当前录音，输出到开始录音时记录的应用
+fn finish_recording(
+    binding_state: &BindingState,
+    listener_state: &ListenerState,
+    app_handle: &AppHandle,
+) {
+    listener_state.is_recording.store(false, Ordering::SeqCst);
+    let pid = listener_state.original_app_pid.load(Ordering::SeqCst);
+
+    let app_handle = app_handle.clone();
+    let binding = binding_state.binding.clone();
+    std::thread::spawn(move || {
+        stop_recording(&app_handle, if pid >= 0 { Some(pid) } else { None }, binding);
+    });
+}
+
 fn start_recording(app_handle: &AppHandle) {
+    let _start = RECORDING_START.lock().unwrap_or_else(|e| e.into_inner());
+
     // 发送事件到前端
     let _ = app_handle.emit("recording-started", ());
 
     // 获取 pipeline 并开始录音
+    output::expire_last_output();
     if let Some(pipeline) = get_pipeline() {
+        pipeline.set_target_app(output::get_frontmost_app());
         if let Err(e) = pipeline.start_recording() {
             tracing::error!("Failed to start recording: {}", e);
-            let _ = app_handle.emit("processing-error", e.to_string());
+            emit_processing_error(app_handle, &e);
         }
     }
 }
 
-fn stop_recording(app_handle: &AppHandle, original_app_pid: Option<i32>) {
-    tracing::info!("stop_recording called");
-
-    // 发送事件到前端
+fn stop_recording(
+    app_handle: &AppHandle,
+    original_app_pid: Option<i32>,
+    binding: HotkeyBinding,
+) {
     let _ = app_handle.emit("recording-stopped", ());
 
-    // 获取 pipeline 并停止录音、处理
     if let Some(pipeline) = get_pipeline() {
-        let app_handle_clone = app_handle.clone();
-
-        // 获取配置
-        let state = app_handle.state::<AppState>();
-        let config = state.config.clone();
-
-        // 获取 tauri async runtime 的 handle，然后在其上 spawn 任务
-        tracing::info!("Spawning async task for stop_and_process");
-        let handle = tauri::async_runtime::handle();
-        handle.spawn(async move {
-            tracing::info!("Async task started");
-            match pipeline.stop_and_process().await {
-                Ok(text) => {
-                    tracing::info!("Processing completed successfully, text: {}", text);
-
-                    // 输出文本到当前应用
-                    if !text.is_empty() {
-                        let cfg = config.read().await;
-                        if let Err(e) = output::output_text(
-                            &text,
-                            cfg.output.restore_clipboard,
-                            cfg.output.paste_delay_ms,
-                            original_app_pid,
-                        ) {
-                            tracing::error!("Text output failed: {}", e);
-                        }
-                    }
-
-                    let _ = app_handle_clone.emit("processing-complete", ());
-                }
-                Err(e) => {
-                    tracing::error!("Processing error: {}", e);
-                    let _ = app_handle_clone.emit("processing-error", e.to_string());
-                }
-            }
-            tracing::info!("Async task finished");
+        pipeline.set_llm_model_override(binding.model_override.clone());
+
+        // 在 tauri 共享 async runtime 上 spawn，取消通过 pipeline.cancel() 完成
+        let app_handle = app_handle.clone();
+        tauri::async_runtime::spawn(async move {
+            let _ = process_stopped_recording(&app_handle, Some(&binding), original_app_pid).await;
         });
-        tracing::info!("Async task spawned");
-    } else {
-        tracing::warn!("Pipeline not available");
     }
-
-    tracing::info!("stop_recording finished");
 }
@@ -4,11 +4,16 @@ mod macos;
 #[cfg(target_os = "windows")]
 mod windows;
 
+#[cfg(target_os = "linux")]
+mod linux;
+
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::OnceLock;
-use tauri::AppHandle;
+use tauri::{AppHandle, Emitter};
 use tokio::sync::mpsc;
 
-use vhisper_core::HotkeyBinding;
+use vhisper_core::HotkeyConfig;
 
 #[derive(Debug, thiserror::Error)]
 pub enum HotkeyError {
@@ -17,81 +22,287 @@ pub enum HotkeyError {
 }
 
 /// 全局的配置更新发送器
-static CONFIG_SENDER: OnceLock<mpsc::UnboundedSender<HotkeyBinding>> = OnceLock::new();
+static CONFIG_SENDER: OnceLock<mpsc::UnboundedSender<HotkeyConfig>> = OnceLock::new();
+
+/// 平台监听线程是否在运行
+static LISTENER_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// 是否响应快捷键（`HotkeyConfig.enabled`），关闭时监听器保持运行但忽略所有按键
+static HOTKEYS_ENABLED: AtomicBool = AtomicBool::new(true);
 
 /// 请求重新加载快捷键配置
-pub fn reload_hotkey(binding: HotkeyBinding) {
+///
+/// 短时间内多次请求会被合并，只应用最新的一次
+pub fn reload_hotkey(config: HotkeyConfig) {
     if let Some(sender) = CONFIG_SENDER.get() {
-        tracing::info!("Hotkey reload requested: {:?}", binding);
-        let _ = sender.send(binding);
+        tracing::info!("Hotkey reload requested: {:?}", config.all_bindings());
+        let _ = sender.send(config);
+    }
+}
+
+/// 快捷键监听线程是否正常运行（监听器启动失败或意外退出时为 false）
+pub fn is_listener_running() -> bool {
+    LISTENER_RUNNING.load(Ordering::SeqCst)
+}
+
+/// 启用或暂停快捷键，立即生效，不重启监听器
+pub fn set_hotkeys_enabled(enabled: bool) {
+    tracing::info!("Hotkeys {}", if enabled { "enabled" } else { "disabled" });
+    HOTKEYS_ENABLED.store(enabled, Ordering::SeqCst);
+}
+
+/// 是否响应快捷键
+#[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
+fn hotkeys_enabled() -> bool {
+    HOTKEYS_ENABLED.load(Ordering::SeqCst)
+}
+
+/// 前台应用是否在快捷键排除列表中（如与快捷键冲突的游戏、OBS）
+///
+/// 在监听线程中调用，配置正被写入时不阻塞，视为未排除
+#[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
+fn is_frontmost_app_excluded(app_handle: &AppHandle) -> bool {
+    use crate::{output, AppState};
+    use tauri::Manager;
+
+    let state = app_handle.state::<AppState>();
+    let Ok(config) = state.config.try_read() else {
+        return false;
+    };
+    if config.hotkey.excluded_apps.is_empty() {
+        return false;
+    }
+
+    let Some(app) = output::get_frontmost_app() else {
+        return false;
+    };
+    let excluded = config
+        .hotkey
+        .is_excluded(app.id.as_deref(), app.name.as_deref());
+    if excluded {
+        tracing::info!("Hotkey ignored, frontmost app is excluded: {:?}", app);
+    }
+    excluded
+}
+
+/// 在后台重新输出最近一次识别结果（`RepeatLastOutput` 快捷键按下时调用）
+#[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
+fn spawn_repeat_last_output(app_handle: &AppHandle, binding: &vhisper_core::HotkeyBinding) {
+    use crate::AppState;
+    use tauri::Manager;
+
+    tracing::info!("Hotkey {} pressed - repeating last output", binding.display_text());
+    let app_handle = app_handle.clone();
+    let binding = binding.clone();
+    tauri::async_runtime::spawn(async move {
+        let output_config = {
+            let state = app_handle.state::<AppState>();
+            let config = state.config.read().await;
+            binding.output_config(&config.output)
+        };
+        if let Err(e) = crate::repeat_last_output(&app_handle, &output_config) {
+            tracing::warn!("Repeat last output failed: {}", e);
+            crate::emit_output_error(&app_handle, &e);
+        }
+    });
+}
+
+/// 取消当前录音或识别（`Cancel` 快捷键按下时调用）
+#[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
+fn cancel_processing(app_handle: &AppHandle, binding: &vhisper_core::HotkeyBinding) {
+    tracing::info!("Hotkey {} pressed - cancelling", binding.display_text());
+    if let Err(e) = crate::cancel_processing(app_handle) {
+        tracing::warn!("Cancel failed: {}", e);
+    }
+}
+
+/// 按住说话时按键时长是否短于 `min_press_ms`（误触，应丢弃本次录音）
+///
+/// 正常说出一个短词也需要按住远超默认阈值的时间，阈值只过滤快速误触
+#[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
+fn is_accidental_press(pressed_at: std::time::Instant, min_press_ms: u64) -> bool {
+    let held = pressed_at.elapsed();
+    let accidental = held < std::time::Duration::from_millis(min_press_ms);
+    if accidental {
+        tracing::info!(
+            "Hotkey released after {:?} (min {}ms) - discarding recording",
+            held,
+            min_press_ms
+        );
+    }
+    accidental
+}
+
+/// 开始录音后多久才检查录音是否已自动结束（录音刚按下时可能还没真正开始）
+#[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
+const AUTO_STOP_SETTLE: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// 自动结束模式下录音是否已因静音自动停止（快捷键状态仍为录音中）
+///
+/// 此时再次按下应开始新的录音，而不是结束一段已经结束的录音
+#[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
+fn recording_auto_stopped(
+    mode: vhisper_core::HotkeyMode,
+    started_at: Option<std::time::Instant>,
+) -> bool {
+    mode == vhisper_core::HotkeyMode::AutoStop
+        && started_at.is_some_and(|at| at.elapsed() >= AUTO_STOP_SETTLE)
+        && crate::get_pipeline().is_some_and(|pipeline| !pipeline.is_recording())
+}
+
+/// 丢弃误触产生的录音：不识别，只通知前端复位状态（`processing-cancelled`），不提示错误
+#[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
+fn discard_recording(app_handle: &AppHandle) {
+    if let Err(e) = crate::cancel_processing(app_handle) {
+        tracing::warn!("Discard recording failed: {}", e);
+    }
+}
+
+/// 双击检测：记录上一次按下的快捷键和时间
+#[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
+struct DoubleTapDetector {
+    window: std::time::Duration,
+    last_tap: Option<(usize, std::time::Instant)>,
+}
+
+#[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
+impl DoubleTapDetector {
+    fn new(window_ms: u64) -> Self {
+        Self {
+            window: std::time::Duration::from_millis(window_ms),
+            last_tap: None,
+        }
+    }
+
+    /// 记录一次按下，返回是否与上一次按下构成双击
+    ///
+    /// 构成双击后清空记录，第三次按下重新计时
+    fn register(&mut self, index: usize) -> bool {
+        let now = std::time::Instant::now();
+        let is_double = matches!(
+            self.last_tap,
+            Some((last, at)) if last == index && now.duration_since(at) <= self.window
+        );
+        self.last_tap = if is_double { None } else { Some((index, now)) };
+        is_double
+    }
+}
+
+/// 取出 channel 中排队的所有配置，只保留最新的一个
+fn latest_config(
+    rx: &mut mpsc::UnboundedReceiver<HotkeyConfig>,
+    mut config: HotkeyConfig,
+) -> HotkeyConfig {
+    let mut skipped = 0;
+    while let Ok(newer) = rx.try_recv() {
+        config = newer;
+        skipped += 1;
+    }
+    if skipped > 0 {
+        tracing::info!("Coalesced {} pending hotkey reloads", skipped);
+    }
+    config
+}
+
+/// 运行当前平台的监听器，直到收到停止信号
+fn run_platform_listener(
+    app_handle: AppHandle,
+    config: HotkeyConfig,
+    stop_rx: std::sync::mpsc::Receiver<()>,
+) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        macos::start_listener(app_handle, config, stop_rx).map_err(|e| e.to_string())
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        windows::start_listener(app_handle, config, stop_rx).map_err(|e| e.to_string())
     }
+
+    #[cfg(target_os = "linux")]
+    {
+        linux::start_listener(app_handle, config, stop_rx).map_err(|e| e.to_string())
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+    {
+        let _ = (app_handle, config, stop_rx);
+        Err("Unsupported platform".to_string())
+    }
+}
+
+/// 在单独线程中运行监听器
+///
+/// 监听器出错或 panic 退出时记录状态并通知前端（`hotkey-listener-error` 事件）
+fn spawn_listener(
+    app_handle: AppHandle,
+    config: HotkeyConfig,
+    stop_rx: std::sync::mpsc::Receiver<()>,
+) -> std::thread::JoinHandle<()> {
+    std::thread::spawn(move || {
+        LISTENER_RUNNING.store(true, Ordering::SeqCst);
+
+        let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+            run_platform_listener(app_handle.clone(), config, stop_rx)
+        }));
+
+        LISTENER_RUNNING.store(false, Ordering::SeqCst);
+
+        let error = match result {
+            Ok(Ok(())) => return,
+            Ok(Err(e)) => e,
+            Err(_) => "listener thread panicked".to_string(),
+        };
+        tracing::error!("Hotkey listener died: {}", error);
+        let _ = app_handle.emit("hotkey-listener-error", format!("快捷键监听已停止: {}", error));
+    })
 }
 
 /// 启动快捷键监听（带热重载支持）
-pub fn start_listener(app_handle: AppHandle, initial_binding: HotkeyBinding) -> Result<(), HotkeyError> {
+///
+/// 监听当前平台生效的主快捷键和所有额外快捷键
+pub fn start_listener(
+    app_handle: AppHandle,
+    initial_config: HotkeyConfig,
+) -> Result<(), HotkeyError> {
+    if cfg!(not(any(target_os = "macos", target_os = "windows", target_os = "linux"))) {
+        return Err(HotkeyError::Error("Unsupported platform".to_string()));
+    }
+
     // 创建配置更新 channel
-    let (tx, mut rx) = mpsc::unbounded_channel::<HotkeyBinding>();
+    let (tx, mut rx) = mpsc::unbounded_channel::<HotkeyConfig>();
     let _ = CONFIG_SENDER.set(tx);
 
-    let mut current_binding = initial_binding;
+    let mut current_config = initial_config;
 
     loop {
-        tracing::info!("Starting hotkey listener with binding: {:?}", current_binding);
-
-        #[cfg(target_os = "macos")]
-        {
-            // macOS: 启动监听器，它会在收到停止信号时返回
-            let binding_clone = current_binding.clone();
-            let app_handle_clone = app_handle.clone();
-
-            // 在单独线程中运行监听器
-            let (stop_tx, stop_rx) = std::sync::mpsc::channel::<()>();
-
-            let listener_handle = std::thread::spawn(move || {
-                macos::start_listener(app_handle_clone, binding_clone, stop_rx)
-            });
-
-            // 等待新配置
-            if let Some(new_binding) = rx.blocking_recv() {
-                tracing::info!("Received new hotkey binding: {:?}", new_binding);
-                current_binding = new_binding;
-                // 发送停止信号
-                let _ = stop_tx.send(());
-                // 等待监听器线程结束
-                let _ = listener_handle.join();
-                tracing::info!("Previous listener stopped, restarting...");
-            } else {
-                // Channel 关闭，退出
-                break;
-            }
-        }
+        HOTKEYS_ENABLED.store(current_config.enabled, Ordering::SeqCst);
+        tracing::info!(
+            "Starting hotkey listener with bindings: {:?} (mode: {:?})",
+            current_config.all_bindings(),
+            current_config.mode
+        );
 
-        #[cfg(target_os = "windows")]
-        {
-            let binding_clone = current_binding.clone();
-            let app_handle_clone = app_handle.clone();
-
-            let (stop_tx, stop_rx) = std::sync::mpsc::channel::<()>();
-
-            let listener_handle = std::thread::spawn(move || {
-                windows::start_listener(app_handle_clone, binding_clone, stop_rx)
-            });
-
-            if let Some(new_binding) = rx.blocking_recv() {
-                tracing::info!("Received new hotkey binding: {:?}", new_binding);
-                current_binding = new_binding;
-                let _ = stop_tx.send(());
-                let _ = listener_handle.join();
-                tracing::info!("Previous listener stopped, restarting...");
-            } else {
-                break;
-            }
-        }
+        // 监听器会在收到停止信号时返回
+        let (stop_tx, stop_rx) = std::sync::mpsc::channel::<()>();
+        let listener_handle =
+            spawn_listener(app_handle.clone(), current_config.clone(), stop_rx);
 
-        #[cfg(not(any(target_os = "macos", target_os = "windows")))]
-        {
-            return Err(HotkeyError::Error("Unsupported platform".to_string()));
-        }
+        // 等待新配置
+        let Some(new_config) = rx.blocking_recv() else {
+            // Channel 关闭，退出
+            break;
+        };
+        tracing::info!("Received new hotkey config: {:?}", new_config.all_bindings());
+
+        // 发送停止信号，等待监听器线程结束
+        let _ = stop_tx.send(());
+        let _ = listener_handle.join();
+        tracing::info!("Previous listener stopped, restarting...");
+
+        // 停止期间又收到的配置一并合并
+        current_config = latest_config(&mut rx, new_config);
     }
 
     Ok(())
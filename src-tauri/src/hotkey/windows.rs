@@ -9,11 +9,12 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::{Receiver, TryRecvError};
 use std::sync::Arc;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter};
 
-use crate::config::settings::{HotkeyBinding, KeyCode};
-use crate::get_pipeline;
+use vhisper_core::{HotkeyAction, HotkeyBinding, HotkeyConfig, HotkeyMode, KeyCode};
+use crate::{emit_processing_error, get_pipeline, process_stopped_recording};
+use crate::output::{self, capture_frontmost_app};
 
 #[derive(Debug, thiserror::Error)]
 pub enum HotkeyError {
@@ -111,19 +112,31 @@ fn check_modifiers(modifiers: &[KeyCode]) -> bool {
 #[cfg(target_os = "windows")]
 pub fn start_listener(
     app_handle: AppHandle,
-    binding: HotkeyBinding,
+    config: HotkeyConfig,
     stop_rx: Receiver<()>,
 ) -> Result<(), HotkeyError> {
-    let is_key_pressed = Arc::new(AtomicBool::new(false));
+    let bindings = config.all_bindings();
+    let mode = config.mode;
+    let min_press_ms = config.min_press_ms;
+
+    // 每个快捷键独立记录按下状态
+    let mut is_key_pressed = vec![false; bindings.len()];
     let is_recording = Arc::new(AtomicBool::new(false));
+    // 触发当前录音的快捷键索引
+    let mut active_binding = 0usize;
+    let mut original_app_pid: Option<i32> = None;
+    let mut double_tap = super::DoubleTapDetector::new(config.double_tap_window_ms);
+    let mut recording_started_at: Option<Instant> = None;
 
-    let main_vk = keycode_to_vk(&binding.key);
+    let main_vks: Vec<VIRTUAL_KEY> = bindings.iter().map(|b| keycode_to_vk(&b.key)).collect();
 
-    tracing::info!(
-        "Starting Windows hotkey listener for: {:?} (vk: {:?})",
-        binding,
-        main_vk
-    );
+    for (binding, vk) in bindings.iter().zip(&main_vks) {
+        tracing::info!(
+            "Starting Windows hotkey listener for: {:?} (vk: {:?})",
+            binding,
+            vk
+        );
+    }
 
     loop {
         // 检查是否收到停止信号
@@ -135,38 +148,98 @@ pub fn start_listener(
             Err(TryRecvError::Empty) => {}
         }
 
-        // 检查主键状态
-        let main_key_down = is_key_down(main_vk);
+        // 快捷键已暂停（如游戏中），保持轮询但不处理按键
+        if !super::hotkeys_enabled() {
+            thread::sleep(Duration::from_millis(10));
+            continue;
+        }
 
-        // 检查修饰键状态
-        let modifiers_down = check_modifiers(&binding.modifiers);
+        for (index, binding) in bindings.iter().enumerate() {
+            // 组合判断：主键按下 + 所有修饰键按下
+            let hotkey_active = is_key_down(main_vks[index]) && check_modifiers(&binding.modifiers);
 
-        // 组合判断：主键按下 + 所有修饰键按下
-        let hotkey_active = main_key_down && modifiers_down;
+            let was_pressed = is_key_pressed[index];
 
-        let was_pressed = is_key_pressed.load(Ordering::SeqCst);
+            if hotkey_active && !was_pressed {
+                // 快捷键激活
+                is_key_pressed[index] = true;
 
-        if hotkey_active && !was_pressed {
-            // 快捷键激活
-            is_key_pressed.store(true, Ordering::SeqCst);
+                if binding.action == HotkeyAction::RepeatLastOutput {
+                    super::spawn_repeat_last_output(&app_handle, binding);
+                    continue;
+                }
 
-            if !is_recording.load(Ordering::SeqCst) {
-                is_recording.store(true, Ordering::SeqCst);
-                tracing::info!("Hotkey pressed - starting recording");
-                start_recording(&app_handle);
-            }
-        } else if !hotkey_active && was_pressed {
-            // 快捷键释放 (主键释放或任一修饰键释放)
-            is_key_pressed.store(false, Ordering::SeqCst);
-
-            if is_recording.load(Ordering::SeqCst) {
-                is_recording.store(false, Ordering::SeqCst);
-                tracing::info!("Hotkey released - stopping recording");
-
-                let app_handle_clone = app_handle.clone();
-                thread::spawn(move || {
-                    stop_recording(&app_handle_clone);
-                });
+                if binding.action == HotkeyAction::Cancel {
+                    // 丢弃本次录音，之后按下录音快捷键重新开始
+                    is_recording.store(false, Ordering::SeqCst);
+                    original_app_pid = None;
+                    super::cancel_processing(&app_handle, binding);
+                    continue;
+                }
+
+                // 双击模式：只有窗口内的第二次按下才开始或结束录音
+                if mode == HotkeyMode::DoubleTap && !double_tap.register(index) {
+                    continue;
+                }
+
+                // 自动结束模式：录音已因静音结束时，本次按下开始新的录音
+                if is_recording.load(Ordering::SeqCst)
+                    && super::recording_auto_stopped(mode, recording_started_at)
+                {
+                    is_recording.store(false, Ordering::SeqCst);
+                    original_app_pid = None;
+                }
+
+                // 切换模式：再次按下（双击模式为再次双击）触发录音的快捷键时结束录音
+                if matches!(
+                    mode,
+                    HotkeyMode::Toggle | HotkeyMode::DoubleTap | HotkeyMode::AutoStop
+                ) && is_recording.load(Ordering::SeqCst)
+                    && active_binding == index
+                {
+                    is_recording.store(false, Ordering::SeqCst);
+                    tracing::info!("Hotkey pressed again - stopping recording");
+                    stop_recording(&app_handle, original_app_pid.take(), binding.clone());
+                    continue;
+                }
+
+                if !is_recording.load(Ordering::SeqCst)
+                    && !super::is_frontmost_app_excluded(&app_handle)
+                {
+                    is_recording.store(true, Ordering::SeqCst);
+                    active_binding = index;
+                    recording_started_at = Some(Instant::now());
+
+                    // 记录当前活跃应用的 PID
+                    original_app_pid = capture_frontmost_app();
+                    tracing::info!(
+                        "Hotkey {} pressed - starting recording (app pid: {:?})",
+                        binding.display_text(),
+                        original_app_pid
+                    );
+                    start_recording(&app_handle);
+                }
+            } else if !hotkey_active && was_pressed {
+                // 快捷键释放 (主键释放或任一修饰键释放)
+                is_key_pressed[index] = false;
+
+                // 按住说话模式下，只有触发录音的快捷键释放时才停止
+                if mode == HotkeyMode::PushToTalk
+                    && is_recording.load(Ordering::SeqCst)
+                    && active_binding == index
+                {
+                    is_recording.store(false, Ordering::SeqCst);
+                    let started_at = recording_started_at.take();
+                    if started_at.is_some_and(|at| super::is_accidental_press(at, min_press_ms)) {
+                        original_app_pid = None;
+                        super::discard_recording(&app_handle);
+                        continue;
+                    }
+                    tracing::info!("Hotkey released - stopping recording");
+
+                    // 处理在共享 runtime 上异步执行，不会阻塞轮询
+                    stop_recording(&app_handle, original_app_pid.take(), binding.clone());
+                }
             }
         }
 
@@ -180,7 +253,7 @@ pub fn start_listener(
 #[cfg(not(target_os = "windows"))]
 pub fn start_listener(
     _app_handle: AppHandle,
-    _binding: HotkeyBinding,
+    _config: HotkeyConfig,
     _stop_rx: std::sync::mpsc::Receiver<()>,
 ) -> Result<(), HotkeyError> {
     Err(HotkeyError::Start(
@@ -191,34 +264,30 @@ pub fn start_listener(
 fn start_recording(app_handle: &AppHandle) {
     let _ = app_handle.emit("recording-started", ());
 
+    output::expire_last_output();
     if let Some(pipeline) = get_pipeline() {
+        pipeline.set_target_app(output::get_frontmost_app());
         if let Err(e) = pipeline.start_recording() {
             tracing::error!("Failed to start recording: {}", e);
-            let _ = app_handle.emit("processing-error", e.to_string());
+            emit_processing_error(app_handle, &e);
         }
     }
 }
 
-fn stop_recording(app_handle: &AppHandle) {
+fn stop_recording(
+    app_handle: &AppHandle,
+    original_app_pid: Option<i32>,
+    binding: HotkeyBinding,
+) {
     let _ = app_handle.emit("recording-stopped", ());
 
     if let Some(pipeline) = get_pipeline() {
-        let app_handle_clone = app_handle.clone();
-
-        tokio::runtime::Builder::new_current_thread()
-            .enable_all()
-            .build()
-            .unwrap()
-            .block_on(async {
-                match pipeline.stop_and_process(None).await {
-                    Ok(_) => {
-                        let _ = app_handle_clone.emit("processing-complete", ());
-                    }
-                    Err(e) => {
-                        tracing::error!("Processing error: {}", e);
-                        let _ = app_handle_clone.emit("processing-error", e.to_string());
-                    }
-                }
-            });
+        pipeline.set_llm_model_override(binding.model_override.clone());
+
+        // 在 tauri 共享 async runtime 上 spawn，取消通过 pipeline.cancel() 完成
+        let app_handle = app_handle.clone();
+        tauri::async_runtime::spawn(async move {
+            let _ = process_stopped_recording(&app_handle, Some(&binding), original_app_pid).await;
+        });
     }
 }
@@ -1,15 +1,21 @@
 pub mod commands;
 pub mod hotkey;
 pub mod output;
+pub mod overlay;
 pub mod permissions;
+pub mod sounds;
 pub mod tray;
 
 use std::sync::{Arc, OnceLock};
-use tauri::{Manager, RunEvent, WindowEvent};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, RunEvent, WindowEvent};
 use tokio::sync::RwLock;
 
 // 从 vhisper-core 导入
-pub use vhisper_core::{AppConfig, VoicePipeline};
+pub use vhisper_core::{
+    AppConfig, DeviceMonitor, PipelineError, PipelineErrorKind, PipelineEvent, PipelineStage,
+    ProcessingError, VoicePipeline,
+};
 
 /// 全局 Pipeline 实例
 static VOICE_PIPELINE: OnceLock<Arc<VoicePipeline>> = OnceLock::new();
@@ -19,6 +25,156 @@ pub fn get_pipeline() -> Option<Arc<VoicePipeline>> {
     VOICE_PIPELINE.get().cloned()
 }
 
+/// 向前端发送带时间戳的处理阶段事件
+pub fn emit_stage(app_handle: &AppHandle, stage: PipelineStage) {
+    let _ = app_handle.emit(stage.event_name(), PipelineEvent::now(stage));
+}
+
+/// 记录输出耗时，并向前端发送本次处理的各阶段耗时（`pipeline-metrics`）
+pub fn emit_pipeline_metrics(app_handle: &AppHandle, pipeline: &VoicePipeline, output: Duration) {
+    if let Some(metrics) = pipeline.record_output_duration(output) {
+        let _ = app_handle.emit("pipeline-metrics", metrics);
+    }
+}
+
+/// 向前端发送处理失败事件，置信度过低时额外发送 `low-confidence`（提示用户重说）
+///
+/// 用户主动取消不视为错误，只发送 `processing-cancelled`
+pub fn emit_processing_error(app_handle: &AppHandle, error: &PipelineError) {
+    if matches!(error, PipelineError::Cancelled) {
+        let _ = app_handle.emit("processing-cancelled", ());
+        return;
+    }
+    if let PipelineError::LowConfidence(confidence) = error {
+        let _ = app_handle.emit("low-confidence", confidence);
+    }
+    let _ = app_handle.emit("processing-error", ProcessingError::from(error));
+}
+
+/// 向前端发送文本输出失败事件
+pub fn emit_output_error(app_handle: &AppHandle, error: impl std::fmt::Display) {
+    let _ = app_handle.emit(
+        "processing-error",
+        ProcessingError::new(PipelineErrorKind::OutputFailed, error.to_string()),
+    );
+}
+
+/// 将最近一次识别结果重新输出到当前前台应用（粘贴未成功时使用）
+pub fn repeat_last_output(
+    app_handle: &AppHandle,
+    output_config: &vhisper_core::OutputConfig,
+) -> Result<(), String> {
+    let text = get_pipeline()
+        .and_then(|pipeline| pipeline.last_output())
+        .ok_or("没有可重复输出的识别结果")?;
+
+    // 重复输出不应删除上次已粘贴的内容
+    let mut output_config = output_config.clone();
+    output_config.replace_last_output = false;
+
+    emit_stage(app_handle, PipelineStage::OutputStarted);
+    output::output_text(app_handle, &text, &output_config, None).map_err(|e| e.to_string())
+}
+
+/// 取消当前录音或正在进行的识别，结果不会输出
+pub fn cancel_processing(app_handle: &AppHandle) -> Result<(), String> {
+    let pipeline = get_pipeline().ok_or("语音管道未初始化")?;
+    pipeline.cancel().map_err(|e| e.to_string())?;
+    tracing::info!("Processing cancelled by user");
+
+    let app_handle_clone = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app_handle_clone.state::<AppState>();
+        *state.is_recording.write().await = false;
+    });
+    let _ = app_handle.emit("processing-cancelled", ());
+    Ok(())
+}
+
+/// 录音达到最长时长：通知前端并处理已录内容
+fn process_after_max_duration(app_handle: AppHandle) {
+    tracing::warn!("Recording reached max duration, processing captured audio");
+    let _ = app_handle.emit("recording-max-duration", ());
+    finish_auto_stopped_recording(app_handle);
+}
+
+/// 自动结束模式下说话后持续静音：处理已录内容
+fn process_after_silence(app_handle: AppHandle) {
+    tracing::info!("Silence timeout reached, processing captured audio");
+    finish_auto_stopped_recording(app_handle);
+}
+
+/// 录音已自动停止采集：通知前端并处理已录内容
+fn finish_auto_stopped_recording(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let state = app_handle.state::<AppState>();
+        *state.is_recording.write().await = false;
+        let _ = app_handle.emit("recording-stopped", ());
+
+        // 快捷键随后结束录音时 stop_and_process 不在录音状态，直接返回空结果
+        let _ = process_stopped_recording(&app_handle, None, None).await;
+    });
+}
+
+/// 处理已停止的录音，并将结果输出到当前前台应用
+///
+/// - `binding`：触发录音的快捷键，提供输出设置的覆盖；None 时使用全局设置
+/// - `original_app_pid`：录音开始时的前台应用
+///
+/// 会发送输出、耗时和完成事件；失败时发送错误事件并返回错误
+pub async fn process_stopped_recording(
+    app_handle: &AppHandle,
+    binding: Option<&vhisper_core::HotkeyBinding>,
+    original_app_pid: Option<i32>,
+) -> Result<(), PipelineError> {
+    let Some(pipeline) = get_pipeline() else {
+        return Ok(());
+    };
+
+    match pipeline.stop_and_process().await {
+        Ok(text) => {
+            let output_started = Instant::now();
+            if !text.is_empty() {
+                emit_stage(app_handle, PipelineStage::OutputStarted);
+                let state = app_handle.state::<AppState>();
+                let base = pipeline.output_config(&*state.config.read().await);
+                let output_config = match binding {
+                    Some(binding) => binding.output_config(&base),
+                    None => base,
+                };
+                if let Err(e) =
+                    output::output_text(app_handle, &text, &output_config, original_app_pid)
+                {
+                    tracing::error!("Text output failed: {}", e);
+                    emit_output_error(app_handle, &e);
+                }
+            }
+            emit_pipeline_metrics(app_handle, &pipeline, output_started.elapsed());
+            let _ = app_handle.emit("processing-complete", ());
+            Ok(())
+        }
+        Err(e) => {
+            tracing::error!("Processing error: {}", e);
+            emit_processing_error(app_handle, &e);
+            Err(e)
+        }
+    }
+}
+
+/// 从启动参数中读取 `--data-dir <path>` 或 `--data-dir=<path>`
+fn data_dir_arg() -> Option<String> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--data-dir" {
+            return args.next();
+        }
+        if let Some(dir) = arg.strip_prefix("--data-dir=") {
+            return Some(dir.to_string());
+        }
+    }
+    None
+}
+
 /// 应用全局状态
 pub struct AppState {
     pub config: Arc<RwLock<AppConfig>>,
@@ -55,6 +211,13 @@ pub fn run() {
 
     tracing::info!("Starting Vhisper...");
 
+    // 自定义数据目录需在加载配置前设置
+    if let Some(dir) = data_dir_arg() {
+        if let Err(e) = vhisper_core::set_data_dir(&dir) {
+            tracing::error!("Invalid --data-dir {}: {}, using default", dir, e);
+        }
+    }
+
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .setup(move |app| {
@@ -67,6 +230,44 @@ pub fn run() {
             // 初始化 VoicePipeline
             match VoicePipeline::new(config_arc.clone()) {
                 Ok(pipeline) => {
+                    // 快捷键回调不在运行时线程中，预连接需要显式提供运行时
+                    pipeline.set_runtime_handle(tauri::async_runtime::handle().inner().clone());
+                    let stage_app_handle = app.handle().clone();
+                    pipeline.set_event_listener(move |event| {
+                        let _ = stage_app_handle.emit(event.stage.event_name(), event);
+                    });
+                    let partial_app_handle = app.handle().clone();
+                    pipeline.set_partial_listener(move |text| {
+                        let _ = partial_app_handle.emit("partial-transcript", text);
+                    });
+                    let llm_partial_app_handle = app.handle().clone();
+                    pipeline.set_llm_partial_listener(move |text| {
+                        let _ = llm_partial_app_handle.emit("llm-partial", text);
+                    });
+                    let level_app_handle = app.handle().clone();
+                    pipeline.set_level_listener(move |level| {
+                        let _ = level_app_handle.emit("audio-level", level);
+                    });
+                    let limit_app_handle = app.handle().clone();
+                    pipeline.set_max_duration_listener(move || {
+                        process_after_max_duration(limit_app_handle.clone());
+                    });
+                    let silence_app_handle = app.handle().clone();
+                    pipeline.set_silence_listener(move || {
+                        process_after_silence(silence_app_handle.clone());
+                    });
+                    let retry_app_handle = app.handle().clone();
+                    pipeline.set_retry_listener(move |event| {
+                        let _ = retry_app_handle.emit("asr-retry", event);
+                    });
+                    let provider_app_handle = app.handle().clone();
+                    pipeline.set_provider_listener(move |provider| {
+                        let _ = provider_app_handle.emit("asr-provider-used", provider);
+                    });
+                    let words_app_handle = app.handle().clone();
+                    pipeline.set_low_confidence_words_listener(move |words| {
+                        let _ = words_app_handle.emit("low-confidence-words", words);
+                    });
                     let _ = VOICE_PIPELINE.set(Arc::new(pipeline));
                     tracing::info!("VoicePipeline initialized");
                 }
@@ -75,6 +276,18 @@ pub fn run() {
                 }
             }
 
+            // 后台探测已配置的服务商，不阻塞启动
+            let status_app_handle = app.handle().clone();
+            let status_config = config.clone();
+            tauri::async_runtime::spawn(async move {
+                let statuses = commands::test::collect_providers_status(&status_config).await;
+                tracing::info!("Providers status: {:?}", statuses);
+                let _ = status_app_handle.emit("providers-status", statuses);
+            });
+
+            // LLM 已启用但不可用时提前提示（识别时仍会回退到原始文本）
+            commands::test::spawn_llm_health_check(app.handle().clone(), config.llm.clone());
+
             // 初始化应用状态
             let state = AppState {
                 config: config_arc,
@@ -82,15 +295,37 @@ pub fn run() {
             };
             app.manage(state);
 
-            // 设置系统托盘（必须保持 TrayIcon 存活，否则点击无效）
-            let tray_icon = tray::setup_tray(app.handle())?;
-            app.manage(tray_icon);
+            // 设置系统托盘（必须保持 TrayHandle 存活，否则点击无效）
+            let tray_handle = tray::setup_tray(
+                app.handle(),
+                config.feedback.silent_mode,
+                config.hotkey.enabled,
+            )?;
+            app.manage(tray_handle);
+
+            // 录音悬浮窗，创建失败不影响录音
+            if let Err(e) = overlay::setup_overlay_window(app.handle()) {
+                tracing::warn!("Failed to create overlay window: {}", e);
+            }
+
+            // 录音提示音
+            sounds::setup_sounds(app.handle());
+
+            // 监听默认输入设备变更（如插入耳机），必须保持 DeviceMonitor 存活
+            let device_app_handle = app.handle().clone();
+            let device_monitor = DeviceMonitor::spawn(Duration::from_secs(2), move |name| {
+                if let Some(pipeline) = get_pipeline() {
+                    pipeline.notify_device_changed();
+                }
+                let _ = device_app_handle.emit("device-changed", name);
+            });
+            app.manage(device_monitor);
 
             // 启动全局快捷键监听
             let app_handle = app.handle().clone();
-            let hotkey_binding = config.hotkey.binding.clone();
+            let hotkey_config = config.hotkey.clone();
             std::thread::spawn(move || {
-                if let Err(e) = hotkey::start_listener(app_handle, hotkey_binding) {
+                if let Err(e) = hotkey::start_listener(app_handle, hotkey_config) {
                     tracing::error!("Failed to start hotkey listener: {}", e);
                 }
             });
@@ -107,13 +342,41 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             commands::config::get_config,
             commands::config::save_config,
+            commands::config::reload_config_from_disk,
+            commands::config::set_temporary_provider,
+            commands::config::clear_temporary_provider,
+            commands::config::get_hotkey_listener_status,
+            commands::config::get_data_dir,
+            commands::setup::check_microphone_permission,
+            commands::setup::check_accessibility_permission,
+            commands::setup::get_setup_status,
+            commands::setup::complete_setup,
             commands::audio::start_recording,
             commands::audio::stop_recording,
+            commands::audio::analyze_last_recording,
+            commands::audio::get_last_metrics,
+            commands::audio::test_microphone,
+            commands::audio::get_input_configs,
+            commands::audio::transcribe_audio,
+            commands::audio::transcribe_file,
+            commands::audio::repeat_last_output,
+            commands::audio::cancel_processing,
+            commands::history::get_history,
+            commands::history::clear_history,
+            commands::history::is_history_locked,
+            commands::history::unlock_history,
             commands::test::test_qwen_api,
             commands::test::test_dashscope_api,
             commands::test::test_openai_api,
+            commands::test::test_deepgram_api,
+            commands::test::test_azure_api,
+            commands::test::test_assemblyai_api,
             commands::test::test_funasr_api,
             commands::test::test_ollama_api,
+            commands::test::test_claude_api,
+            commands::test::test_gemini_api,
+            commands::test::get_providers_status,
+            commands::test::benchmark_providers,
             commands::permissions::check_permissions,
             commands::permissions::request_microphone_permission,
             commands::permissions::request_accessibility_permission,
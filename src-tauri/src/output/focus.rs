@@ -1,7 +1,35 @@
-//! 应用焦点管理模块 (macOS)
+//! 应用焦点管理模块 (macOS / Windows / Linux X11)
+//!
+//! 除读取前台应用外，macOS / Windows 还可在输出前切回录音开始时的应用
 
 #[cfg(target_os = "macos")]
 use objc2_app_kit::NSWorkspace;
+use std::time::Duration;
+use vhisper_core::TargetApp;
+
+/// 记录录音开始时的前台应用，输出前据此恢复焦点；返回其 PID
+pub fn capture_frontmost_app() -> Option<i32> {
+    #[cfg(target_os = "windows")]
+    remember_foreground_window();
+    get_frontmost_app_pid()
+}
+
+/// 将录音开始时的应用切回前台，并等待其成为活跃应用
+///
+/// 无法切换（应用已退出、平台不支持）时返回 false
+pub fn restore_focus(pid: i32) -> bool {
+    if !activate_app(pid) {
+        return false;
+    }
+    // 激活是异步的，等窗口真正获得焦点后再粘贴
+    for _ in 0..10 {
+        if get_frontmost_app_pid() == Some(pid) {
+            return true;
+        }
+        std::thread::sleep(Duration::from_millis(30));
+    }
+    false
+}
 
 /// 获取当前活跃应用的进程 ID
 #[cfg(target_os = "macos")]
@@ -18,15 +46,265 @@ pub fn get_frontmost_app_pid() -> Option<i32> {
     .flatten()
 }
 
-/// Windows 占位实现
+/// 获取当前活跃应用的 bundle id 和名称
+#[cfg(target_os = "macos")]
+pub fn get_frontmost_app() -> Option<TargetApp> {
+    std::panic::catch_unwind(|| {
+        unsafe {
+            let workspace = NSWorkspace::sharedWorkspace();
+            let app = workspace.frontmostApplication()?;
+            Some(TargetApp {
+                id: app.bundleIdentifier().map(|id| id.to_string()),
+                name: app.localizedName().map(|name| name.to_string()),
+            })
+        }
+    })
+    .ok()
+    .flatten()
+}
+
+/// macOS: 激活指定 PID 的应用
+#[cfg(target_os = "macos")]
+fn activate_app(pid: i32) -> bool {
+    use objc2_app_kit::{NSApplicationActivationOptions, NSRunningApplication};
+
+    std::panic::catch_unwind(|| unsafe {
+        NSRunningApplication::runningApplicationWithProcessIdentifier(pid).is_some_and(|app| {
+            app.activateWithOptions(NSApplicationActivationOptions::NSApplicationActivateAllWindows)
+        })
+    })
+    .unwrap_or(false)
+}
+
+/// Windows: 获取前台窗口所属进程的可执行文件名（作为 id，如 "Code.exe"）和不含扩展名的名称
 #[cfg(target_os = "windows")]
-pub fn get_frontmost_app_pid() -> Option<i32> {
-    // TODO: Windows 实现
+pub fn get_frontmost_app() -> Option<TargetApp> {
+    use windows::core::PWSTR;
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+        PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    let pid = get_frontmost_app_pid()?;
+    let path = unsafe {
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid as u32).ok()?;
+        let mut buffer = [0u16; 1024];
+        let mut len = buffer.len() as u32;
+        let result = QueryFullProcessImageNameW(
+            process,
+            PROCESS_NAME_WIN32,
+            PWSTR(buffer.as_mut_ptr()),
+            &mut len,
+        );
+        let _ = CloseHandle(process);
+        result.ok()?;
+        String::from_utf16_lossy(&buffer[..len as usize])
+    };
+
+    let path = std::path::Path::new(&path);
+    Some(TargetApp {
+        id: path.file_name().map(|name| name.to_string_lossy().into_owned()),
+        name: path.file_stem().map(|stem| stem.to_string_lossy().into_owned()),
+    })
+}
+
+/// 其他平台占位实现
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+pub fn get_frontmost_app() -> Option<TargetApp> {
     None
 }
 
+/// Windows: 获取前台窗口的句柄值
+#[cfg(target_os = "windows")]
+pub fn get_frontmost_window_id() -> Option<u64> {
+    use windows::Win32::UI::WindowsAndMessaging::GetForegroundWindow;
+
+    let hwnd = unsafe { GetForegroundWindow() };
+    (!hwnd.is_invalid()).then_some(hwnd.0 as u64)
+}
+
+/// Windows: 录音开始时的前台窗口（保存 HWND 的地址，HWND 本身不能跨线程传递）
+#[cfg(target_os = "windows")]
+static FOREGROUND_WINDOW: std::sync::Mutex<Option<isize>> = std::sync::Mutex::new(None);
+
+#[cfg(target_os = "windows")]
+fn remember_foreground_window() {
+    use windows::Win32::UI::WindowsAndMessaging::GetForegroundWindow;
+
+    let hwnd = unsafe { GetForegroundWindow() };
+    *FOREGROUND_WINDOW.lock().unwrap() = (!hwnd.is_invalid()).then_some(hwnd.0 as isize);
+}
+
+/// Windows: 将录音开始时记录的窗口切回前台
+///
+/// 后台进程调用 `SetForegroundWindow` 会被系统拒绝，
+/// 先用 `AttachThreadInput` 把当前线程挂到前台窗口的输入队列上
+#[cfg(target_os = "windows")]
+fn activate_app(pid: i32) -> bool {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::System::Threading::{AttachThreadInput, GetCurrentThreadId};
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetForegroundWindow, GetWindowThreadProcessId, IsIconic, IsWindow, SetForegroundWindow,
+        ShowWindow, SW_RESTORE,
+    };
+
+    let Some(raw) = *FOREGROUND_WINDOW.lock().unwrap() else {
+        return false;
+    };
+
+    unsafe {
+        let hwnd = HWND(raw as *mut _);
+        if !IsWindow(hwnd).as_bool() {
+            return false;
+        }
+        // 窗口句柄可能已被其他进程复用
+        let mut window_pid = 0u32;
+        GetWindowThreadProcessId(hwnd, Some(&mut window_pid));
+        if window_pid as i32 != pid {
+            return false;
+        }
+        if IsIconic(hwnd).as_bool() {
+            let _ = ShowWindow(hwnd, SW_RESTORE);
+        }
+
+        let current_thread = GetCurrentThreadId();
+        let foreground_thread = GetWindowThreadProcessId(GetForegroundWindow(), None);
+        let attached = foreground_thread != 0
+            && foreground_thread != current_thread
+            && AttachThreadInput(current_thread, foreground_thread, true).as_bool();
+        let activated = SetForegroundWindow(hwnd).as_bool();
+        if attached {
+            let _ = AttachThreadInput(current_thread, foreground_thread, false);
+        }
+        activated
+    }
+}
+
+/// Windows: 获取前台窗口所属进程的 PID
+#[cfg(target_os = "windows")]
+pub fn get_frontmost_app_pid() -> Option<i32> {
+    use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowThreadProcessId};
+
+    unsafe {
+        let hwnd = GetForegroundWindow();
+        if hwnd.is_invalid() {
+            return None;
+        }
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(hwnd, Some(&mut pid));
+        (pid != 0).then_some(pid as i32)
+    }
+}
+
+/// Linux: 通过 X11 的 `_NET_ACTIVE_WINDOW` 获取活跃窗口
+///
+/// 纯 Wayland 会话下无法获取，返回 None（输出时总是粘贴）
+#[cfg(target_os = "linux")]
+mod x11 {
+    use x11rb::connection::Connection;
+    use x11rb::protocol::xproto::{Atom, AtomEnum, ConnectionExt, GetPropertyReply, Window};
+    use x11rb::rust_connection::RustConnection;
+
+    pub struct ActiveWindow {
+        conn: RustConnection,
+        window: Window,
+    }
+
+    fn intern(conn: &RustConnection, name: &str) -> Option<Atom> {
+        Some(conn.intern_atom(false, name.as_bytes()).ok()?.reply().ok()?.atom)
+    }
+
+    fn property(
+        conn: &RustConnection,
+        window: Window,
+        property: Atom,
+        type_: AtomEnum,
+    ) -> Option<GetPropertyReply> {
+        conn.get_property(false, window, property, type_, 0, 1024)
+            .ok()?
+            .reply()
+            .ok()
+    }
+
+    impl ActiveWindow {
+        pub fn get() -> Option<Self> {
+            let (conn, screen_num) = x11rb::connect(None).ok()?;
+            let root = conn.setup().roots.get(screen_num)?.root;
+            let atom = intern(&conn, "_NET_ACTIVE_WINDOW")?;
+            let window = property(&conn, root, atom, AtomEnum::WINDOW)?
+                .value32()?
+                .next()?;
+            (window != 0).then_some(Self { conn, window })
+        }
+
+        pub fn id(&self) -> Window {
+            self.window
+        }
+
+        /// 窗口所属进程 PID（`_NET_WM_PID`）
+        pub fn pid(&self) -> Option<i32> {
+            let atom = intern(&self.conn, "_NET_WM_PID")?;
+            let pid = property(&self.conn, self.window, atom, AtomEnum::CARDINAL)?
+                .value32()?
+                .next()?;
+            Some(pid as i32)
+        }
+
+        /// 窗口类名（`WM_CLASS` 的第二项，如 "firefox"、"Code"）
+        pub fn class(&self) -> Option<String> {
+            let reply = property(
+                &self.conn,
+                self.window,
+                AtomEnum::WM_CLASS.into(),
+                AtomEnum::STRING,
+            )?;
+            let class = reply.value.split(|&b| b == 0).filter(|s| !s.is_empty()).nth(1)?;
+            Some(String::from_utf8_lossy(class).into_owned())
+        }
+    }
+}
+
+/// Linux: 获取活跃窗口所属进程的 PID
+#[cfg(target_os = "linux")]
+pub fn get_frontmost_app_pid() -> Option<i32> {
+    x11::ActiveWindow::get()?.pid()
+}
+
+/// Linux: 获取活跃窗口的 X11 窗口 ID
+#[cfg(target_os = "linux")]
+pub fn get_frontmost_window_id() -> Option<u64> {
+    Some(x11::ActiveWindow::get()?.id() as u64)
+}
+
+/// Linux: 获取活跃窗口的类名（作为 id）和进程名
+#[cfg(target_os = "linux")]
+pub fn get_frontmost_app() -> Option<TargetApp> {
+    let window = x11::ActiveWindow::get()?;
+    let name = window
+        .pid()
+        .and_then(|pid| std::fs::read_to_string(format!("/proc/{}/comm", pid)).ok())
+        .map(|comm| comm.trim().to_string());
+    Some(TargetApp {
+        id: window.class(),
+        name,
+    })
+}
+
 /// 其他平台占位实现
-#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
 pub fn get_frontmost_app_pid() -> Option<i32> {
     None
 }
+
+/// 其他平台无法区分同一应用的不同窗口，只比较 PID
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub fn get_frontmost_window_id() -> Option<u64> {
+    None
+}
+
+/// 其他平台暂不支持切换前台应用
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn activate_app(_pid: i32) -> bool {
+    false
+}
@@ -3,8 +3,51 @@ mod focus;
 mod paste;
 
 pub use clipboard::{get_clipboard_text, set_clipboard_text, ClipboardError};
-pub use focus::get_frontmost_app_pid;
-pub use paste::{simulate_paste, PasteError};
+pub use focus::{
+    capture_frontmost_app, get_frontmost_app, get_frontmost_app_pid, get_frontmost_window_id,
+};
+pub use paste::{delete_backward, move_caret, simulate_paste, type_text, PasteError, TypeError};
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+use vhisper_core::{OutputConfig, OutputMode, PasteShortcut};
+
+/// 上次输出后超过此时长才开始下一次录音的，不再替换（用户很可能已经在继续编辑）
+const REPLACE_WINDOW: Duration = Duration::from_secs(5);
+
+/// 上次粘贴的输出，用于替换上次输出
+struct LastOutput {
+    /// 字符数（退格删除的次数）
+    chars: usize,
+    /// 粘贴时的前台应用 PID
+    pid: Option<i32>,
+    /// 粘贴时的前台窗口
+    window: Option<u64>,
+    at: Instant,
+}
+
+static LAST_OUTPUT: Mutex<Option<LastOutput>> = Mutex::new(None);
+
+/// 开始录音时调用：距上次输出已超过 `REPLACE_WINDOW` 时不再替换上次输出
+pub fn expire_last_output() {
+    let mut last = LAST_OUTPUT.lock().unwrap();
+    if last.as_ref().is_some_and(|last| last.at.elapsed() > REPLACE_WINDOW) {
+        tracing::info!("output_text: last output expired, will not replace it");
+        *last = None;
+    }
+}
+
+/// 取出仍可替换的上次输出字符数（仍在同一应用的同一窗口）
+fn take_replaceable_output(current_pid: Option<i32>, current_window: Option<u64>) -> Option<usize> {
+    let last = LAST_OUTPUT.lock().unwrap().take()?;
+    if last.pid != current_pid || last.window != current_window {
+        tracing::info!("output_text: last output no longer in place, skip replacing");
+        return None;
+    }
+    Some(last.chars)
+}
 
 #[derive(Debug, thiserror::Error)]
 pub enum OutputError {
@@ -16,20 +59,39 @@ pub enum OutputError {
 
 /// 输出文本到当前应用
 ///
+/// - `ClipboardOnly` 模式下只写入剪贴板并发出 `text-copied` 事件，不模拟按键
 /// - 如果 `original_app_pid` 与当前活跃应用相同，则执行粘贴
-/// - 如果不同（用户切换了应用），则只复制到剪贴板
+/// - 如果不同（用户切换了应用），先切回原应用再粘贴；无法切回时只复制到剪贴板
+/// - 启用 `replace_last_output` 且上次输出仍在原位时，先删除上次输出再粘贴
+/// - `Type` 模式下逐字模拟键入，中途失败时剩余部分回退到剪贴板粘贴
 ///
 /// 参数:
+/// - `app_handle`: 用于发出 `text-copied` 事件
 /// - `text`: 要输出的文本
-/// - `restore_clipboard`: 是否恢复原剪贴板内容
-/// - `paste_delay_ms`: 粘贴前的延迟（毫秒）
+/// - `config`: 输出配置（是否恢复剪贴板、粘贴延迟、粘贴位置等）
 /// - `original_app_pid`: 开始录音时的应用 PID，None 表示总是粘贴
 pub fn output_text(
+    app_handle: &AppHandle,
     text: &str,
-    restore_clipboard: bool,
-    paste_delay_ms: u64,
+    config: &OutputConfig,
     original_app_pid: Option<i32>,
 ) -> Result<(), OutputError> {
+    if config.mode == OutputMode::ClipboardOnly {
+        tracing::info!("output_text: clipboard-only mode, skip paste");
+        set_clipboard_text(text)?;
+        // 没有粘贴，上次输出无法替换
+        *LAST_OUTPUT.lock().unwrap() = None;
+        let _ = app_handle.emit("text-copied", text);
+        return Ok(());
+    }
+
+    let restore_clipboard = config.restore_clipboard;
+    let paste_delay_ms = config.paste_delay_ms;
+    let restore_delay_ms = config.clipboard_restore_delay_ms;
+    let paste_shortcut = config
+        .paste_shortcut
+        .unwrap_or_else(PasteShortcut::platform_default);
+
     tracing::info!("output_text: starting, original_app_pid={:?}", original_app_pid);
 
     // 检查是否需要粘贴（用户是否还在原应用）
@@ -38,23 +100,96 @@ pub fn output_text(
             tracing::info!("output_text: getting current frontmost app pid");
             let current_pid = get_frontmost_app_pid();
             tracing::info!("output_text: current_pid={:?}, original_pid={}", current_pid, original_pid);
-            let same_app = current_pid == Some(original_pid);
-            if !same_app {
+            if current_pid == Some(original_pid) {
+                true
+            } else if focus::restore_focus(original_pid) {
+                tracing::info!("应用已切换 (当前: {:?})，已切回原应用 {}", current_pid, original_pid);
+                true
+            } else {
                 tracing::info!(
                     "应用已切换 (原: {}, 当前: {:?})，只复制到剪贴板",
                     original_pid,
                     current_pid
                 );
+                false
             }
-            same_app
         }
         None => true, // 没有原始 PID，总是粘贴
     };
 
     tracing::info!("output_text: should_paste={}", should_paste);
 
+    // 只有在同一应用时才模拟粘贴
+    if !should_paste {
+        set_clipboard_text(text)?;
+        // 已切换应用，上次输出无法再替换
+        *LAST_OUTPUT.lock().unwrap() = None;
+        tracing::info!("output_text: completed successfully");
+        return Ok(());
+    }
+
+    let current_pid = get_frontmost_app_pid();
+    let current_window = get_frontmost_window_id();
+    let replace_chars = if config.replace_last_output {
+        take_replaceable_output(current_pid, current_window)
+    } else {
+        None
+    };
+
+    match replace_chars {
+        // 光标仍在上次输出末尾，退格删除上次输出
+        Some(chars) => delete_backward(chars)?,
+        // 粘贴前移动光标（开头/末尾/全选）
+        None => move_caret(config.paste_position)?,
+    }
+
+    // 打字模式：逐字模拟键入，不经过剪贴板；中途失败时只粘贴尚未键入的部分
+    let to_paste = if config.mode == OutputMode::Type {
+        match type_text(text, paste_delay_ms) {
+            Ok(()) => "",
+            Err(e) => {
+                tracing::warn!("output_text: typing failed ({}), pasting the rest", e);
+                let offset = text
+                    .char_indices()
+                    .nth(e.typed)
+                    .map_or(text.len(), |(index, _)| index);
+                &text[offset..]
+            }
+        }
+    } else {
+        text
+    };
+    if !to_paste.is_empty() {
+        paste_via_clipboard(
+            to_paste,
+            restore_clipboard.then_some(restore_delay_ms),
+            paste_delay_ms,
+            paste_shortcut,
+        )?;
+    }
+
+    *LAST_OUTPUT.lock().unwrap() = Some(LastOutput {
+        chars: text.chars().count(),
+        pid: current_pid,
+        window: current_window,
+        at: Instant::now(),
+    });
+
+    tracing::info!("output_text: completed successfully");
+    Ok(())
+}
+
+/// 写入剪贴板并模拟粘贴
+///
+/// `restore_delay_ms` 不为 None 时，粘贴后等待该时长再恢复原剪贴板内容
+fn paste_via_clipboard(
+    text: &str,
+    restore_delay_ms: Option<u64>,
+    paste_delay_ms: u64,
+    paste_shortcut: PasteShortcut,
+) -> Result<(), OutputError> {
     // 保存当前剪贴板内容
-    let original_clipboard = if restore_clipboard && should_paste {
+    let original_clipboard = if restore_delay_ms.is_some() {
         tracing::info!("output_text: getting original clipboard");
         get_clipboard_text()?
     } else {
@@ -66,24 +201,18 @@ pub fn output_text(
     set_clipboard_text(text)?;
     tracing::info!("output_text: clipboard text set successfully");
 
-    // 只有在同一应用时才模拟粘贴
-    if should_paste {
-        tracing::info!("output_text: simulating paste with delay {}ms", paste_delay_ms);
-        simulate_paste(paste_delay_ms)?;
-        tracing::info!("output_text: paste simulated successfully");
-
-        // 恢复原剪贴板内容
-        if restore_clipboard {
-            if let Some(original) = original_clipboard {
-                tracing::info!("output_text: restoring original clipboard");
-                // 延迟一下再恢复，确保粘贴完成
-                std::thread::sleep(std::time::Duration::from_millis(100));
-                set_clipboard_text(&original)?;
-                tracing::info!("output_text: original clipboard restored");
-            }
-        }
+    tracing::info!("output_text: simulating paste with delay {}ms", paste_delay_ms);
+    simulate_paste(paste_delay_ms, paste_shortcut)?;
+    tracing::info!("output_text: paste simulated successfully");
+
+    // 恢复原剪贴板内容
+    if let (Some(original), Some(delay_ms)) = (original_clipboard, restore_delay_ms) {
+        tracing::info!("output_text: restoring original clipboard after {}ms", delay_ms);
+        // 等目标应用读取完剪贴板再恢复，否则可能粘贴出旧内容
+        std::thread::sleep(std::time::Duration::from_millis(delay_ms));
+        set_clipboard_text(&original)?;
+        tracing::info!("output_text: original clipboard restored");
     }
 
-    tracing::info!("output_text: completed successfully");
     Ok(())
 }
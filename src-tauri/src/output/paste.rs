@@ -1,14 +1,173 @@
 use std::thread;
 use std::time::Duration;
 
+use vhisper_core::{PastePosition, PasteShortcut};
+
 #[derive(Debug, thiserror::Error)]
 pub enum PasteError {
     #[error("Paste error: {0}")]
     Paste(String),
 }
 
-/// 模拟粘贴操作
-pub fn simulate_paste(delay_ms: u64) -> Result<(), PasteError> {
+/// 键入中途失败
+#[derive(Debug, thiserror::Error)]
+#[error("{error} (typed {typed} chars)")]
+pub struct TypeError {
+    /// 失败前已键入的字符数
+    pub typed: usize,
+    pub error: PasteError,
+}
+
+/// macOS: 发送带修饰键的按键事件
+#[cfg(target_os = "macos")]
+fn post_cg_shortcut(
+    key: core_graphics::event::CGKeyCode,
+    flags: core_graphics::event::CGEventFlags,
+) -> Result<(), PasteError> {
+    use core_graphics::event::CGEvent;
+    use core_graphics::event_source::{CGEventSource, CGEventSourceStateID};
+
+    // 创建事件源
+    let source = CGEventSource::new(CGEventSourceStateID::HIDSystemState)
+        .map_err(|_| PasteError::Paste("Failed to create CGEventSource".to_string()))?;
+
+    // 创建按下事件
+    let key_down = CGEvent::new_keyboard_event(source.clone(), key, true)
+        .map_err(|_| PasteError::Paste("Failed to create key down event".to_string()))?;
+    key_down.set_flags(flags);
+
+    // 创建释放事件
+    let key_up = CGEvent::new_keyboard_event(source, key, false)
+        .map_err(|_| PasteError::Paste("Failed to create key up event".to_string()))?;
+    key_up.set_flags(flags);
+
+    // 发送事件
+    key_down.post(core_graphics::event::CGEventTapLocation::HID);
+    thread::sleep(Duration::from_millis(10));
+    key_up.post(core_graphics::event::CGEventTapLocation::HID);
+
+    Ok(())
+}
+
+/// Windows / 其他平台: 按住修饰键发送按键
+#[cfg(not(target_os = "macos"))]
+fn post_shortcut(modifiers: &[enigo::Key], key: enigo::Key) -> Result<(), PasteError> {
+    use enigo::{Enigo, Keyboard, Settings};
+
+    let mut enigo =
+        Enigo::new(&Settings::default()).map_err(|e| PasteError::Paste(e.to_string()))?;
+
+    for modifier in modifiers {
+        enigo
+            .key(*modifier, enigo::Direction::Press)
+            .map_err(|e| PasteError::Paste(e.to_string()))?;
+    }
+    enigo
+        .key(key, enigo::Direction::Click)
+        .map_err(|e| PasteError::Paste(e.to_string()))?;
+    // 按相反顺序释放修饰键
+    for modifier in modifiers.iter().rev() {
+        enigo
+            .key(*modifier, enigo::Direction::Release)
+            .map_err(|e| PasteError::Paste(e.to_string()))?;
+    }
+
+    Ok(())
+}
+
+/// Windows / 其他平台: 发送 Ctrl + 按键
+#[cfg(not(target_os = "macos"))]
+fn post_ctrl_shortcut(key: enigo::Key) -> Result<(), PasteError> {
+    post_shortcut(&[enigo::Key::Control], key)
+}
+
+/// Windows / 其他平台: 发送单个按键
+#[cfg(not(target_os = "macos"))]
+fn post_key(key: enigo::Key) -> Result<(), PasteError> {
+    use enigo::{Enigo, Keyboard, Settings};
+
+    let mut enigo =
+        Enigo::new(&Settings::default()).map_err(|e| PasteError::Paste(e.to_string()))?;
+    enigo
+        .key(key, enigo::Direction::Click)
+        .map_err(|e| PasteError::Paste(e.to_string()))?;
+
+    Ok(())
+}
+
+/// 粘贴前移动光标到指定位置
+pub fn move_caret(position: PastePosition) -> Result<(), PasteError> {
+    if position == PastePosition::Cursor {
+        return Ok(());
+    }
+
+    tracing::info!("move_caret: {:?}", position);
+
+    #[cfg(target_os = "macos")]
+    {
+        use core_graphics::event::{CGEventFlags, CGKeyCode};
+
+        // Cmd+↑ / Cmd+↓ 移动到文本开头/末尾，Cmd+A 全选，→ 收起选区
+        const KEY_UP: CGKeyCode = 0x7E;
+        const KEY_DOWN: CGKeyCode = 0x7D;
+        const KEY_RIGHT: CGKeyCode = 0x7C;
+        const KEY_A: CGKeyCode = 0x00;
+
+        let (key, flags) = match position {
+            PastePosition::Start => (KEY_UP, CGEventFlags::CGEventFlagCommand),
+            PastePosition::End => (KEY_DOWN, CGEventFlags::CGEventFlagCommand),
+            PastePosition::ReplaceAll => (KEY_A, CGEventFlags::CGEventFlagCommand),
+            PastePosition::AfterSelection => (KEY_RIGHT, CGEventFlags::CGEventFlagNull),
+            PastePosition::Cursor => unreachable!(),
+        };
+        post_cg_shortcut(key, flags)?;
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        use enigo::Key;
+
+        // Ctrl+Home / Ctrl+End 移动到文本开头/末尾，Ctrl+A 全选，→ 收起选区
+        match position {
+            PastePosition::Start => post_ctrl_shortcut(Key::Home)?,
+            PastePosition::End => post_ctrl_shortcut(Key::End)?,
+            PastePosition::ReplaceAll => post_ctrl_shortcut(Key::Unicode('a'))?,
+            PastePosition::AfterSelection => post_key(Key::RightArrow)?,
+            PastePosition::Cursor => unreachable!(),
+        }
+    }
+
+    // 给目标应用一点时间处理光标移动
+    thread::sleep(Duration::from_millis(20));
+    Ok(())
+}
+
+/// 向前删除 `count` 个字符（模拟按退格键）
+pub fn delete_backward(count: usize) -> Result<(), PasteError> {
+    tracing::info!("delete_backward: {} chars", count);
+
+    for _ in 0..count {
+        #[cfg(target_os = "macos")]
+        {
+            use core_graphics::event::{CGEventFlags, CGKeyCode};
+
+            const KEY_DELETE: CGKeyCode = 0x33;
+            post_cg_shortcut(KEY_DELETE, CGEventFlags::CGEventFlagNull)?;
+        }
+
+        #[cfg(not(target_os = "macos"))]
+        {
+            post_key(enigo::Key::Backspace)?;
+        }
+    }
+
+    // 给目标应用一点时间处理删除
+    thread::sleep(Duration::from_millis(20));
+    Ok(())
+}
+
+/// 模拟粘贴操作，发送 `shortcut` 指定的快捷键
+pub fn simulate_paste(delay_ms: u64, shortcut: PasteShortcut) -> Result<(), PasteError> {
     tracing::info!("simulate_paste: sleeping for {}ms", delay_ms);
     // 等待一小段时间，确保剪贴板内容已就绪
     thread::sleep(Duration::from_millis(delay_ms));
@@ -16,72 +175,116 @@ pub fn simulate_paste(delay_ms: u64) -> Result<(), PasteError> {
 
     #[cfg(target_os = "macos")]
     {
-        use core_graphics::event::{CGEvent, CGEventFlags, CGKeyCode};
-        use core_graphics::event_source::{CGEventSource, CGEventSourceStateID};
-
-        tracing::info!("simulate_paste: using CGEvent for paste");
+        use core_graphics::event::{CGEventFlags, CGKeyCode};
 
-        // 创建事件源
-        let source = CGEventSource::new(CGEventSourceStateID::HIDSystemState)
-            .map_err(|_| PasteError::Paste("Failed to create CGEventSource".to_string()))?;
+        tracing::info!("simulate_paste: using CGEvent for {:?}", shortcut);
 
-        // 'v' 键的虚拟键码是 9
+        // 'v' 键的虚拟键码是 9，Insert 对应 Mac 键盘上的 Help 键
         const KEY_V: CGKeyCode = 9;
+        const KEY_INSERT: CGKeyCode = 0x72;
 
-        // 创建按下 Cmd+V 事件
-        let key_down = CGEvent::new_keyboard_event(source.clone(), KEY_V, true)
-            .map_err(|_| PasteError::Paste("Failed to create key down event".to_string()))?;
-        key_down.set_flags(CGEventFlags::CGEventFlagCommand);
+        let (key, flags) = match shortcut {
+            PasteShortcut::CmdV => (KEY_V, CGEventFlags::CGEventFlagCommand),
+            PasteShortcut::CtrlV => (KEY_V, CGEventFlags::CGEventFlagControl),
+            PasteShortcut::ShiftInsert => (KEY_INSERT, CGEventFlags::CGEventFlagShift),
+            PasteShortcut::CtrlShiftV => (
+                KEY_V,
+                CGEventFlags::CGEventFlagControl | CGEventFlags::CGEventFlagShift,
+            ),
+        };
+        post_cg_shortcut(key, flags)?;
 
-        // 创建释放 Cmd+V 事件
-        let key_up = CGEvent::new_keyboard_event(source, KEY_V, false)
-            .map_err(|_| PasteError::Paste("Failed to create key up event".to_string()))?;
-        key_up.set_flags(CGEventFlags::CGEventFlagCommand);
+        tracing::info!("simulate_paste: CGEvent paste successful");
+    }
 
-        // 发送事件
-        key_down.post(core_graphics::event::CGEventTapLocation::HID);
-        thread::sleep(Duration::from_millis(10));
-        key_up.post(core_graphics::event::CGEventTapLocation::HID);
+    #[cfg(not(target_os = "macos"))]
+    {
+        use enigo::Key;
 
-        tracing::info!("simulate_paste: CGEvent paste successful");
+        tracing::info!("simulate_paste: sending {:?}", shortcut);
+
+        // Windows / Linux 上 Cmd 对应 Meta（Win / Super）键
+        match shortcut {
+            PasteShortcut::CmdV => post_shortcut(&[Key::Meta], Key::Unicode('v'))?,
+            PasteShortcut::CtrlV => post_ctrl_shortcut(Key::Unicode('v'))?,
+            PasteShortcut::ShiftInsert => post_shortcut(&[Key::Shift], Key::Insert)?,
+            PasteShortcut::CtrlShiftV => {
+                post_shortcut(&[Key::Control, Key::Shift], Key::Unicode('v'))?
+            }
+        }
     }
 
-    #[cfg(target_os = "windows")]
+    tracing::info!("simulate_paste: completed successfully");
+    Ok(())
+}
+
+/// 逐字键入时的字符间隔（不超过 `paste_delay_ms`）
+const TYPE_CHAR_DELAY_MS: u64 = 5;
+
+/// 逐字模拟键入文本（用于忽略粘贴的终端、远程桌面）
+///
+/// 按 Unicode 字符发送，不依赖键盘布局，中文等多字节字符与 emoji 均可直接输入；
+/// 失败时返回已键入的字符数
+pub fn type_text(text: &str, delay_ms: u64) -> Result<(), TypeError> {
+    let char_delay = Duration::from_millis(TYPE_CHAR_DELAY_MS.min(delay_ms));
+    tracing::info!(
+        "type_text: typing {} chars with {:?} interval",
+        text.chars().count(),
+        char_delay
+    );
+
+    let mut typed = 0;
+    let failed = |typed, error| TypeError { typed, error };
+
+    #[cfg(target_os = "macos")]
     {
-        use enigo::{Enigo, Key, Keyboard, Settings};
+        use core_graphics::event::{CGEvent, CGEventTapLocation};
+        use core_graphics::event_source::{CGEventSource, CGEventSourceStateID};
 
-        let mut enigo = Enigo::new(&Settings::default())
-            .map_err(|e| PasteError::Paste(e.to_string()))?;
+        let source = CGEventSource::new(CGEventSourceStateID::HIDSystemState).map_err(|_| {
+            failed(0, PasteError::Paste("Failed to create CGEventSource".to_string()))
+        })?;
 
-        enigo
-            .key(Key::Control, enigo::Direction::Press)
-            .map_err(|e| PasteError::Paste(e.to_string()))?;
-        enigo
-            .key(Key::Unicode('v'), enigo::Direction::Click)
-            .map_err(|e| PasteError::Paste(e.to_string()))?;
-        enigo
-            .key(Key::Control, enigo::Direction::Release)
-            .map_err(|e| PasteError::Paste(e.to_string()))?;
+        let mut buf = [0u8; 4];
+        for ch in text.chars() {
+            // 键码无意义，实际输入的字符由事件附带的 Unicode 字符串决定（内部转为 UTF-16）
+            let ch = ch.encode_utf8(&mut buf);
+            // 先创建按下和松开事件再发送，保证失败时当前字符没有输入
+            let events = [true, false]
+                .map(|key_down| CGEvent::new_keyboard_event(source.clone(), 0, key_down));
+            let [Ok(key_down), Ok(key_up)] = events else {
+                return Err(failed(
+                    typed,
+                    PasteError::Paste("Failed to create key event".to_string()),
+                ));
+            };
+            for event in [key_down, key_up] {
+                event.set_string(ch);
+                event.post(CGEventTapLocation::HID);
+            }
+            typed += 1;
+            thread::sleep(char_delay);
+        }
     }
 
-    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    #[cfg(not(target_os = "macos"))]
     {
-        use enigo::{Enigo, Key, Keyboard, Settings};
+        use enigo::{Enigo, Keyboard, Settings};
 
         let mut enigo = Enigo::new(&Settings::default())
-            .map_err(|e| PasteError::Paste(e.to_string()))?;
+            .map_err(|e| failed(0, PasteError::Paste(e.to_string())))?;
 
-        enigo
-            .key(Key::Control, enigo::Direction::Press)
-            .map_err(|e| PasteError::Paste(e.to_string()))?;
-        enigo
-            .key(Key::Unicode('v'), enigo::Direction::Click)
-            .map_err(|e| PasteError::Paste(e.to_string()))?;
-        enigo
-            .key(Key::Control, enigo::Direction::Release)
-            .map_err(|e| PasteError::Paste(e.to_string()))?;
+        let mut buf = [0u8; 4];
+        for ch in text.chars() {
+            // Windows 下以 KEYEVENTF_UNICODE 发送，BMP 以外的字符自动拆为代理对
+            enigo
+                .text(ch.encode_utf8(&mut buf))
+                .map_err(|e| failed(typed, PasteError::Paste(e.to_string())))?;
+            typed += 1;
+            thread::sleep(char_delay);
+        }
     }
 
-    tracing::info!("simulate_paste: completed successfully");
+    tracing::info!("type_text: completed successfully");
     Ok(())
 }
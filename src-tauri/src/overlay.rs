@@ -0,0 +1,124 @@
+//! 录音悬浮窗
+//!
+//! 录音时在鼠标附近显示一个置顶的小窗口，展示录音状态、音量和实时识别文本。
+//! 悬浮窗不可获得焦点，避免抢走目标应用的焦点导致粘贴失败。
+
+use tauri::{
+    AppHandle, Listener, LogicalSize, Manager, PhysicalPosition, WebviewUrl, WebviewWindow,
+    WebviewWindowBuilder,
+};
+
+use crate::AppState;
+
+/// 悬浮窗标签
+pub const OVERLAY_LABEL: &str = "overlay";
+
+/// 悬浮窗尺寸（逻辑像素）
+const OVERLAY_WIDTH: f64 = 320.0;
+const OVERLAY_HEIGHT: f64 = 64.0;
+
+/// 悬浮窗相对鼠标位置的偏移（逻辑像素），避免遮挡光标处的文字
+const CURSOR_OFFSET: f64 = 24.0;
+
+/// 创建悬浮窗（初始隐藏），并根据录音和处理事件显示或隐藏
+pub fn setup_overlay_window(app: &AppHandle) -> tauri::Result<()> {
+    let window = WebviewWindowBuilder::new(
+        app,
+        OVERLAY_LABEL,
+        WebviewUrl::App("index.html#overlay".into()),
+    )
+    .title("Vhisper")
+    .inner_size(OVERLAY_WIDTH, OVERLAY_HEIGHT)
+    .decorations(false)
+    .transparent(true)
+    .shadow(false)
+    .resizable(false)
+    .always_on_top(true)
+    .visible_on_all_workspaces(true)
+    .skip_taskbar(true)
+    .focused(false)
+    .focusable(false)
+    .visible(false)
+    .build()?;
+
+    // 鼠标事件穿透到下层窗口
+    window.set_ignore_cursor_events(true)?;
+
+    let show_handle = app.clone();
+    app.listen_any("recording-started", move |_| show_overlay(&show_handle));
+
+    for event in ["processing-complete", "processing-error", "processing-cancelled"] {
+        let hide_handle = app.clone();
+        app.listen_any(event, move |_| hide_overlay(&hide_handle));
+    }
+
+    Ok(())
+}
+
+/// 在鼠标附近显示悬浮窗（静音模式下不显示）
+fn show_overlay(app: &AppHandle) {
+    if silent_mode(app) {
+        return;
+    }
+    let Some(window) = app.get_webview_window(OVERLAY_LABEL) else {
+        return;
+    };
+    if let Err(e) = move_near_cursor(app, &window) {
+        tracing::warn!("Failed to position overlay: {}", e);
+    }
+    if let Err(e) = window.show() {
+        tracing::warn!("Failed to show overlay: {}", e);
+    }
+}
+
+fn hide_overlay(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(OVERLAY_LABEL) {
+        let _ = window.hide();
+    }
+}
+
+/// 将悬浮窗移动到鼠标右下方，超出屏幕时改为显示在鼠标左上方
+fn move_near_cursor(app: &AppHandle, window: &WebviewWindow) -> tauri::Result<()> {
+    let cursor = app.cursor_position()?;
+    let Some(monitor) = app
+        .monitor_from_point(cursor.x, cursor.y)?
+        .or(app.primary_monitor()?)
+    else {
+        return Ok(());
+    };
+
+    let scale = monitor.scale_factor();
+    let size = LogicalSize::new(OVERLAY_WIDTH, OVERLAY_HEIGHT).to_physical::<f64>(scale);
+    let offset = CURSOR_OFFSET * scale;
+    let area_pos = monitor.position();
+    let area_size = monitor.size();
+    let right = area_pos.x as f64 + area_size.width as f64;
+    let bottom = area_pos.y as f64 + area_size.height as f64;
+
+    let mut x = cursor.x + offset;
+    if x + size.width > right {
+        x = cursor.x - offset - size.width;
+    }
+    let mut y = cursor.y + offset;
+    if y + size.height > bottom {
+        y = cursor.y - offset - size.height;
+    }
+
+    window.set_position(PhysicalPosition::new(
+        x.max(area_pos.x as f64),
+        y.max(area_pos.y as f64),
+    ))
+}
+
+/// 读取静音模式设置，配置被占用时按非静音处理
+fn silent_mode(app: &AppHandle) -> bool {
+    app.try_state::<AppState>()
+        .and_then(|state| {
+            state
+                .config
+                .try_read()
+                .ok()
+                .map(|config| config.feedback.silent_mode)
+        })
+        .unwrap_or(false)
+}
@@ -0,0 +1,104 @@
+//! 录音提示音
+//!
+//! 录音开始、结束和处理完成时播放内置的短提示音。每次播放使用独立线程，不阻塞快捷键线程；
+//! 结束提示音等麦克风停止采集后再播放，避免被录进音频。
+
+use std::io::Cursor;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rodio::{Decoder, OutputStream, Sink};
+use tauri::{AppHandle, Listener, Manager};
+
+use crate::{get_pipeline, AppState};
+
+// 嵌入提示音
+const START_SOUND: &[u8] = include_bytes!("../sounds/start.wav");
+const STOP_SOUND: &[u8] = include_bytes!("../sounds/stop.wav");
+const DONE_SOUND: &[u8] = include_bytes!("../sounds/done.wav");
+
+/// 等待麦克风停止采集的最长时间，超时后照常播放
+const CAPTURE_STOP_TIMEOUT: Duration = Duration::from_millis(500);
+const CAPTURE_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+#[derive(Debug, thiserror::Error)]
+pub enum SoundError {
+    #[error("Failed to open audio output: {0}")]
+    Output(String),
+    #[error("Failed to decode sound: {0}")]
+    Decode(String),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Cue {
+    Start,
+    Stop,
+    Done,
+}
+
+impl Cue {
+    fn data(self) -> &'static [u8] {
+        match self {
+            Cue::Start => START_SOUND,
+            Cue::Stop => STOP_SOUND,
+            Cue::Done => DONE_SOUND,
+        }
+    }
+}
+
+/// 根据录音和处理事件播放提示音
+pub fn setup_sounds(app: &AppHandle) {
+    let events = [
+        ("recording-started", Cue::Start),
+        ("recording-stopped", Cue::Stop),
+        ("processing-complete", Cue::Done),
+    ];
+    for (event, cue) in events {
+        let app_handle = app.clone();
+        app.listen_any(event, move |_| {
+            if let Some(volume) = sound_volume(&app_handle) {
+                thread::spawn(move || play_cue(cue, volume));
+            }
+        });
+    }
+}
+
+fn play_cue(cue: Cue, volume: f32) {
+    if matches!(cue, Cue::Stop) {
+        wait_capture_stopped();
+    }
+    if let Err(e) = play(cue.data(), volume) {
+        tracing::warn!("Failed to play sound cue: {}", e);
+    }
+}
+
+/// 等待录音线程结束，避免结束提示音被录进去
+fn wait_capture_stopped() {
+    let deadline = Instant::now() + CAPTURE_STOP_TIMEOUT;
+    while get_pipeline().is_some_and(|pipeline| pipeline.is_capturing()) {
+        if Instant::now() >= deadline {
+            return;
+        }
+        thread::sleep(CAPTURE_POLL_INTERVAL);
+    }
+}
+
+/// 播放 WAV 数据，阻塞到播放结束
+fn play(data: &'static [u8], volume: f32) -> Result<(), SoundError> {
+    let (_stream, handle) =
+        OutputStream::try_default().map_err(|e| SoundError::Output(e.to_string()))?;
+    let sink = Sink::try_new(&handle).map_err(|e| SoundError::Output(e.to_string()))?;
+    let source = Decoder::new(Cursor::new(data)).map_err(|e| SoundError::Decode(e.to_string()))?;
+    sink.set_volume(volume);
+    sink.append(source);
+    sink.sleep_until_end();
+    Ok(())
+}
+
+/// 读取提示音设置，返回音量；未开启、静音模式或配置被占用时不播放
+fn sound_volume(app: &AppHandle) -> Option<f32> {
+    let state = app.try_state::<AppState>()?;
+    let config = state.config.try_read().ok()?;
+    let feedback = &config.feedback;
+    (feedback.play_sounds && !feedback.silent_mode).then(|| feedback.sound_volume.clamp(0.0, 1.0))
+}
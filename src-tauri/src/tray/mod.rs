@@ -1,10 +1,12 @@
 use tauri::{
     image::Image,
-    menu::{Menu, MenuItem, PredefinedMenuItem},
+    menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem},
     tray::{MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent},
-    AppHandle, Manager,
+    AppHandle, Emitter, Listener, Manager,
 };
 
+use crate::AppState;
+
 #[derive(Debug, thiserror::Error)]
 pub enum TrayError {
     #[error("Failed to setup tray: {0}")]
@@ -14,21 +16,148 @@ pub enum TrayError {
 // 嵌入图标
 const ICON_BYTES: &[u8] = include_bytes!("../../icons/icon.png");
 
-/// 设置系统托盘，返回 TrayIcon 对象（必须保持存活）
-pub fn setup_tray(app: &AppHandle) -> Result<TrayIcon, TrayError> {
-    // 创建菜单项
+/// 状态圆点颜色（macOS 模板图标只使用透明度）
+const STATUS_DOT_RGBA: [u8; 4] = [255, 59, 48, 255];
+
+/// 录音时菜单栏图标旁显示的文字（macOS）
+#[cfg(target_os = "macos")]
+const RECORDING_TITLE: &str = "🔴";
+
+/// 托盘显示的处理状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayStatus {
+    Idle,
+    Recording,
+    Processing,
+}
+
+impl TrayStatus {
+    /// 状态菜单项文本
+    fn label(&self) -> &'static str {
+        match self {
+            TrayStatus::Idle => "空闲",
+            TrayStatus::Recording => "● 录音中",
+            TrayStatus::Processing => "处理中...",
+        }
+    }
+}
+
+/// 托盘图标及状态菜单项（必须保持存活，否则点击无效）
+pub struct TrayHandle {
+    tray: TrayIcon,
+    status_item: MenuItem<tauri::Wry>,
+    idle_icon: Image<'static>,
+    recording_icon: Image<'static>,
+    processing_icon: Image<'static>,
+}
+
+/// 更新托盘的状态菜单项和图标（托盘未初始化时忽略）
+pub fn set_tray_status(app: &AppHandle, status: TrayStatus) {
+    let Some(handle) = app.try_state::<TrayHandle>() else {
+        return;
+    };
+    if let Err(e) = handle.status_item.set_text(status.label()) {
+        tracing::warn!("Failed to update tray status: {}", e);
+    }
+    let icon = match status {
+        TrayStatus::Idle => &handle.idle_icon,
+        TrayStatus::Recording => &handle.recording_icon,
+        TrayStatus::Processing => &handle.processing_icon,
+    };
+    if let Err(e) = handle.tray.set_icon(Some(icon.clone())) {
+        tracing::warn!("Failed to update tray icon: {}", e);
+    }
+    #[cfg(target_os = "macos")]
+    set_recording_title(app, &handle.tray, status);
+}
+
+/// 录音时按配置在菜单栏图标旁显示文字，其他状态（含处理出错、取消）一律清除
+#[cfg(target_os = "macos")]
+fn set_recording_title(app: &AppHandle, tray: &TrayIcon, status: TrayStatus) {
+    let show = status == TrayStatus::Recording && show_recording_title(app);
+    if let Err(e) = tray.set_title(show.then_some(RECORDING_TITLE)) {
+        tracing::warn!("Failed to update tray title: {}", e);
+    }
+}
+
+/// 是否开启了菜单栏录音文字；静音模式或配置被占用时不显示
+#[cfg(target_os = "macos")]
+fn show_recording_title(app: &AppHandle) -> bool {
+    let Some(state) = app.try_state::<AppState>() else {
+        return false;
+    };
+    let Ok(config) = state.config.try_read() else {
+        return false;
+    };
+    config.feedback.show_menubar_recording_text && !config.feedback.silent_mode
+}
+
+/// 根据录音和处理事件更新托盘状态
+fn listen_pipeline_events(app: &AppHandle) {
+    let events = [
+        ("recording-started", TrayStatus::Recording),
+        ("recording-stopped", TrayStatus::Processing),
+        ("processing-complete", TrayStatus::Idle),
+        ("processing-error", TrayStatus::Idle),
+        ("processing-cancelled", TrayStatus::Idle),
+    ];
+    for (event, status) in events {
+        let app_handle = app.clone();
+        app.listen_any(event, move |_| set_tray_status(&app_handle, status));
+    }
+}
+
+/// 设置系统托盘，返回需要保持存活的 TrayHandle
+pub fn setup_tray(
+    app: &AppHandle,
+    silent_mode: bool,
+    hotkey_enabled: bool,
+) -> Result<TrayHandle, TrayError> {
+    // 创建菜单项（状态项只用于显示，不可点击）
+    let status_item =
+        MenuItem::with_id(app, "status", TrayStatus::Idle.label(), false, None::<&str>)
+            .map_err(|e| TrayError::Setup(e.to_string()))?;
+
     let settings_item = MenuItem::with_id(app, "settings", "设置...", true, None::<&str>)
         .map_err(|e| TrayError::Setup(e.to_string()))?;
 
+    let silent_item =
+        CheckMenuItem::with_id(app, "silent_mode", "静音模式", true, silent_mode, None::<&str>)
+            .map_err(|e| TrayError::Setup(e.to_string()))?;
+
+    let hotkey_item = CheckMenuItem::with_id(
+        app,
+        "hotkey_enabled",
+        "启用快捷键",
+        true,
+        hotkey_enabled,
+        None::<&str>,
+    )
+    .map_err(|e| TrayError::Setup(e.to_string()))?;
+
     let separator = PredefinedMenuItem::separator(app)
         .map_err(|e| TrayError::Setup(e.to_string()))?;
 
+    let status_separator = PredefinedMenuItem::separator(app)
+        .map_err(|e| TrayError::Setup(e.to_string()))?;
+
     let quit_item = MenuItem::with_id(app, "quit", "退出 Vhisper", true, Some("CmdOrCtrl+Q"))
         .map_err(|e| TrayError::Setup(e.to_string()))?;
 
     // 创建菜单
-    let menu = Menu::with_items(app, &[&settings_item, &separator, &quit_item])
-        .map_err(|e| TrayError::Setup(e.to_string()))?;
+    let menu = Menu::with_items(
+        app,
+        &[
+            &status_item,
+            &status_separator,
+            &settings_item,
+            &hotkey_item,
+            &silent_item,
+            &separator,
+            &quit_item,
+        ],
+    )
+    .map_err(|e| TrayError::Setup(e.to_string()))?;
 
     // 从 PNG 解码图标
     let icon = load_icon_from_png(ICON_BYTES)
@@ -38,7 +167,7 @@ pub fn setup_tray(app: &AppHandle) -> Result<TrayIcon, TrayError> {
 
     // 创建托盘图标
     let tray = TrayIconBuilder::new()
-        .icon(icon)
+        .icon(icon.clone())
         .icon_as_template(true)  // macOS: 使用模板图标
         .menu(&menu)
         .show_menu_on_left_click(true)  // 左键点击显示菜单
@@ -55,7 +184,7 @@ pub fn setup_tray(app: &AppHandle) -> Result<TrayIcon, TrayError> {
                 // 左键点击时菜单会自动显示（因为 show_menu_on_left_click(true)）
             }
         })
-        .on_menu_event(|app, event| {
+        .on_menu_event(move |app, event| {
             tracing::info!("Menu event: {:?}", event.id);
             match event.id.as_ref() {
                 "settings" => {
@@ -66,6 +195,20 @@ pub fn setup_tray(app: &AppHandle) -> Result<TrayIcon, TrayError> {
                         let _ = window.set_focus();
                     }
                 }
+                "silent_mode" => {
+                    let app = app.clone();
+                    let silent_item = silent_item.clone();
+                    tauri::async_runtime::spawn(async move {
+                        toggle_silent_mode(&app, &silent_item).await;
+                    });
+                }
+                "hotkey_enabled" => {
+                    let app = app.clone();
+                    let hotkey_item = hotkey_item.clone();
+                    tauri::async_runtime::spawn(async move {
+                        toggle_hotkey_enabled(&app, &hotkey_item).await;
+                    });
+                }
                 "quit" => {
                     tracing::info!("Quit menu clicked");
                     app.exit(0);
@@ -77,7 +220,85 @@ pub fn setup_tray(app: &AppHandle) -> Result<TrayIcon, TrayError> {
         .map_err(|e| TrayError::Setup(e.to_string()))?;
 
     tracing::info!("System tray initialized successfully, tray id: {:?}", tray.id());
-    Ok(tray)
+    listen_pipeline_events(app);
+
+    Ok(TrayHandle {
+        tray,
+        status_item,
+        recording_icon: with_status_dot(&icon, false),
+        processing_icon: with_status_dot(&icon, true),
+        idle_icon: icon,
+    })
+}
+
+/// 切换静音模式并保存配置
+///
+/// 保存失败时恢复菜单勾选状态，内存中的配置保持不变
+async fn toggle_silent_mode(app: &AppHandle, silent_item: &CheckMenuItem<tauri::Wry>) {
+    let state = app.state::<AppState>();
+    let mut config = state.config.write().await;
+
+    let mut new_config = config.clone();
+    new_config.feedback.silent_mode = !config.feedback.silent_mode;
+    let silent_mode = new_config.feedback.silent_mode;
+
+    if let Err(e) = vhisper_core::save_config(&new_config) {
+        tracing::error!("Failed to save silent mode: {}", e);
+        let _ = silent_item.set_checked(config.feedback.silent_mode);
+        return;
+    }
+
+    *config = new_config;
+    let _ = silent_item.set_checked(silent_mode);
+    let _ = app.emit("silent-mode-changed", silent_mode);
+    tracing::info!("Silent mode: {}", silent_mode);
+}
+
+/// 启用或暂停快捷键并保存配置
+///
+/// 监听器保持运行，只切换是否响应按键；保存失败时恢复菜单勾选状态
+async fn toggle_hotkey_enabled(app: &AppHandle, hotkey_item: &CheckMenuItem<tauri::Wry>) {
+    let state = app.state::<AppState>();
+    let mut config = state.config.write().await;
+
+    let mut new_config = config.clone();
+    new_config.hotkey.enabled = !config.hotkey.enabled;
+    let enabled = new_config.hotkey.enabled;
+
+    if let Err(e) = vhisper_core::save_config(&new_config) {
+        tracing::error!("Failed to save hotkey enabled state: {}", e);
+        let _ = hotkey_item.set_checked(config.hotkey.enabled);
+        return;
+    }
+
+    *config = new_config;
+    crate::hotkey::set_hotkeys_enabled(enabled);
+    let _ = hotkey_item.set_checked(enabled);
+    let _ = app.emit("hotkey-enabled-changed", enabled);
+}
+
+/// 在图标右下角画状态圆点，`ring` 为 true 时画空心圆环（处理中）
+fn with_status_dot(icon: &Image<'_>, ring: bool) -> Image<'static> {
+    let (width, height) = (icon.width(), icon.height());
+    let mut rgba = icon.rgba().to_vec();
+
+    let radius = width.min(height) as f32 * 0.22;
+    let inner_radius = if ring { radius * 0.55 } else { 0.0 };
+    let (center_x, center_y) = (width as f32 - radius, height as f32 - radius);
+
+    for y in 0..height {
+        for x in 0..width {
+            let dx = x as f32 + 0.5 - center_x;
+            let dy = y as f32 + 0.5 - center_y;
+            let distance = (dx * dx + dy * dy).sqrt();
+            if distance <= radius && distance >= inner_radius {
+                let offset = ((y * width + x) * 4) as usize;
+                rgba[offset..offset + 4].copy_from_slice(&STATUS_DOT_RGBA);
+            }
+        }
+    }
+
+    Image::new_owned(rgba, width, height)
 }
 
 /// 从 PNG 数据加载图标
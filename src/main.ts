@@ -1,5 +1,9 @@
 import { createApp } from 'vue';
 import App from './App.vue';
+import Overlay from './Overlay.vue';
 import './app.css';
 
-createApp(App).mount('#app');
+// 悬浮窗与主窗口共用同一入口，通过 hash 区分
+const root = window.location.hash === '#overlay' ? Overlay : App;
+
+createApp(root).mount('#app');